@@ -0,0 +1,859 @@
+use crate::{Connection, ResponseCache};
+use futures_channel::oneshot;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_client_wasm::{
+    solana_sdk::{
+        account::Account,
+        clock::Slot,
+        commitment_config::{CommitmentConfig, CommitmentLevel},
+        epoch_info::EpochInfo,
+        hash::Hash,
+        message::Message,
+        pubkey::Pubkey,
+        signature::Signature,
+        transaction::{Transaction, TransactionError, VersionedTransaction},
+    },
+    utils::{
+        rpc_config::{
+            serialize_and_encode, GetConfirmedSignaturesForAddress2Config, RpcAccountInfoConfig,
+            RpcKeyedAccount, RpcProgramAccountsConfig, RpcSendTransactionConfig,
+            RpcSignaturesForAddressConfig, RpcTokenAccountsFilter, RpcTransactionConfig,
+        },
+        rpc_filter::TokenAccountsFilter,
+        rpc_response::{RpcBlockhash, RpcConfirmedTransactionStatusWithSignature, RpcPerfSample},
+    },
+    ClientError, ClientRequest, ClientResult,
+};
+use solana_extra_wasm::{
+    account_decoder::{parse_token::UiTokenAmount, UiAccount, UiAccountEncoding},
+    transaction_status::{
+        EncodedConfirmedTransactionWithStatusMeta, TransactionConfirmationStatus,
+        UiTransactionEncoding,
+    },
+};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+/// How long calls are collected before they are sent together, in milliseconds.
+const BATCH_WINDOW_MS: u32 = 10;
+
+/// The most calls sent in a single batch, RPC providers reject larger ones.
+const MAX_BATCH_SIZE: usize = 100;
+
+/// How many times, and how often, `confirm_transaction` polls the status of a transaction.
+const CONFIRM_RETRIES: usize = 5;
+const CONFIRM_INTERVAL_MS: u32 = 2_000;
+
+/// The result of a call as handed to its callers, errors are serialized since they can't be
+/// cloned.
+type CallResult = Result<Value, Value>;
+
+struct PendingCall {
+    key: String,
+    method: String,
+    params: Value,
+}
+
+/// The calls of a connection waiting for their batch to be sent, or for its response.
+#[derive(Default)]
+pub(crate) struct BatchQueue {
+    pending: Vec<PendingCall>,
+    /// The callers of every queued or in-flight call, by method and params.
+    waiters: HashMap<String, Vec<oneshot::Sender<CallResult>>>,
+    scheduled: bool,
+}
+
+impl BatchQueue {
+    /// Queues a call unless an identical one is queued or in flight, returns whether a flush
+    /// has to be scheduled.
+    fn push(&mut self, call: PendingCall, waiter: oneshot::Sender<CallResult>) -> bool {
+        if let Some(waiters) = self.waiters.get_mut(&call.key) {
+            waiters.push(waiter);
+            return false;
+        }
+        self.waiters.insert(call.key.clone(), vec![waiter]);
+        self.pending.push(call);
+        !std::mem::replace(&mut self.scheduled, true)
+    }
+
+    /// Takes the queued calls, split in batches of at most [`MAX_BATCH_SIZE`] calls.
+    fn take_batches(&mut self) -> Vec<Vec<PendingCall>> {
+        self.scheduled = false;
+        let mut pending = std::mem::take(&mut self.pending).into_iter().peekable();
+        let mut batches = vec![];
+        while pending.peek().is_some() {
+            batches.push(pending.by_ref().take(MAX_BATCH_SIZE).collect());
+        }
+        batches
+    }
+
+    /// Takes the callers of a call, once its result is in.
+    fn take_waiters(&mut self, key: &str) -> Vec<oneshot::Sender<CallResult>> {
+        self.waiters.remove(key).unwrap_or_default()
+    }
+}
+
+/// The `value` of the responses carrying a context.
+#[derive(Deserialize)]
+pub(crate) struct WithContext<T> {
+    pub value: T,
+}
+
+/// The status of a transaction, as returned by `getSignatureStatuses`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureStatus {
+    pub slot: Slot,
+    /// The number of blocks since the transaction was confirmed, `None` once it is finalized.
+    pub confirmations: Option<usize>,
+    pub err: Option<TransactionError>,
+    pub confirmation_status: Option<TransactionConfirmationStatus>,
+}
+
+impl SignatureStatus {
+    pub fn satisfies_commitment(&self, commitment_config: CommitmentConfig) -> bool {
+        match (&self.confirmation_status, commitment_config.commitment) {
+            (Some(TransactionConfirmationStatus::Finalized), _) => true,
+            (Some(TransactionConfirmationStatus::Confirmed), CommitmentLevel::Finalized) => false,
+            (Some(TransactionConfirmationStatus::Confirmed), _) => true,
+            (Some(TransactionConfirmationStatus::Processed), level) => {
+                level == CommitmentLevel::Processed
+            }
+            (None, _) => false,
+        }
+    }
+}
+
+/// A client with the typed API of [`solana_client_wasm::WasmClient`], requests go through
+/// [`Connection::send_batch`].
+///
+/// Calls made within a few milliseconds of each other are sent in a single JSON-RPC batch, and
+/// an identical call already queued or in flight is awaited rather than sent again. Calls are
+/// answered from the [`crate::ResponseCache`] of the connection when it has one.
+///
+/// Get one with [`Connection::rpc`].
+#[derive(Clone)]
+pub struct BatchClient {
+    connection: Connection,
+    queue: Rc<RefCell<BatchQueue>>,
+}
+
+impl BatchClient {
+    pub(crate) fn new(connection: Connection, queue: Rc<RefCell<BatchQueue>>) -> Self {
+        Self { connection, queue }
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    pub fn commitment(&self) -> CommitmentLevel {
+        self.connection.commitment().commitment
+    }
+
+    pub fn commitment_config(&self) -> CommitmentConfig {
+        self.connection.commitment()
+    }
+
+    /// Queues a JSON-RPC call, deserializing its result.
+    ///
+    /// Reads are answered from the cache of the connection, when it has one.
+    pub async fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> ClientResult<T> {
+        let result = match self.connection.cache() {
+            Some(cache) if ResponseCache::caches(method) => {
+                // Keyed by cluster, as the cache may outlive the connection
+                let key = format!("{}:{}{}", self.get_genesis_hash().await?, method, params);
+                match cache.get(&key) {
+                    Some(result) => result,
+                    None => {
+                        let result = self.queue(method, params.clone()).await?;
+                        cache.insert(&key, method, &params, &result);
+                        result
+                    }
+                }
+            }
+            _ => self.queue(method, params).await?,
+        };
+        serde_json::from_value(result).map_err(|e| ClientError::new(&e.to_string()))
+    }
+
+    /// Queues a JSON-RPC call, deduplicated with the identical ones waiting for a response.
+    async fn queue(&self, method: &str, params: Value) -> ClientResult<Value> {
+        let key = format!("{}{}", method, params);
+        let (sender, receiver) = oneshot::channel();
+        let call = PendingCall {
+            key,
+            method: method.to_string(),
+            params,
+        };
+        let schedule = self.queue.borrow_mut().push(call, sender);
+
+        if schedule {
+            let client = self.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                gloo_timers::future::TimeoutFuture::new(BATCH_WINDOW_MS).await;
+                client.flush().await;
+            });
+        }
+
+        match receiver.await {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(error)) => {
+                Err(serde_json::from_value(error)
+                    .unwrap_or_else(|e| ClientError::new(&e.to_string())))
+            }
+            Err(_) => Err(ClientError::new("Request cancelled.")),
+        }
+    }
+
+    /// Sends the queued calls and hands the results to their callers.
+    async fn flush(&self) {
+        let batches = self.queue.borrow_mut().take_batches();
+
+        let batches = batches.iter().map(|calls| async move {
+            let requests: Vec<ClientRequest> = calls
+                .iter()
+                .map(|call| {
+                    ClientRequest::new(&call.method)
+                        .params(call.params.clone())
+                        .clone()
+                })
+                .collect();
+
+            // A lone call is not worth a batch
+            let results: Vec<CallResult> = if requests.len() == 1 {
+                vec![self.connection.send(&requests[0]).await.map(|r| r.result)]
+            } else {
+                match self.connection.send_batch(&requests).await {
+                    Ok(responses) => responses.into_iter().map(|r| r.map(|r| r.result)).collect(),
+                    Err(e) => {
+                        let error = to_value(&e);
+                        return self.resolve(calls, |_| Err(error.clone()));
+                    }
+                }
+            }
+            .into_iter()
+            .map(|r| r.map_err(|e| to_value(&e)))
+            .collect();
+
+            let mut results = results.into_iter();
+            self.resolve(calls, |_| results.next().unwrap_or(Ok(Value::Null)));
+        });
+        futures::future::join_all(batches).await;
+    }
+
+    fn resolve(&self, calls: &[PendingCall], mut result: impl FnMut(&PendingCall) -> CallResult) {
+        for call in calls {
+            let result = result(call);
+            let waiters = self.queue.borrow_mut().take_waiters(&call.key);
+            for waiter in waiters {
+                let _ = waiter.send(result.clone());
+            }
+        }
+    }
+
+    pub async fn get_balance_with_commitment(
+        &self,
+        pubkey: &Pubkey,
+        commitment_config: CommitmentConfig,
+    ) -> ClientResult<u64> {
+        let response: WithContext<u64> = self
+            .call("getBalance", json!([pubkey.to_string(), commitment_config]))
+            .await?;
+        Ok(response.value)
+    }
+
+    pub async fn get_balance(&self, pubkey: &Pubkey) -> ClientResult<u64> {
+        self.get_balance_with_commitment(pubkey, self.commitment_config())
+            .await
+    }
+
+    pub async fn get_account_with_config(
+        &self,
+        pubkey: &Pubkey,
+        config: RpcAccountInfoConfig,
+    ) -> ClientResult<Option<Account>> {
+        let response: WithContext<Option<UiAccount>> = self
+            .call("getAccountInfo", json!([pubkey.to_string(), config]))
+            .await?;
+        Ok(response.value.and_then(|account| account.decode()))
+    }
+
+    pub async fn get_account_with_commitment(
+        &self,
+        pubkey: &Pubkey,
+        commitment_config: CommitmentConfig,
+    ) -> ClientResult<Option<Account>> {
+        self.get_account_with_config(
+            pubkey,
+            RpcAccountInfoConfig {
+                commitment: Some(commitment_config),
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    pub async fn get_account(&self, pubkey: &Pubkey) -> ClientResult<Account> {
+        self.get_account_with_commitment(pubkey, self.commitment_config())
+            .await?
+            .ok_or_else(|| ClientError::new(&format!("Account {} not found.", pubkey)))
+    }
+
+    pub async fn get_account_data(&self, pubkey: &Pubkey) -> ClientResult<Vec<u8>> {
+        Ok(self.get_account(pubkey).await?.data)
+    }
+
+    /// Unlike [`solana_client_wasm::WasmClient`], missing accounts are kept as `None` so the
+    /// accounts line up with the pubkeys.
+    pub async fn get_multiple_accounts_with_config(
+        &self,
+        pubkeys: &[Pubkey],
+        config: RpcAccountInfoConfig,
+    ) -> ClientResult<Vec<Option<Account>>> {
+        let pubkeys: Vec<String> = pubkeys.iter().map(|p| p.to_string()).collect();
+        let response: WithContext<Vec<Option<UiAccount>>> = self
+            .call("getMultipleAccounts", json!([pubkeys, config]))
+            .await?;
+        Ok(response
+            .value
+            .into_iter()
+            .map(|account| account.and_then(|account| account.decode()))
+            .collect())
+    }
+
+    pub async fn get_multiple_accounts_with_commitment(
+        &self,
+        pubkeys: &[Pubkey],
+        commitment_config: CommitmentConfig,
+    ) -> ClientResult<Vec<Option<Account>>> {
+        self.get_multiple_accounts_with_config(
+            pubkeys,
+            RpcAccountInfoConfig {
+                commitment: Some(commitment_config),
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    pub async fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> ClientResult<Vec<Option<Account>>> {
+        self.get_multiple_accounts_with_commitment(pubkeys, self.commitment_config())
+            .await
+    }
+
+    pub async fn get_slot_with_commitment(
+        &self,
+        commitment_config: CommitmentConfig,
+    ) -> ClientResult<Slot> {
+        self.call("getSlot", json!([commitment_config])).await
+    }
+
+    pub async fn get_slot(&self) -> ClientResult<Slot> {
+        self.get_slot_with_commitment(self.commitment_config())
+            .await
+    }
+
+    pub async fn get_block_height_with_commitment(
+        &self,
+        commitment_config: CommitmentConfig,
+    ) -> ClientResult<u64> {
+        self.call("getBlockHeight", json!([commitment_config]))
+            .await
+    }
+
+    pub async fn get_block_height(&self) -> ClientResult<u64> {
+        self.get_block_height_with_commitment(self.commitment_config())
+            .await
+    }
+
+    pub async fn get_epoch_info_with_commitment(
+        &self,
+        commitment_config: CommitmentConfig,
+    ) -> ClientResult<EpochInfo> {
+        self.call("getEpochInfo", json!([commitment_config])).await
+    }
+
+    pub async fn get_epoch_info(&self) -> ClientResult<EpochInfo> {
+        self.get_epoch_info_with_commitment(self.commitment_config())
+            .await
+    }
+
+    /// The genesis hash of the cluster, fetched once per connection.
+    pub async fn get_genesis_hash(&self) -> ClientResult<Hash> {
+        if let Some(hash) = *self.connection.genesis_hash.borrow() {
+            return Ok(hash);
+        }
+
+        // Queued directly, `call` needs the genesis hash of cached reads
+        let hash: String = serde_json::from_value(self.queue("getGenesisHash", json!([])).await?)
+            .map_err(|e| ClientError::new(&e.to_string()))?;
+        let hash: Hash = hash
+            .parse()
+            .map_err(|_| ClientError::new("Hash not parsable."))?;
+        *self.connection.genesis_hash.borrow_mut() = Some(hash);
+        Ok(hash)
+    }
+
+    pub async fn get_latest_blockhash_with_config(
+        &self,
+        commitment_config: CommitmentConfig,
+    ) -> ClientResult<(Hash, u64)> {
+        let response: WithContext<RpcBlockhash> = self
+            .call("getLatestBlockhash", json!([commitment_config]))
+            .await?;
+        let hash = response
+            .value
+            .blockhash
+            .parse()
+            .map_err(|_| ClientError::new("Hash not parsable."))?;
+
+        Ok((hash, response.value.last_valid_block_height))
+    }
+
+    pub async fn get_latest_blockhash_with_commitment(
+        &self,
+        commitment_config: CommitmentConfig,
+    ) -> ClientResult<(Hash, u64)> {
+        self.get_latest_blockhash_with_config(commitment_config)
+            .await
+    }
+
+    pub async fn get_latest_blockhash(&self) -> ClientResult<Hash> {
+        let result = self
+            .get_latest_blockhash_with_commitment(self.commitment_config())
+            .await?;
+
+        Ok(result.0)
+    }
+
+    pub async fn is_blockhash_valid(
+        &self,
+        blockhash: &Hash,
+        commitment_config: CommitmentConfig,
+    ) -> ClientResult<bool> {
+        let response: WithContext<bool> = self
+            .call(
+                "isBlockhashValid",
+                json!([blockhash.to_string(), commitment_config]),
+            )
+            .await?;
+        Ok(response.value)
+    }
+
+    pub async fn get_fee_for_message_with_commitment(
+        &self,
+        message: &Message,
+        commitment_config: CommitmentConfig,
+    ) -> ClientResult<u64> {
+        let message = serialize_and_encode(message, UiTransactionEncoding::Base64)?;
+        let response: WithContext<Option<u64>> = self
+            .call("getFeeForMessage", json!([message, commitment_config]))
+            .await?;
+        response
+            .value
+            .ok_or_else(|| ClientError::new("Blockhash of the message not found."))
+    }
+
+    pub async fn get_fee_for_message(&self, message: &Message) -> ClientResult<u64> {
+        self.get_fee_for_message_with_commitment(message, self.commitment_config())
+            .await
+    }
+
+    pub async fn get_minimum_balance_for_rent_exemption(
+        &self,
+        data_len: usize,
+    ) -> ClientResult<u64> {
+        self.call("getMinimumBalanceForRentExemption", json!([data_len]))
+            .await
+    }
+
+    pub async fn get_recent_performance_samples(
+        &self,
+        limit: Option<usize>,
+    ) -> ClientResult<Vec<RpcPerfSample>> {
+        self.call("getRecentPerformanceSamples", json!([limit]))
+            .await
+    }
+
+    pub async fn get_signatures_for_address_with_config(
+        &self,
+        address: &Pubkey,
+        config: GetConfirmedSignaturesForAddress2Config,
+    ) -> ClientResult<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        let config = RpcSignaturesForAddressConfig {
+            before: config.before.map(|signature| signature.to_string()),
+            until: config.until.map(|signature| signature.to_string()),
+            limit: config.limit,
+            commitment: config.commitment,
+            min_context_slot: None,
+        };
+
+        self.call(
+            "getSignaturesForAddress",
+            json!([address.to_string(), config]),
+        )
+        .await
+    }
+
+    pub async fn get_transaction_with_config(
+        &self,
+        signature: &Signature,
+        config: RpcTransactionConfig,
+    ) -> ClientResult<EncodedConfirmedTransactionWithStatusMeta> {
+        let response: Option<EncodedConfirmedTransactionWithStatusMeta> = self
+            .call("getTransaction", json!([signature.to_string(), config]))
+            .await?;
+
+        response.ok_or_else(|| ClientError::new(&format!("Signature {signature} not found.")))
+    }
+
+    pub async fn get_token_accounts_by_owner_with_commitment(
+        &self,
+        owner: &Pubkey,
+        token_account_filter: TokenAccountsFilter,
+        commitment_config: CommitmentConfig,
+    ) -> ClientResult<Vec<RpcKeyedAccount>> {
+        let token_account_filter = match token_account_filter {
+            TokenAccountsFilter::Mint(mint) => RpcTokenAccountsFilter::Mint(mint.to_string()),
+            TokenAccountsFilter::ProgramId(program_id) => {
+                RpcTokenAccountsFilter::ProgramId(program_id.to_string())
+            }
+        };
+
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::JsonParsed),
+            commitment: Some(commitment_config),
+            data_slice: None,
+            min_context_slot: None,
+        };
+
+        let response: WithContext<Vec<RpcKeyedAccount>> = self
+            .call(
+                "getTokenAccountsByOwner",
+                json!([owner.to_string(), token_account_filter, config]),
+            )
+            .await?;
+        Ok(response.value)
+    }
+
+    pub async fn get_token_accounts_by_owner(
+        &self,
+        owner: &Pubkey,
+        token_account_filter: TokenAccountsFilter,
+    ) -> ClientResult<Vec<RpcKeyedAccount>> {
+        self.get_token_accounts_by_owner_with_commitment(
+            owner,
+            token_account_filter,
+            self.commitment_config(),
+        )
+        .await
+    }
+
+    pub async fn get_token_account_balance_with_commitment(
+        &self,
+        pubkey: &Pubkey,
+        commitment_config: CommitmentConfig,
+    ) -> ClientResult<UiTokenAmount> {
+        let response: WithContext<UiTokenAmount> = self
+            .call(
+                "getTokenAccountBalance",
+                json!([pubkey.to_string(), commitment_config]),
+            )
+            .await?;
+        Ok(response.value)
+    }
+
+    pub async fn get_token_account_balance(&self, pubkey: &Pubkey) -> ClientResult<UiTokenAmount> {
+        self.get_token_account_balance_with_commitment(pubkey, self.commitment_config())
+            .await
+    }
+
+    pub async fn get_program_accounts_with_config(
+        &self,
+        pubkey: &Pubkey,
+        config: RpcProgramAccountsConfig,
+    ) -> ClientResult<Vec<(Pubkey, Account)>> {
+        // Responses are expected without their context
+        let config = RpcProgramAccountsConfig {
+            account_config: RpcAccountInfoConfig {
+                commitment: config
+                    .account_config
+                    .commitment
+                    .or(Some(self.commitment_config())),
+                ..config.account_config
+            },
+            with_context: None,
+            ..config
+        };
+        let accounts: Vec<RpcKeyedAccount> = self
+            .call("getProgramAccounts", json!([pubkey.to_string(), config]))
+            .await?;
+
+        accounts
+            .into_iter()
+            .map(|RpcKeyedAccount { pubkey, account }| {
+                let pubkey = pubkey
+                    .parse()
+                    .map_err(|_| ClientError::new(&format!("{pubkey} is not a valid pubkey.")))?;
+                let account = account
+                    .decode()
+                    .ok_or_else(|| ClientError::new(&format!("Unable to decode {pubkey}")))?;
+                Ok((pubkey, account))
+            })
+            .collect()
+    }
+
+    pub async fn get_program_accounts(
+        &self,
+        pubkey: &Pubkey,
+    ) -> ClientResult<Vec<(Pubkey, Account)>> {
+        self.get_program_accounts_with_config(
+            pubkey,
+            RpcProgramAccountsConfig {
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    pub async fn send_transaction_with_config(
+        &self,
+        transaction: &Transaction,
+        config: RpcSendTransactionConfig,
+    ) -> ClientResult<Signature> {
+        self.send_encoded_transaction(
+            transaction,
+            transaction.signatures.first(),
+            &transaction.message.account_keys,
+            config,
+        )
+        .await
+    }
+
+    pub async fn send_transaction(&self, transaction: &Transaction) -> ClientResult<Signature> {
+        self.send_transaction_with_config(transaction, self.send_config())
+            .await
+    }
+
+    pub async fn send_versioned_transaction_with_config(
+        &self,
+        transaction: &VersionedTransaction,
+        config: RpcSendTransactionConfig,
+    ) -> ClientResult<Signature> {
+        self.send_encoded_transaction(
+            transaction,
+            transaction.signatures.first(),
+            transaction.message.static_account_keys(),
+            config,
+        )
+        .await
+    }
+
+    pub async fn send_versioned_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+    ) -> ClientResult<Signature> {
+        self.send_versioned_transaction_with_config(transaction, self.send_config())
+            .await
+    }
+
+    fn send_config(&self) -> RpcSendTransactionConfig {
+        RpcSendTransactionConfig {
+            preflight_commitment: Some(self.commitment()),
+            encoding: Some(UiTransactionEncoding::Base64),
+            ..Default::default()
+        }
+    }
+
+    async fn send_encoded_transaction<T: Serialize>(
+        &self,
+        transaction: &T,
+        expected: Option<&Signature>,
+        accounts: &[Pubkey],
+        config: RpcSendTransactionConfig,
+    ) -> ClientResult<Signature> {
+        let encoding = config.encoding.unwrap_or(UiTransactionEncoding::Base64);
+        let transaction = serialize_and_encode(transaction, encoding)?;
+        let signature: String = self
+            .call("sendTransaction", json!([transaction, config]))
+            .await?;
+
+        // The cached reads of the accounts are about to be outdated
+        if let Some(cache) = self.connection.cache() {
+            for account in accounts {
+                cache.invalidate(account);
+            }
+        }
+        let signature: Signature = signature
+            .parse()
+            .map_err(|_| ClientError::new("Signature not parsable."))?;
+
+        // A mismatching signature means the RPC node misbehaved, the transaction may or may not
+        // have been sent
+        match expected {
+            Some(expected) if *expected != signature => Err(ClientError::new(&format!(
+                "RPC node returned mismatched signature {:?}, expected {:?}",
+                signature, expected
+            ))),
+            _ => Ok(signature),
+        }
+    }
+
+    pub async fn get_signature_statuses(
+        &self,
+        signatures: &[Signature],
+    ) -> ClientResult<Vec<Option<SignatureStatus>>> {
+        let signatures: Vec<String> = signatures.iter().map(|s| s.to_string()).collect();
+        let response: WithContext<Vec<Option<SignatureStatus>>> = self
+            .call("getSignatureStatuses", json!([signatures]))
+            .await?;
+        Ok(response.value)
+    }
+
+    /// Polls the status of the transaction until it reaches the commitment, returns whether it
+    /// succeeded.
+    pub async fn confirm_transaction_with_commitment(
+        &self,
+        signature: &Signature,
+        commitment_config: CommitmentConfig,
+    ) -> ClientResult<bool> {
+        for _ in 0..CONFIRM_RETRIES {
+            let statuses = self.get_signature_statuses(&[*signature]).await?;
+            if let Some(status) = statuses.into_iter().flatten().next() {
+                if status.satisfies_commitment(commitment_config) {
+                    return Ok(status.err.is_none());
+                }
+            }
+            gloo_timers::future::TimeoutFuture::new(CONFIRM_INTERVAL_MS).await;
+        }
+
+        Ok(false)
+    }
+
+    pub async fn confirm_transaction(&self, signature: &Signature) -> ClientResult<bool> {
+        self.confirm_transaction_with_commitment(signature, self.commitment_config())
+            .await
+    }
+
+    pub async fn request_airdrop(&self, pubkey: &Pubkey, lamports: u64) -> ClientResult<Signature> {
+        let signature: String = self
+            .call("requestAirdrop", json!([pubkey.to_string(), lamports]))
+            .await?;
+        signature
+            .parse()
+            .map_err(|_| ClientError::new("Signature not parsable."))
+    }
+}
+
+fn to_value(error: &ClientError) -> Value {
+    serde_json::to_value(error).unwrap_or_else(|_| Value::String(error.to_string()))
+}
+
+impl std::fmt::Debug for BatchClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BatchClient")
+            .field("connection", &self.connection)
+            .field("pending", &self.queue.borrow().pending.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(key: &str) -> PendingCall {
+        PendingCall {
+            key: key.to_string(),
+            method: "getSlot".to_string(),
+            params: json!([]),
+        }
+    }
+
+    #[test]
+    fn deduplicates_identical_calls() {
+        let mut queue = BatchQueue::default();
+        let (first, mut first_receiver) = oneshot::channel();
+        let (second, mut second_receiver) = oneshot::channel();
+        let (other, _other_receiver) = oneshot::channel();
+
+        assert!(queue.push(call("getSlot[]"), first));
+        assert!(!queue.push(call("getSlot[]"), second));
+        assert!(!queue.push(call("getBlockHeight[]"), other));
+
+        let batches = queue.take_batches();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].len(), 2);
+
+        for waiter in queue.take_waiters("getSlot[]") {
+            waiter.send(Ok(json!(42))).unwrap();
+        }
+        assert_eq!(first_receiver.try_recv(), Ok(Some(Ok(json!(42)))));
+        assert_eq!(second_receiver.try_recv(), Ok(Some(Ok(json!(42)))));
+        assert!(queue.take_waiters("getSlot[]").is_empty());
+    }
+
+    #[test]
+    fn deduplicates_calls_in_flight() {
+        let mut queue = BatchQueue::default();
+        let (first, _first_receiver) = oneshot::channel();
+        let (second, _second_receiver) = oneshot::channel();
+
+        assert!(queue.push(call("getSlot[]"), first));
+        assert_eq!(queue.take_batches().len(), 1);
+
+        // The first call is sent but not answered yet
+        assert!(!queue.push(call("getSlot[]"), second));
+        assert!(queue.take_batches().is_empty());
+        assert_eq!(queue.take_waiters("getSlot[]").len(), 2);
+    }
+
+    #[test]
+    fn splits_batches_at_max_size() {
+        let mut queue = BatchQueue::default();
+        let mut receivers = vec![];
+        for i in 0..MAX_BATCH_SIZE * 2 + 1 {
+            let (sender, receiver) = oneshot::channel();
+            receivers.push(receiver);
+            queue.push(call(&format!("getSlot[{}]", i)), sender);
+        }
+
+        let sizes: Vec<usize> = queue.take_batches().iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![MAX_BATCH_SIZE, MAX_BATCH_SIZE, 1]);
+        assert!(queue.pending.is_empty());
+
+        // A flush has to be scheduled again for the next call
+        let (sender, _receiver) = oneshot::channel();
+        assert!(queue.push(call("getSlot[next]"), sender));
+    }
+
+    #[test]
+    fn checks_status_commitment() {
+        let status = |confirmation_status| SignatureStatus {
+            slot: 0,
+            confirmations: None,
+            err: None,
+            confirmation_status,
+        };
+        let processed = status(Some(TransactionConfirmationStatus::Processed));
+        let confirmed = status(Some(TransactionConfirmationStatus::Confirmed));
+        let finalized = status(Some(TransactionConfirmationStatus::Finalized));
+
+        assert!(processed.satisfies_commitment(CommitmentConfig::processed()));
+        assert!(!processed.satisfies_commitment(CommitmentConfig::confirmed()));
+        assert!(confirmed.satisfies_commitment(CommitmentConfig::confirmed()));
+        assert!(!confirmed.satisfies_commitment(CommitmentConfig::finalized()));
+        assert!(finalized.satisfies_commitment(CommitmentConfig::finalized()));
+        assert!(!status(None).satisfies_commitment(CommitmentConfig::processed()));
+    }
+}
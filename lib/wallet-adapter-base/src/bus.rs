@@ -0,0 +1,97 @@
+use crate::Event;
+use futures::Stream;
+use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use std::{
+    pin::Pin,
+    sync::{Arc, Mutex, Weak},
+    task::{Context, Poll},
+};
+
+#[derive(Default)]
+struct Subscribers {
+    next_id: usize,
+    senders: Vec<(usize, UnboundedSender<Event>)>,
+}
+
+/// A broadcast bus for wallet events.
+///
+/// Every subscriber receives its own copy of each event emitted after it subscribed.
+/// Clones share the same subscribers.
+#[derive(Clone, Default)]
+pub struct EventBus {
+    subscribers: Arc<Mutex<Subscribers>>,
+}
+
+impl std::fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventBus")
+            .field("subscribers", &self.subscriber_count())
+            .finish()
+    }
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sends the event to every subscriber.
+    pub fn emit(&self, event: Event) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers
+            .senders
+            .retain(|(_, sender)| sender.unbounded_send(event.clone()).is_ok());
+    }
+
+    /// Returns a stream of every event emitted from now on.
+    pub fn subscribe(&self) -> EventSubscription {
+        let (sender, receiver) = unbounded();
+        let mut subscribers = self.subscribers.lock().unwrap();
+        let id = subscribers.next_id;
+        subscribers.next_id += 1;
+        subscribers.senders.push((id, sender));
+
+        EventSubscription {
+            id,
+            receiver,
+            subscribers: Arc::downgrade(&self.subscribers),
+        }
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.lock().unwrap().senders.len()
+    }
+}
+
+/// A subscription to an [`EventBus`], unregistered when dropped.
+pub struct EventSubscription {
+    id: usize,
+    receiver: UnboundedReceiver<Event>,
+    subscribers: Weak<Mutex<Subscribers>>,
+}
+
+impl std::fmt::Debug for EventSubscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventSubscription")
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+impl Stream for EventSubscription {
+    type Item = Event;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+impl Drop for EventSubscription {
+    fn drop(&mut self) {
+        if let Some(subscribers) = self.subscribers.upgrade() {
+            if let Ok(mut subscribers) = subscribers.lock() {
+                subscribers.senders.retain(|(id, _)| *id != self.id);
+            }
+        }
+    }
+}
@@ -0,0 +1,419 @@
+use crate::now;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_client_wasm::solana_sdk::{commitment_config::CommitmentLevel, pubkey::Pubkey};
+use std::{cell::RefCell, collections::HashMap, rc::Rc, time::Duration};
+
+/// How long a persisted cache waits before saving, so a burst of responses is saved once.
+const SAVE_DELAY_MS: u32 = 1_000;
+
+/// The reads cached for [`CacheConfig::ttl`], the ones which change as the chain moves on.
+const TTL_METHODS: &[&str] = &[
+    "getAccountInfo",
+    "getBalance",
+    "getMultipleAccounts",
+    "getSignaturesForAddress",
+    "getTokenAccountsByOwner",
+    "getTokenAccountBalance",
+];
+
+/// How a [`ResponseCache`] keeps responses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheConfig {
+    /// How long accounts, balances and signature lists are kept.
+    pub ttl: Duration,
+    /// The most responses kept, the least recently used ones are evicted first.
+    pub max_entries: usize,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(30),
+            max_entries: 500,
+        }
+    }
+}
+
+impl CacheConfig {
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+}
+
+/// A cached JSON-RPC result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedResponse {
+    /// The genesis hash of the cluster, the method and params of the request.
+    pub key: String,
+    pub result: Value,
+    /// When the response expires, in milliseconds since the epoch, `None` for immutable data.
+    pub expires_at: Option<u64>,
+}
+
+/// Where a [`ResponseCache`] persists the immutable responses, e.g. the local storage.
+pub trait CacheStorage {
+    fn load(&self) -> Vec<CachedResponse>;
+
+    fn save(&self, responses: &[CachedResponse]);
+}
+
+struct CacheEntry {
+    response: CachedResponse,
+    /// Mentioned addresses, for [`ResponseCache::invalidate`].
+    params: Value,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    clock: u64,
+    save_scheduled: bool,
+}
+
+/// An opt-in cache of the RPC reads made through [`crate::BatchClient`].
+///
+/// Confirmed transactions are kept for good, accounts, balances and signature lists for the
+/// configured TTL. Entries are kept by commitment, reads at the `processed` commitment are never
+/// cached, and a `finalized` read is not answered with a `confirmed` response.
+///
+/// Entries are keyed by the genesis hash of their cluster, so a cache shared by the connections
+/// to different clusters, or persisted across them, never answers with another cluster's data.
+///
+/// Clones share the same entries.
+#[derive(Clone, Default)]
+pub struct ResponseCache {
+    config: CacheConfig,
+    state: Rc<RefCell<CacheState>>,
+    storage: Option<Rc<dyn CacheStorage>>,
+}
+
+impl ResponseCache {
+    pub fn new(config: CacheConfig) -> Self {
+        Self {
+            config,
+            state: Default::default(),
+            storage: None,
+        }
+    }
+
+    /// Persists the immutable responses, the ones already stored are loaded.
+    pub fn with_storage<S: CacheStorage + 'static>(mut self, storage: S) -> Self {
+        let now = now_millis();
+        for response in storage.load() {
+            if !is_expired(&response, now) {
+                self.insert_response(response, Value::Null, now);
+            }
+        }
+        self.storage = Some(Rc::new(storage));
+        self
+    }
+
+    pub fn config(&self) -> &CacheConfig {
+        &self.config
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.borrow().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the results of the method are ever cached.
+    pub fn caches(method: &str) -> bool {
+        method == "getTransaction" || TTL_METHODS.contains(&method)
+    }
+
+    /// The cached result of a request, unless it expired.
+    pub fn get(&self, key: &str) -> Option<Value> {
+        self.get_at(key, now_millis())
+    }
+
+    fn get_at(&self, key: &str, now: u64) -> Option<Value> {
+        let mut state = self.state.borrow_mut();
+        state.clock += 1;
+        let clock = state.clock;
+
+        let entry = state.entries.get_mut(key)?;
+        if is_expired(&entry.response, now) {
+            state.entries.remove(key);
+            return None;
+        }
+        entry.last_used = clock;
+        Some(entry.response.result.clone())
+    }
+
+    /// Caches the result of a request, when the method and commitment allow it.
+    pub fn insert(&self, key: &str, method: &str, params: &Value, result: &Value) {
+        self.insert_at(key, method, params, result, now_millis());
+    }
+
+    fn insert_at(&self, key: &str, method: &str, params: &Value, result: &Value, now: u64) {
+        if commitment(params) == CommitmentLevel::Processed {
+            return;
+        }
+
+        let expires_at = match method {
+            // Transactions only show up once confirmed, and do not change afterwards
+            "getTransaction" if !result.is_null() => None,
+            _ if TTL_METHODS.contains(&method) => Some(now + self.config.ttl.as_millis() as u64),
+            _ => return,
+        };
+
+        self.insert_response(
+            CachedResponse {
+                key: key.to_string(),
+                result: result.clone(),
+                expires_at,
+            },
+            params.clone(),
+            now,
+        );
+        if expires_at.is_none() {
+            self.schedule_save();
+        }
+    }
+
+    /// Drops the expiring responses mentioning the address, e.g. after a transaction changed it.
+    pub fn invalidate(&self, address: &Pubkey) {
+        let address = Value::String(address.to_string());
+        self.state.borrow_mut().entries.retain(|_, entry| {
+            entry.response.expires_at.is_none() || !mentions(&entry.params, &address)
+        });
+    }
+
+    pub fn clear(&self) {
+        self.state.borrow_mut().entries.clear();
+        self.schedule_save();
+    }
+
+    fn insert_response(&self, response: CachedResponse, params: Value, now: u64) {
+        let mut state = self.state.borrow_mut();
+        state.clock += 1;
+        let clock = state.clock;
+
+        if !state.entries.contains_key(&response.key)
+            && state.entries.len() >= self.config.max_entries
+        {
+            state
+                .entries
+                .retain(|_, entry| !is_expired(&entry.response, now));
+            if state.entries.len() >= self.config.max_entries {
+                let least_recently_used = state
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.last_used)
+                    .map(|(key, _)| key.clone());
+                if let Some(key) = least_recently_used {
+                    state.entries.remove(&key);
+                }
+            }
+        }
+
+        state.entries.insert(
+            response.key.clone(),
+            CacheEntry {
+                response,
+                params,
+                last_used: clock,
+            },
+        );
+    }
+
+    fn schedule_save(&self) {
+        if self.storage.is_none()
+            || std::mem::replace(&mut self.state.borrow_mut().save_scheduled, true)
+        {
+            return;
+        }
+
+        let cache = self.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            gloo_timers::future::TimeoutFuture::new(SAVE_DELAY_MS).await;
+            let responses: Vec<CachedResponse> = {
+                let mut state = cache.state.borrow_mut();
+                state.save_scheduled = false;
+                state
+                    .entries
+                    .values()
+                    .filter(|entry| entry.response.expires_at.is_none())
+                    .map(|entry| entry.response.clone())
+                    .collect()
+            };
+            if let Some(storage) = &cache.storage {
+                storage.save(&responses);
+            }
+        });
+    }
+}
+
+/// The commitment of a request, the RPC defaults to `finalized` when the params have none.
+fn commitment(params: &Value) -> CommitmentLevel {
+    params
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find_map(|param| param.get("commitment"))
+        .and_then(|commitment| serde_json::from_value(commitment.clone()).ok())
+        .unwrap_or(CommitmentLevel::Finalized)
+}
+
+fn is_expired(response: &CachedResponse, now: u64) -> bool {
+    response
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= now)
+}
+
+fn mentions(params: &Value, address: &Value) -> bool {
+    match params {
+        Value::Array(values) => values.iter().any(|value| mentions(value, address)),
+        value => value == address,
+    }
+}
+
+fn now_millis() -> u64 {
+    now().as_millis() as u64
+}
+
+impl std::fmt::Debug for ResponseCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseCache")
+            .field("config", &self.config)
+            .field("entries", &self.len())
+            .field("persisted", &self.storage.is_some())
+            .finish()
+    }
+}
+
+/// Two caches are equal when they share the same entries.
+impl PartialEq for ResponseCache {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const ADDRESS: &str = "11111111111111111111111111111111";
+
+    fn cache() -> ResponseCache {
+        ResponseCache::new(
+            CacheConfig::default()
+                .with_ttl(Duration::from_secs(30))
+                .with_max_entries(2),
+        )
+    }
+
+    fn get_balance(cache: &ResponseCache, commitment: &str, lamports: u64, now: u64) -> String {
+        get_balance_of(cache, ADDRESS, commitment, lamports, now)
+    }
+
+    fn get_balance_of(
+        cache: &ResponseCache,
+        address: &str,
+        commitment: &str,
+        lamports: u64,
+        now: u64,
+    ) -> String {
+        let params = json!([address, { "commitment": commitment }]);
+        let key = format!("genesis:getBalance{}", params);
+        cache.insert_at(&key, "getBalance", &params, &json!(lamports), now);
+        key
+    }
+
+    #[test]
+    fn expires_reads_after_the_ttl() {
+        let cache = cache();
+        let key = get_balance(&cache, "confirmed", 42, 1_000);
+
+        assert_eq!(cache.get_at(&key, 30_999), Some(json!(42)));
+        assert_eq!(cache.get_at(&key, 31_000), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn keeps_transactions_for_good() {
+        let cache = cache();
+        let params = json!(["signature", { "commitment": "confirmed" }]);
+        cache.insert_at("tx", "getTransaction", &params, &json!({ "slot": 1 }), 0);
+        // Transactions that did not land yet are fetched again
+        cache.insert_at("pending", "getTransaction", &params, &Value::Null, 0);
+
+        assert_eq!(cache.get_at("tx", u64::MAX), Some(json!({ "slot": 1 })));
+        assert_eq!(cache.get_at("pending", 0), None);
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used_entry() {
+        let cache = cache();
+        let first = get_balance(&cache, "confirmed", 1, 0);
+        let second = get_balance(&cache, "finalized", 2, 0);
+        cache.get_at(&first, 0);
+
+        let third = get_balance_of(&cache, "other", "confirmed", 3, 0);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get_at(&first, 0), Some(json!(1)));
+        assert_eq!(cache.get_at(&second, 0), None);
+        assert_eq!(cache.get_at(&third, 0), Some(json!(3)));
+    }
+
+    #[test]
+    fn evicts_expired_entries_first() {
+        let cache = cache();
+        let expired = get_balance(&cache, "confirmed", 1, 0);
+        let recent = get_balance(&cache, "finalized", 2, 20_000);
+        cache.get_at(&expired, 0);
+
+        get_balance_of(&cache, "other", "confirmed", 3, 40_000);
+        assert_eq!(cache.get_at(&recent, 40_000), Some(json!(2)));
+        assert_eq!(cache.get_at(&expired, 0), None);
+    }
+
+    #[test]
+    fn caches_by_commitment() {
+        let cache = cache();
+        let processed = get_balance(&cache, "processed", 1, 0);
+        get_balance(&cache, "confirmed", 2, 0);
+
+        assert_eq!(cache.get_at(&processed, 0), None);
+        assert_eq!(cache.len(), 1);
+        // Without a commitment, the RPC reads at `finalized`
+        let finalized = format!("genesis:getBalance{}", json!([ADDRESS]));
+        assert_eq!(cache.get_at(&finalized, 0), None);
+        assert_eq!(commitment(&json!([ADDRESS])), CommitmentLevel::Finalized);
+    }
+
+    #[test]
+    fn invalidates_the_reads_mentioning_an_address() {
+        let cache = cache();
+        let balance = get_balance(&cache, "confirmed", 1, 0);
+        let params = json!(["signature"]);
+        cache.insert_at("tx", "getTransaction", &params, &json!({ "slot": 1 }), 0);
+
+        cache.invalidate(&Pubkey::new_unique());
+        assert_eq!(cache.len(), 2);
+        cache.invalidate(&ADDRESS.parse().unwrap());
+        assert_eq!(cache.get_at(&balance, 0), None);
+        assert_eq!(cache.get_at("tx", 0), Some(json!({ "slot": 1 })));
+    }
+
+    #[test]
+    fn caches_known_methods_only() {
+        assert!(ResponseCache::caches("getTransaction"));
+        assert!(ResponseCache::caches("getAccountInfo"));
+        assert!(!ResponseCache::caches("sendTransaction"));
+        assert!(!ResponseCache::caches("getLatestBlockhash"));
+    }
+}
@@ -0,0 +1,41 @@
+use crate::WalletTransaction;
+use solana_client_wasm::solana_sdk::transaction::TransactionVersion;
+
+/// The transaction versions recent wallets sign, legacy and version 0.
+pub const DEFAULT_TRANSACTION_VERSIONS: &[TransactionVersion] =
+    &[TransactionVersion::LEGACY, TransactionVersion::Number(0)];
+
+/// What a wallet supports beyond connecting.
+///
+/// The default supports nothing, as for a wallet that has not been detected.
+///
+/// ## Standard
+/// The transaction versions mirror the `supportedTransactionVersions` of the typescript adapter.
+///
+/// Read: https://github.com/anza-xyz/wallet-adapter/blob/master/packages/core/base/src/adapter.ts
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WalletCapabilities {
+    /// The transaction versions the wallet signs.
+    pub supported_transaction_versions: Vec<TransactionVersion>,
+    pub sign_message: bool,
+    /// Wallets without a native Sign-In are signed in with a message, see [`crate::sign_in_with_message`].
+    pub sign_in: bool,
+    pub sign_and_send_transaction: bool,
+    /// Whether several transactions are signed in a single request.
+    pub sign_all_transactions: bool,
+}
+
+impl WalletCapabilities {
+    pub fn supports_version(&self, version: TransactionVersion) -> bool {
+        self.supported_transaction_versions.contains(&version)
+    }
+
+    /// Whether the wallet signs version 0 transactions, the ones using address lookup tables.
+    pub fn supports_versioned_transactions(&self) -> bool {
+        self.supports_version(TransactionVersion::Number(0))
+    }
+
+    pub fn supports_transaction(&self, transaction: &WalletTransaction) -> bool {
+        self.supports_version(transaction.version())
+    }
+}
@@ -0,0 +1,53 @@
+use crate::WalletError;
+use futures::future::{select, Either};
+use std::{future::Future, time::Duration};
+
+/// Options for connecting to a wallet.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ConnectOptions {
+    /// The cluster endpoint, for wallets that connect to a given cluster.
+    pub rpc_endpoint: Option<String>,
+    /// Only connect if the user already trusts the app, without prompting them.
+    ///
+    /// Wallets reject the request when the app is not trusted yet.
+    pub only_if_trusted: bool,
+    /// How long to wait for the wallet before failing with [`WalletError::Timeout`].
+    pub timeout: Option<Duration>,
+}
+
+impl ConnectOptions {
+    pub fn new(rpc_endpoint: Option<&str>) -> Self {
+        Self {
+            rpc_endpoint: rpc_endpoint.map(|e| e.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Options for a silent reconnect to a wallet the user already trusts.
+    pub fn trusted(rpc_endpoint: Option<&str>) -> Self {
+        Self {
+            only_if_trusted: true,
+            ..Self::new(rpc_endpoint)
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+/// Awaits the future, failing with [`WalletError::Timeout`] if it does not resolve in time.
+pub async fn with_timeout<F: Future>(
+    future: F,
+    timeout: Option<Duration>,
+) -> Result<F::Output, WalletError> {
+    let Some(timeout) = timeout else {
+        return Ok(future.await);
+    };
+    let sleep = gloo_timers::future::TimeoutFuture::new(timeout.as_millis() as u32);
+    match select(Box::pin(future), sleep).await {
+        Either::Left((output, _)) => Ok(output),
+        Either::Right(_) => Err(WalletError::Timeout),
+    }
+}
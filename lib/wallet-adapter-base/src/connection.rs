@@ -0,0 +1,402 @@
+use crate::{
+    with_timeout, BatchClient, BatchQueue, EndpointPool, ResponseCache, SubscriptionManager,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use solana_client_wasm::{
+    solana_sdk::{commitment_config::CommitmentConfig, hash::Hash},
+    ClientError, ClientRequest, ClientResponse, ClientResult,
+};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc, time::Duration};
+
+/// How a [`Connection`] talks to the cluster.
+#[derive(Clone, PartialEq)]
+pub struct ConnectionConfig {
+    /// The commitment requests use unless they specify their own.
+    pub commitment: CommitmentConfig,
+    /// The WebSocket endpoint, derived from the HTTP endpoint when not set.
+    pub ws_endpoint: Option<String>,
+    /// Headers sent with every HTTP request, e.g. the API key of an authenticated RPC provider.
+    pub http_headers: BTreeMap<String, String>,
+    /// How long to wait for a response before failing.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        Self {
+            commitment: CommitmentConfig::confirmed(),
+            ws_endpoint: None,
+            http_headers: BTreeMap::new(),
+            timeout: None,
+        }
+    }
+}
+
+impl ConnectionConfig {
+    pub fn with_commitment(mut self, commitment: CommitmentConfig) -> Self {
+        self.commitment = commitment;
+        self
+    }
+
+    pub fn with_ws_endpoint(mut self, ws_endpoint: &str) -> Self {
+        self.ws_endpoint = Some(ws_endpoint.to_string());
+        self
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.http_headers
+            .insert(name.to_string(), value.to_string());
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+/// Header values are left out, they usually hold credentials.
+impl std::fmt::Debug for ConnectionConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionConfig")
+            .field("commitment", &self.commitment.commitment)
+            .field("ws_endpoint", &self.ws_endpoint)
+            .field(
+                "http_headers",
+                &self.http_headers.keys().collect::<Vec<_>>(),
+            )
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+/// Why a request to an endpoint failed.
+enum SendError {
+    /// The endpoint is rate-limiting, unavailable or did not answer in time, another one may
+    /// serve the request.
+    Retryable(ClientError),
+    Fatal(ClientError),
+}
+
+/// A connection to the cluster.
+///
+/// Clones share the same batch queue, cache and subscriptions.
+pub struct Connection {
+    pub(crate) endpoint: String,
+    config: ConnectionConfig,
+    http: reqwest::Client,
+    endpoints: EndpointPool,
+    batch: Rc<RefCell<BatchQueue>>,
+    cache: Option<ResponseCache>,
+    /// Fetched along the first cached read, to key the cache by cluster.
+    pub(crate) genesis_hash: Rc<RefCell<Option<Hash>>>,
+    subscriptions: SubscriptionManager,
+}
+
+impl Connection {
+    pub fn new(endpoint: &str) -> Self {
+        Self::new_with_config(endpoint, ConnectionConfig::default())
+    }
+
+    pub fn new_with_config(endpoint: &str, config: ConnectionConfig) -> Self {
+        Self::new_with_endpoints(EndpointPool::new(&[endpoint]), config)
+    }
+
+    /// Creates a connection to the best of the endpoints, requests fail over to the others.
+    pub fn new_with_endpoints(endpoints: EndpointPool, config: ConnectionConfig) -> Self {
+        let endpoint = endpoints.best().unwrap_or_default();
+        let ws_endpoint = match &config.ws_endpoint {
+            Some(ws_endpoint) => ws_endpoint.clone(),
+            None => to_ws_endpoint(&endpoint),
+        };
+        Self {
+            endpoint,
+            http: reqwest::Client::new(),
+            config,
+            endpoints,
+            batch: Default::default(),
+            cache: None,
+            genesis_hash: Default::default(),
+            subscriptions: SubscriptionManager::new(&ws_endpoint),
+        }
+    }
+
+    /// Caches the reads made through [`Connection::rpc`].
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    pub fn endpoints(&self) -> &EndpointPool {
+        &self.endpoints
+    }
+
+    pub fn cache(&self) -> Option<&ResponseCache> {
+        self.cache.as_ref()
+    }
+
+    pub fn config(&self) -> &ConnectionConfig {
+        &self.config
+    }
+
+    pub fn commitment(&self) -> CommitmentConfig {
+        self.config.commitment
+    }
+
+    /// The WebSocket endpoint, `ws(s)://` with the port after the HTTP one when not configured.
+    pub fn ws_endpoint(&self) -> String {
+        self.subscriptions.endpoint().to_string()
+    }
+
+    /// The subscriptions over the WebSocket endpoint, shared by the clones of the connection.
+    pub fn subscriptions(&self) -> &SubscriptionManager {
+        &self.subscriptions
+    }
+
+    /// A client with the typed API of [`WasmClient`](solana_client_wasm::WasmClient), batching and
+    /// deduplicating requests.
+    ///
+    /// Clients of the same connection share their batches.
+    pub fn rpc(&self) -> BatchClient {
+        BatchClient::new(self.clone(), self.batch.clone())
+    }
+
+    /// Sends a JSON-RPC request with the configured headers and timeout.
+    ///
+    /// The request goes to the best endpoint, and fails over to the next one when an endpoint
+    /// rate-limits, is unavailable or times out.
+    pub async fn send(&self, request: &ClientRequest) -> ClientResult<ClientResponse> {
+        parse_response(self.post(request).await?)
+    }
+
+    /// Sends the requests in a single JSON-RPC batch, the way [`Connection::send`] does.
+    ///
+    /// The requests are numbered by position, and the responses returned in the same order.
+    pub async fn send_batch(
+        &self,
+        requests: &[ClientRequest],
+    ) -> ClientResult<Vec<ClientResult<ClientResponse>>> {
+        let requests: Vec<ClientRequest> = requests
+            .iter()
+            .enumerate()
+            .map(|(id, request)| request.clone().id(id as u32).clone())
+            .collect();
+
+        let value = self.post(&requests).await?;
+        let Value::Array(values) = value else {
+            // The whole batch was rejected
+            return Err(parse_response(value)
+                .err()
+                .unwrap_or_else(|| ClientError::new("Invalid batch response.")));
+        };
+
+        let mut responses: Vec<Option<ClientResult<ClientResponse>>> =
+            requests.iter().map(|_| None).collect();
+        for value in values {
+            let id = value.get("id").and_then(|id| id.as_u64());
+            if let Some(response) = id.and_then(|id| responses.get_mut(id as usize)) {
+                *response = Some(parse_response(value));
+            }
+        }
+        Ok(responses
+            .into_iter()
+            .map(|r| r.unwrap_or_else(|| Err(ClientError::new("Missing batch response."))))
+            .collect())
+    }
+
+    /// Posts the body to the best endpoint, failing over to the others.
+    async fn post<B: Serialize + ?Sized>(&self, body: &B) -> ClientResult<Value> {
+        let mut last_error = ClientError::new("No RPC endpoint configured.");
+        for endpoint in self.endpoints.ranked() {
+            let started = now();
+            match self.post_to(&endpoint, body).await {
+                Ok(response) => {
+                    self.endpoints
+                        .record_success(&endpoint, now().saturating_sub(started));
+                    return Ok(response);
+                }
+                Err(SendError::Retryable(e)) => {
+                    log::warn!("RPC endpoint {} failed, failing over. {}", endpoint, e);
+                    self.endpoints.record_failure(&endpoint);
+                    last_error = e;
+                }
+                Err(SendError::Fatal(e)) => return Err(e),
+            }
+        }
+        Err(last_error)
+    }
+
+    async fn send_to(
+        &self,
+        endpoint: &str,
+        request: &ClientRequest,
+    ) -> Result<ClientResponse, SendError> {
+        let value = self.post_to(endpoint, request).await?;
+        parse_response(value).map_err(SendError::Fatal)
+    }
+
+    async fn post_to<B: Serialize + ?Sized>(
+        &self,
+        endpoint: &str,
+        body: &B,
+    ) -> Result<Value, SendError> {
+        let mut builder = self.http.post(endpoint).json(body);
+        for (name, value) in &self.config.http_headers {
+            builder = builder.header(name, value);
+        }
+        let response = async {
+            let response = builder
+                .send()
+                .await
+                .map_err(|e| SendError::Retryable(e.into()))?;
+            let status = response.status();
+            if status.as_u16() == 429 || status.is_server_error() {
+                return Err(SendError::Retryable(ClientError::new(&format!(
+                    "HTTP status {}",
+                    status
+                ))));
+            }
+            response
+                .json::<Value>()
+                .await
+                .map_err(|e| SendError::Fatal(e.into()))
+        };
+        with_timeout(response, self.config.timeout)
+            .await
+            .map_err(|_| SendError::Retryable(ClientError::new("Request timed out.")))?
+    }
+
+    /// Checks the health, the slot and the latency of every endpoint.
+    pub async fn check_endpoints(&self) {
+        let checks = self
+            .endpoints
+            .endpoints()
+            .into_iter()
+            .map(|endpoint| async move {
+                let started = now();
+                let health = self
+                    .send_to(&endpoint, ClientRequest::new("getHealth").id(1))
+                    .await;
+                let latency = now().saturating_sub(started);
+                let slot = self
+                    .send_to(
+                        &endpoint,
+                        ClientRequest::new("getSlot")
+                            .id(2)
+                            .params(json!([{ "commitment": self.config.commitment.commitment }])),
+                    )
+                    .await
+                    .ok()
+                    .and_then(|r| r.result.as_u64());
+
+                let healthy = matches!(&health, Ok(r) if r.result.as_str() == Some("ok"));
+                if !healthy {
+                    log::warn!("RPC endpoint {} is unhealthy.", endpoint);
+                }
+                self.endpoints.record_health(
+                    &endpoint,
+                    healthy,
+                    slot,
+                    health.is_ok().then_some(latency),
+                );
+            });
+        futures::future::join_all(checks).await;
+    }
+
+    /// Sends a JSON-RPC request, deserializing its result.
+    pub async fn request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> ClientResult<T> {
+        let response = self.send(ClientRequest::new(method).params(params)).await?;
+        serde_json::from_value(response.result).map_err(|e| ClientError::new(&e.to_string()))
+    }
+}
+
+/// Parses a JSON-RPC response, the error responses included.
+fn parse_response(value: Value) -> ClientResult<ClientResponse> {
+    // Error responses have no result, which would otherwise parse as a `null` one
+    if let Some(error) = value.get("error") {
+        let message = error["message"]
+            .as_str()
+            .unwrap_or("Unknown RPC error.")
+            .to_string();
+        return Err(serde_json::from_value::<ClientError>(value)
+            .unwrap_or_else(|_| ClientError::new(&message)));
+    }
+    serde_json::from_value::<ClientResponse>(value).map_err(|e| ClientError::new(&e.to_string()))
+}
+
+/// The current time, `Instant` is not available in the browser.
+pub(crate) fn now() -> Duration {
+    Duration::from_secs_f64(js_sys::Date::now() / 1_000.0)
+}
+
+/// Derives the WebSocket endpoint the same way `WasmClient` does.
+fn to_ws_endpoint(endpoint: &str) -> String {
+    let Some(scheme_end) = endpoint.find(':') else {
+        return endpoint.to_string();
+    };
+    let scheme = match &endpoint[..scheme_end] {
+        "https" => "wss",
+        "http" => "ws",
+        _ => return endpoint.to_string(),
+    };
+    let mut ws_endpoint = format!("{}{}", scheme, &endpoint[scheme_end..]);
+
+    // The WebSocket listens on the port after the HTTP one, when a port is given
+    let port_start = ws_endpoint.rfind(':').unwrap();
+    if port_start > scheme.len() {
+        if let Ok(port) = ws_endpoint[port_start + 1..].parse::<u16>() {
+            ws_endpoint.replace_range(port_start + 1.., &(port + 1).to_string());
+        }
+    }
+    ws_endpoint
+}
+
+impl PartialEq for Connection {
+    fn eq(&self, other: &Self) -> bool {
+        self.endpoint == other.endpoint
+            && self.config == other.config
+            && self.endpoints == other.endpoints
+            && self.cache == other.cache
+    }
+}
+
+impl std::fmt::Debug for Connection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Connection")
+            .field("endpoint", &self.endpoint)
+            .field("config", &self.config)
+            .field("endpoints", &self.endpoints)
+            .field("cache", &self.cache)
+            .finish()
+    }
+}
+
+impl Default for Connection {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl Clone for Connection {
+    fn clone(&self) -> Self {
+        Self {
+            endpoint: self.endpoint.clone(),
+            config: self.config.clone(),
+            http: self.http.clone(),
+            endpoints: self.endpoints.clone(),
+            batch: self.batch.clone(),
+            cache: self.cache.clone(),
+            genesis_hash: self.genesis_hash.clone(),
+            subscriptions: self.subscriptions.clone(),
+        }
+    }
+}
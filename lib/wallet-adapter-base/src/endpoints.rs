@@ -0,0 +1,151 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// How many slots an endpoint may lag behind the most advanced one before it is avoided.
+pub const MAX_SLOT_LAG: u64 = 50;
+
+/// The health of an RPC endpoint, as last measured.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndpointStatus {
+    pub endpoint: String,
+    /// Whether the node reported itself healthy through `getHealth`.
+    pub healthy: bool,
+    pub slot: Option<u64>,
+    /// How many slots the endpoint lags behind the most advanced endpoint.
+    pub slot_lag: u64,
+    pub latency: Option<Duration>,
+    /// Retryable failures since the last successful request.
+    pub failures: u32,
+}
+
+impl EndpointStatus {
+    fn new(endpoint: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            healthy: true,
+            slot: None,
+            slot_lag: 0,
+            latency: None,
+            failures: 0,
+        }
+    }
+
+    pub fn is_available(&self) -> bool {
+        self.healthy && self.slot_lag <= MAX_SLOT_LAG
+    }
+}
+
+/// The RPC endpoints of a connection and their health.
+///
+/// Endpoints are ranked by availability, then by failures and latency. Endpoints that have not
+/// been checked yet keep the order they were given in. Clones share the same state.
+#[derive(Clone, Default)]
+pub struct EndpointPool {
+    statuses: Arc<Mutex<Vec<EndpointStatus>>>,
+}
+
+impl EndpointPool {
+    pub fn new<S: AsRef<str>>(endpoints: &[S]) -> Self {
+        let mut statuses: Vec<EndpointStatus> = vec![];
+        for endpoint in endpoints {
+            if !statuses.iter().any(|s| s.endpoint == endpoint.as_ref()) {
+                statuses.push(EndpointStatus::new(endpoint.as_ref()));
+            }
+        }
+        Self {
+            statuses: Arc::new(Mutex::new(statuses)),
+        }
+    }
+
+    pub fn statuses(&self) -> Vec<EndpointStatus> {
+        self.statuses.lock().unwrap().clone()
+    }
+
+    pub fn endpoints(&self) -> Vec<String> {
+        self.statuses
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|s| s.endpoint.clone())
+            .collect()
+    }
+
+    /// The endpoints, best first.
+    pub fn ranked(&self) -> Vec<String> {
+        let mut statuses = self.statuses();
+        statuses.sort_by_key(|s| {
+            (
+                !s.is_available(),
+                s.failures,
+                s.latency.unwrap_or(Duration::MAX),
+            )
+        });
+        statuses.into_iter().map(|s| s.endpoint).collect()
+    }
+
+    pub fn best(&self) -> Option<String> {
+        self.ranked().into_iter().next()
+    }
+
+    pub(crate) fn record_success(&self, endpoint: &str, latency: Duration) {
+        self.update(endpoint, |s| {
+            s.failures = 0;
+            s.latency = Some(latency);
+        });
+    }
+
+    pub(crate) fn record_failure(&self, endpoint: &str) {
+        self.update(endpoint, |s| s.failures += 1);
+    }
+
+    /// Records the outcome of a health check, the slot lags are updated along.
+    pub(crate) fn record_health(
+        &self,
+        endpoint: &str,
+        healthy: bool,
+        slot: Option<u64>,
+        latency: Option<Duration>,
+    ) {
+        self.update(endpoint, |s| {
+            s.healthy = healthy;
+            s.slot = slot;
+            s.latency = latency;
+            if healthy {
+                s.failures = 0;
+            }
+        });
+
+        let mut statuses = self.statuses.lock().unwrap();
+        let max_slot = statuses.iter().filter_map(|s| s.slot).max().unwrap_or(0);
+        for status in statuses.iter_mut() {
+            status.slot_lag = status.slot.map_or(0, |slot| max_slot.saturating_sub(slot));
+        }
+    }
+
+    fn update(&self, endpoint: &str, f: impl FnOnce(&mut EndpointStatus)) {
+        if let Some(status) = self
+            .statuses
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|s| s.endpoint == endpoint)
+        {
+            f(status);
+        }
+    }
+}
+
+impl std::fmt::Debug for EndpointPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.statuses().iter()).finish()
+    }
+}
+
+/// Two pools are equal when they share the same state.
+impl PartialEq for EndpointPool {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.statuses, &other.statuses)
+    }
+}
@@ -1,3 +1,16 @@
+use wasm_bindgen::{JsCast, JsValue};
+
+/// The messages wallets reject requests with when they leave out the error code, lowercased
+/// and without the final period.
+const USER_REJECTED_MESSAGES: &[&str] = &[
+    "user rejected the request",
+    "user rejected request",
+    "user rejected the transaction",
+    "request rejected by user",
+    "transaction rejected by user",
+    "user denied the request",
+];
+
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub enum WalletError {
     NotReady,
@@ -17,4 +30,308 @@ pub enum WalletError {
     Timeout,
     WindowBlocked,
     WindowClosed,
+    /// The wallet can only watch an address, it cannot sign.
+    WatchOnly,
+    /// The request continues in another app, its result comes back with a redirect to the app.
+    Redirected,
+    /// An error reported by the wallet provider itself.
+    Provider(ProviderError),
+}
+
+impl WalletError {
+    /// Maps a value thrown by a wallet provider into a wallet error.
+    ///
+    /// Values that carry a provider error code, or that are recognizably a user rejection,
+    /// become [`WalletError::Provider`], anything else becomes the given `fallback`.
+    pub fn from_js(value: &JsValue, fallback: WalletError) -> Self {
+        match ProviderError::from_js(value) {
+            Some(e) => Self::Provider(e),
+            None => fallback,
+        }
+    }
+
+    /// The provider error code, if the error was reported by the wallet provider.
+    pub fn code(&self) -> Option<i64> {
+        match self {
+            Self::Provider(e) => Some(e.code.code()),
+            _ => None,
+        }
+    }
+
+    /// Whether the user rejected the request in their wallet.
+    pub fn is_user_rejected(&self) -> bool {
+        matches!(
+            self,
+            Self::Provider(ProviderError {
+                code: ProviderErrorCode::UserRejected,
+                ..
+            })
+        )
+    }
+}
+
+impl std::fmt::Display for WalletError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use WalletError::*;
+        match self {
+            NotReady => write!(f, "Wallet not ready"),
+            Load => write!(f, "Failed to load wallet"),
+            Config => write!(f, "Invalid wallet configuration"),
+            Connection => write!(f, "Failed to connect to wallet"),
+            Disconnected => write!(f, "Wallet disconnected"),
+            Disconnection => write!(f, "Failed to disconnect from wallet"),
+            Account => write!(f, "Invalid wallet account"),
+            Pubkey => write!(f, "Invalid wallet public key"),
+            Keypair => write!(f, "Invalid keypair"),
+            NotConnected => write!(f, "Wallet not connected"),
+            SendTransaction => write!(f, "Failed to send transaction"),
+            SignTransaction => write!(f, "Failed to sign transaction"),
+            SignMessage => write!(f, "Failed to sign message"),
+            SignIn => write!(f, "Failed to sign in"),
+            Timeout => write!(f, "Wallet request timed out"),
+            WindowBlocked => write!(f, "Wallet window was blocked"),
+            WindowClosed => write!(f, "Wallet window was closed"),
+            WatchOnly => write!(f, "Watch-only wallets cannot sign"),
+            Redirected => write!(f, "Request continues in the wallet app"),
+            Provider(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for WalletError {}
+
+/// An error reported by a wallet provider.
+///
+/// ## Standard
+/// Providers reject requests with EIP-1193 style errors, which carry a JSON-RPC style code.
+///
+/// Read: https://eips.ethereum.org/EIPS/eip-1193#provider-errors
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct ProviderError {
+    pub code: ProviderErrorCode,
+    pub message: String,
+}
+
+impl ProviderError {
+    pub fn new<T: Into<String>>(code: ProviderErrorCode, message: T) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Reads a provider error from a thrown value.
+    ///
+    /// Wallets throw either `{ code, message }` objects, `Error` instances, or plain strings.
+    /// Some of them signal user rejections only through the message, the known ones are
+    /// recognized as [`ProviderErrorCode::UserRejected`].
+    pub fn from_js(value: &JsValue) -> Option<Self> {
+        let get = |key: &str| js_sys::Reflect::get(value, &key.into()).ok();
+
+        let message = if let Some(message) = value.as_string() {
+            message
+        } else if let Some(error) = value.dyn_ref::<js_sys::Error>() {
+            String::from(error.message())
+        } else {
+            get("message")
+                .and_then(|m| m.as_string())
+                .unwrap_or_default()
+        };
+
+        let code = if value.is_object() {
+            get("code").and_then(|c| c.as_f64()).map(|c| c as i64)
+        } else {
+            None
+        };
+        Self::from_parts(code, message)
+    }
+
+    /// The provider error of a thrown code and message, see [`ProviderError::from_js`].
+    fn from_parts(code: Option<i64>, message: String) -> Option<Self> {
+        if let Some(code) = code {
+            return Some(Self::new(ProviderErrorCode::from(code), message));
+        }
+
+        let normalized = message.trim().trim_end_matches('.').to_lowercase();
+        if USER_REJECTED_MESSAGES.contains(&normalized.as_str()) {
+            return Some(Self::new(ProviderErrorCode::UserRejected, message));
+        }
+
+        None
+    }
+}
+
+impl std::fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.message.is_empty() {
+            write!(f, "{} ({})", self.code, self.code.code())
+        } else {
+            write!(f, "{} ({})", self.message, self.code.code())
+        }
+    }
+}
+
+/// The error codes wallet providers reject requests with.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum ProviderErrorCode {
+    /// 4001, the user rejected the request.
+    UserRejected,
+    /// 4100, the requested method or account has not been authorized by the user.
+    Unauthorized,
+    /// 4200, the provider does not support the requested method.
+    UnsupportedMethod,
+    /// 4900, the provider is disconnected from all chains.
+    Disconnected,
+    /// 4901, the provider is not connected to the requested chain.
+    ChainDisconnected,
+    /// -32000, missing or invalid parameters.
+    InvalidInput,
+    /// -32002, the requested resource is not available, e.g. a request is already pending.
+    ResourceNotAvailable,
+    /// -32003, the transaction was rejected.
+    TransactionRejected,
+    /// -32601, the method does not exist.
+    MethodNotFound,
+    /// -32602, invalid method parameters.
+    InvalidParams,
+    /// -32603, something went wrong within the wallet.
+    Internal,
+    /// Any other code.
+    Other(i64),
+}
+
+impl ProviderErrorCode {
+    pub fn code(&self) -> i64 {
+        use ProviderErrorCode::*;
+        match self {
+            UserRejected => 4001,
+            Unauthorized => 4100,
+            UnsupportedMethod => 4200,
+            Disconnected => 4900,
+            ChainDisconnected => 4901,
+            InvalidInput => -32000,
+            ResourceNotAvailable => -32002,
+            TransactionRejected => -32003,
+            MethodNotFound => -32601,
+            InvalidParams => -32602,
+            Internal => -32603,
+            Other(code) => *code,
+        }
+    }
+}
+
+impl From<i64> for ProviderErrorCode {
+    fn from(value: i64) -> Self {
+        use ProviderErrorCode::*;
+        match value {
+            4001 => UserRejected,
+            4100 => Unauthorized,
+            4200 => UnsupportedMethod,
+            4900 => Disconnected,
+            4901 => ChainDisconnected,
+            -32000 => InvalidInput,
+            -32002 => ResourceNotAvailable,
+            -32003 => TransactionRejected,
+            -32601 => MethodNotFound,
+            -32602 => InvalidParams,
+            -32603 => Internal,
+            code => Other(code),
+        }
+    }
+}
+
+impl std::fmt::Display for ProviderErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use ProviderErrorCode::*;
+        match self {
+            UserRejected => write!(f, "User rejected the request"),
+            Unauthorized => write!(f, "Unauthorized"),
+            UnsupportedMethod => write!(f, "Unsupported method"),
+            Disconnected => write!(f, "Disconnected"),
+            ChainDisconnected => write!(f, "Chain disconnected"),
+            InvalidInput => write!(f, "Invalid input"),
+            ResourceNotAvailable => write!(f, "Requested resource not available"),
+            TransactionRejected => write!(f, "Transaction rejected"),
+            MethodNotFound => write!(f, "Method not found"),
+            InvalidParams => write!(f, "Invalid params"),
+            Internal => write!(f, "Internal error"),
+            Other(_) => write!(f, "Provider error"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_provider_error_codes() {
+        assert_eq!(
+            ProviderError::from_parts(Some(4001), "Denied".to_string()),
+            Some(ProviderError::new(
+                ProviderErrorCode::UserRejected,
+                "Denied"
+            ))
+        );
+        assert_eq!(
+            ProviderError::from_parts(Some(-32603), String::new()),
+            Some(ProviderError::new(ProviderErrorCode::Internal, ""))
+        );
+        assert_eq!(
+            ProviderError::from_parts(Some(1234), String::new()).map(|e| e.code),
+            Some(ProviderErrorCode::Other(1234))
+        );
+    }
+
+    #[test]
+    fn recognizes_only_known_rejection_messages() {
+        assert_eq!(
+            ProviderError::from_parts(None, "User rejected the request.".to_string())
+                .map(|e| e.code),
+            Some(ProviderErrorCode::UserRejected)
+        );
+        assert_eq!(
+            ProviderError::from_parts(None, "user rejected request".to_string()).map(|e| e.code),
+            Some(ProviderErrorCode::UserRejected)
+        );
+        assert_eq!(
+            ProviderError::from_parts(
+                None,
+                "Transaction rejected: blockhash not found".to_string()
+            ),
+            None
+        );
+        assert_eq!(
+            ProviderError::from_parts(None, "Signature verification rejected".to_string()),
+            None
+        );
+        assert_eq!(ProviderError::from_parts(None, String::new()), None);
+    }
+
+    #[test]
+    fn maps_codes_both_ways() {
+        for code in [
+            4001, 4100, 4200, 4900, 4901, -32000, -32002, -32003, -32601, -32602, -32603, 42,
+        ] {
+            assert_eq!(ProviderErrorCode::from(code).code(), code);
+        }
+        assert_eq!(
+            ProviderErrorCode::from(4001),
+            ProviderErrorCode::UserRejected
+        );
+        assert_eq!(
+            ProviderErrorCode::from(-32003),
+            ProviderErrorCode::TransactionRejected
+        );
+        assert_eq!(ProviderErrorCode::from(42), ProviderErrorCode::Other(42));
+    }
+
+    #[test]
+    fn tells_user_rejections_apart() {
+        let rejected = ProviderError::new(ProviderErrorCode::UserRejected, "");
+        assert!(WalletError::Provider(rejected).is_user_rejected());
+        let rejected_transaction = ProviderError::new(ProviderErrorCode::TransactionRejected, "");
+        assert!(!WalletError::Provider(rejected_transaction).is_user_rejected());
+        assert!(!WalletError::SignTransaction.is_user_rejected());
+    }
 }
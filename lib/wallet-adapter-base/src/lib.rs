@@ -1,10 +1,37 @@
 pub mod adapter;
+pub mod batch;
+pub mod bus;
+pub mod cache;
+pub mod capabilities;
+pub mod connect;
+pub mod connection;
+pub mod endpoints;
 pub mod errors;
-
+pub mod message;
+pub mod signer;
+pub mod siws;
+pub mod subscription;
+pub mod transaction;
+pub mod watch;
+
+pub use batch::*;
+pub use bus::*;
+pub use cache::*;
+pub use capabilities::*;
+pub use connect::*;
+pub use connection::*;
+pub use endpoints::*;
 pub use errors::*;
+pub use message::*;
+pub use signer::*;
+pub use siws::*;
+pub use subscription::*;
+pub use transaction::*;
+pub use watch::*;
 
 use async_trait::async_trait;
-use solana_client_wasm::{solana_sdk::pubkey::Pubkey, WasmClient};
+use futures::StreamExt;
+use solana_client_wasm::solana_sdk::{pubkey::Pubkey, signature::Signature};
 
 /// Events fired by the wallet adapter.
 #[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
@@ -15,15 +42,46 @@ pub enum Event {
     Disconnect,
     Error(WalletError),
     ReadyStateChange(WalletReadyState),
+    /// The user switched accounts inside the wallet.
+    ///
+    /// `None` when the new account has not been connected to the app yet.
+    AccountChanged(Option<Pubkey>),
+}
+
+/// The answer of a wallet to a request that left the app, delivered when the wallet redirects
+/// back to it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RedirectResponse {
+    Connect(Pubkey),
+    Disconnect,
+    SignTransaction(WalletTransaction),
+    SignMessage(Signature),
 }
 
 /// An owned wallet name.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct WalletName(String);
 
 impl WalletName {
     pub fn new<T: Into<String>>(w: T) -> Self {
         Self(w.into())
     }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for WalletName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for WalletName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
 }
 
 impl From<String> for WalletName {
@@ -52,6 +110,37 @@ impl Default for WalletReadyState {
     }
 }
 
+/// Parses the ready states emitted with `readyStateChange`, as named by the typescript adapter.
+impl std::str::FromStr for WalletReadyState {
+    type Err = WalletError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Installed" => Ok(Self::Installed),
+            "NotDetected" => Ok(Self::NotDetected),
+            "Loadable" => Ok(Self::Loadable),
+            "Unsupported" => Ok(Self::Unsupported),
+            _ => Err(WalletError::NotReady),
+        }
+    }
+}
+
+/// The state of the listeners an adapter registers on the wallet provider.
+///
+/// Listeners are registered for the duration of a session, from connect to disconnect.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ListenerStatus {
+    /// No listener has been registered.
+    #[default]
+    Unbound,
+    /// The listeners are registered for the active session.
+    Bound,
+    /// The listeners were removed from the provider when the session ended.
+    Removed,
+    /// The provider could not remove the listeners, they were muted and left to the provider.
+    Retained,
+}
+
 #[async_trait(?Send)]
 pub trait WalletAdapterMetadata: std::fmt::Debug {
     fn name(&self) -> &str;
@@ -65,87 +154,79 @@ pub trait WalletAdapterBase: WalletAdapterMetadata {
     fn is_connected(&self) -> bool;
 
     fn ready_state(&self) -> WalletReadyState;
-    
+
     fn pubkey(&self) -> Option<Pubkey>;
     fn connecting(&self) -> bool;
 
-    async fn connect(&self, rpc_endpoint: Option<&str>);
+    /// Requests the wallet to connect, returning the public key of the connected account.
+    async fn connect(&self, options: ConnectOptions) -> Result<Pubkey, WalletError>;
     async fn disconnect(&self);
-}
 
-#[async_trait(?Send)]
-pub trait WalletAdapter: WalletAdapterBase {
-    fn priority_fees_supported(&self) -> bool;
+    /// Requests the wallet to sign a transaction, returning the signed transaction.
+    async fn sign_transaction(
+        &self,
+        transaction: WalletTransaction,
+    ) -> Result<WalletTransaction, WalletError>;
 
-    async fn on_event(&self, f: &mut dyn FnMut(Event));
-}
+    /// Requests the wallet to sign all of the given transactions at once.
+    async fn sign_all_transactions(
+        &self,
+        transactions: Vec<WalletTransaction>,
+    ) -> Result<Vec<WalletTransaction>, WalletError>;
 
-impl PartialEq for dyn WalletAdapter {
-    fn eq(&self, other: &Self) -> bool {
-        self.name() == other.name()
-    }
+    /// Requests the wallet to sign a transaction and submit it to the cluster, returning its signature.
+    async fn sign_and_send_transaction(
+        &self,
+        transaction: WalletTransaction,
+    ) -> Result<Signature, WalletError>;
 
-    fn ne(&self, other: &Self) -> bool {
-        self.name() != other.name()
-    }
-}
+    /// Requests the wallet to sign an arbitrary off-chain message.
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature, WalletError>;
 
-/// A connection to the cluster.
-pub struct Connection {
-    pub(crate) endpoint: String,
-    pub client: WasmClient,
+    /// Requests the wallet to Sign-In With Solana.
+    async fn sign_in(&self, input: SignInInput) -> Result<SignInOutput, WalletError>;
 }
 
-impl Connection {
-    pub fn new(endpoint: &str) -> Self {
-        Self {
-            endpoint: endpoint.to_string(),
-            client: WasmClient::new(&endpoint),
-        }
-    }
+#[async_trait(?Send)]
+pub trait WalletAdapter: WalletAdapterBase {
+    fn priority_fees_supported(&self) -> bool;
 
-    pub fn endpoint(&self) -> &str {
-        &self.endpoint
-    }
-}
+    /// What the wallet supports, e.g. whether it signs versioned transactions.
+    fn capabilities(&self) -> WalletCapabilities;
 
-impl PartialEq for Connection {
-    fn eq(&self, other: &Self) -> bool {
-        self.endpoint == other.endpoint
-    }
+    /// Subscribes to the events emitted by the wallet.
+    fn subscribe(&self) -> EventSubscription;
 
-    fn ne(&self, other: &Self) -> bool {
-        self.endpoint != other.endpoint
+    /// The state of the listeners registered on the wallet provider, for adapters that
+    /// register any.
+    fn listener_status(&self) -> ListenerStatus {
+        ListenerStatus::Unbound
     }
-}
 
-impl std::fmt::Debug for Connection {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Connection")
-            .field("endpoint", &format!("{}", self.endpoint))
-            .finish()
+    /// Takes the answer to the request that left the app, for wallets answering by redirecting
+    /// back to it, e.g. through universal links.
+    ///
+    /// Requests that leave the app fail with [`WalletError::Redirected`], their answer is only
+    /// available once the app has been opened again by the wallet.
+    fn take_redirect_response(&self) -> Option<Result<RedirectResponse, WalletError>> {
+        None
     }
-}
 
-impl Default for Connection {
-    fn default() -> Self {
-        Self {
-            endpoint: String::new(),
-            client: WasmClient::new(""),
+    /// Invokes `f` with every event emitted by the wallet, until the wallet is dropped.
+    async fn on_event(&self, f: &mut dyn FnMut(Event)) {
+        let mut events = self.subscribe();
+        while let Some(event) = events.next().await {
+            f(event)
         }
     }
 }
 
-impl Clone for Connection {
-    fn clone(&self) -> Self {
-        Self {
-            endpoint: self.endpoint.clone(),
-            client: WasmClient::new(&self.endpoint),
-        }
+impl PartialEq for dyn WalletAdapter {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
     }
 
-    fn clone_from(&mut self, source: &Self) {
-        self.endpoint = source.endpoint.clone();
-        self.client = WasmClient::new(&source.endpoint);
+    fn ne(&self, other: &Self) -> bool {
+        self.name() != other.name()
     }
 }
@@ -0,0 +1,18 @@
+use crate::WalletError;
+use solana_client_wasm::solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+/// Verifies that `signature` is a valid Ed25519 signature of `message` by `pubkey`.
+///
+/// This is the off-chain counterpart of `WalletAdapterBase::sign_message`, so signed
+/// messages can be checked without a backend.
+pub fn verify_message(
+    pubkey: &Pubkey,
+    message: &[u8],
+    signature: &Signature,
+) -> Result<(), WalletError> {
+    if signature.verify(pubkey.as_ref(), message) {
+        Ok(())
+    } else {
+        Err(WalletError::SignMessage)
+    }
+}
@@ -1 +1,334 @@
+use crate::{
+    sign_in_with_message, ConnectOptions, Connection, Event, EventBus, EventSubscription,
+    SignInInput, SignInOutput, WalletAdapter, WalletAdapterBase, WalletAdapterMetadata,
+    WalletCapabilities, WalletError, WalletReadyState, WalletTransaction,
+    DEFAULT_TRANSACTION_VERSIONS,
+};
+use async_trait::async_trait;
+use solana_client_wasm::solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+};
+use std::sync::Mutex;
 
+pub const KEYPAIR_WALLET_NAME: &str = "Keypair";
+
+/// A wallet adapter that signs with an in-memory keypair.
+///
+/// It emits the same events as a browser extension, which makes it possible to drive the
+/// wallet context against a local validator, or deterministically in tests.
+pub struct KeypairWalletAdapter {
+    keypair: Keypair,
+    connected: Mutex<bool>,
+    /// The connection used by `sign_and_send_transaction`, when one was given.
+    connection: Option<Connection>,
+    /// The endpoint used by `sign_and_send_transaction` otherwise, set on connect.
+    endpoint: Mutex<Option<String>>,
+    bus: EventBus,
+}
+
+impl std::fmt::Debug for KeypairWalletAdapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeypairWalletAdapter")
+            .field("pubkey", &format!("{}", self.keypair.pubkey()))
+            .field("connected", &self.connected)
+            .field("connection", &self.connection)
+            .field("endpoint", &self.endpoint)
+            .finish()
+    }
+}
+
+impl Default for KeypairWalletAdapter {
+    fn default() -> Self {
+        Self::new(Keypair::new())
+    }
+}
+
+impl KeypairWalletAdapter {
+    pub fn new(keypair: Keypair) -> Self {
+        Self {
+            keypair,
+            connected: false.into(),
+            connection: None,
+            endpoint: None.into(),
+            bus: EventBus::new(),
+        }
+    }
+
+    /// Sends transactions through the connection, e.g. the one of the app, rather than through
+    /// the endpoint given on connect.
+    pub fn with_connection(mut self, connection: Connection) -> Self {
+        self.connection = Some(connection);
+        self
+    }
+
+    fn ensure_connected(&self) -> Result<(), WalletError> {
+        if self.is_connected() {
+            Ok(())
+        } else {
+            Err(WalletError::NotConnected)
+        }
+    }
+
+    fn sign(&self, transaction: WalletTransaction) -> Result<WalletTransaction, WalletError> {
+        self.ensure_connected()?;
+        let pubkey = self.keypair.pubkey();
+        match transaction {
+            WalletTransaction::Legacy(mut tx) => {
+                let blockhash = tx.message.recent_blockhash;
+                tx.try_partial_sign(&[&self.keypair], blockhash)
+                    .map_err(|e| {
+                        log::error!("Failed to sign transaction. {:?}", e);
+                        WalletError::SignTransaction
+                    })?;
+                Ok(WalletTransaction::Legacy(tx))
+            }
+            WalletTransaction::Versioned(mut tx) => {
+                let required = tx.message.header().num_required_signatures as usize;
+                let position = tx.message.static_account_keys()[..required]
+                    .iter()
+                    .position(|k| *k == pubkey)
+                    .ok_or_else(|| {
+                        log::error!("Transaction does not require a signature from {}.", pubkey);
+                        WalletError::SignTransaction
+                    })?;
+                tx.signatures.resize(required, Signature::default());
+                tx.signatures[position] = self.keypair.sign_message(&tx.message.serialize());
+                Ok(WalletTransaction::Versioned(tx))
+            }
+        }
+    }
+}
+
+impl WalletAdapterMetadata for KeypairWalletAdapter {
+    fn name(&self) -> &str {
+        KEYPAIR_WALLET_NAME
+    }
+
+    fn url(&self) -> &str {
+        ""
+    }
+
+    fn icon(&self) -> &str {
+        ""
+    }
+}
+
+#[async_trait(?Send)]
+impl WalletAdapterBase for KeypairWalletAdapter {
+    fn is_name(&self) -> bool {
+        true
+    }
+
+    fn is_connected(&self) -> bool {
+        *self.connected.lock().unwrap()
+    }
+
+    fn ready_state(&self) -> WalletReadyState {
+        WalletReadyState::Installed
+    }
+
+    fn pubkey(&self) -> Option<Pubkey> {
+        if self.is_connected() {
+            Some(self.keypair.pubkey())
+        } else {
+            None
+        }
+    }
+
+    fn connecting(&self) -> bool {
+        false
+    }
+
+    async fn connect(&self, options: ConnectOptions) -> Result<Pubkey, WalletError> {
+        if let Some(endpoint) = options.rpc_endpoint {
+            *self.endpoint.lock().unwrap() = Some(endpoint);
+        }
+        *self.connected.lock().unwrap() = true;
+        self.bus.emit(Event::Connect(self.keypair.pubkey()));
+        Ok(self.keypair.pubkey())
+    }
+
+    async fn disconnect(&self) {
+        *self.connected.lock().unwrap() = false;
+        self.bus.emit(Event::Disconnect);
+    }
+
+    async fn sign_transaction(
+        &self,
+        transaction: WalletTransaction,
+    ) -> Result<WalletTransaction, WalletError> {
+        self.sign(transaction)
+    }
+
+    async fn sign_all_transactions(
+        &self,
+        transactions: Vec<WalletTransaction>,
+    ) -> Result<Vec<WalletTransaction>, WalletError> {
+        transactions.into_iter().map(|tx| self.sign(tx)).collect()
+    }
+
+    async fn sign_and_send_transaction(
+        &self,
+        transaction: WalletTransaction,
+    ) -> Result<Signature, WalletError> {
+        self.ensure_connected()?;
+        let connection = match &self.connection {
+            Some(connection) => connection.clone(),
+            None => {
+                let endpoint = self.endpoint.lock().unwrap().clone();
+                Connection::new(&endpoint.ok_or(WalletError::Config)?)
+            }
+        };
+
+        let rpc = connection.rpc();
+        match self.sign(transaction)? {
+            WalletTransaction::Legacy(tx) => rpc.send_transaction(&tx).await,
+            WalletTransaction::Versioned(tx) => rpc.send_versioned_transaction(&tx).await,
+        }
+        .map_err(|e| {
+            log::error!("Failed to send transaction. {:?}", e);
+            WalletError::SendTransaction
+        })
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature, WalletError> {
+        self.ensure_connected()?;
+        Ok(self.keypair.sign_message(message))
+    }
+
+    async fn sign_in(&self, input: SignInInput) -> Result<SignInOutput, WalletError> {
+        sign_in_with_message(self, &input).await
+    }
+}
+
+#[async_trait(?Send)]
+impl WalletAdapter for KeypairWalletAdapter {
+    fn priority_fees_supported(&self) -> bool {
+        true
+    }
+
+    fn capabilities(&self) -> WalletCapabilities {
+        WalletCapabilities {
+            supported_transaction_versions: DEFAULT_TRANSACTION_VERSIONS.to_vec(),
+            sign_message: true,
+            sign_in: true,
+            sign_and_send_transaction: true,
+            sign_all_transactions: true,
+        }
+    }
+
+    fn subscribe(&self) -> EventSubscription {
+        self.bus.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify_message;
+    use futures::executor::block_on;
+    use solana_client_wasm::solana_sdk::{
+        hash::Hash,
+        message::{v0, VersionedMessage},
+        system_instruction,
+        transaction::{Transaction, VersionedTransaction},
+    };
+
+    fn connected_adapter() -> KeypairWalletAdapter {
+        let adapter = KeypairWalletAdapter::default();
+        block_on(adapter.connect(ConnectOptions::default())).unwrap();
+        adapter
+    }
+
+    fn transfer(from: &Pubkey) -> Transaction {
+        let instruction = system_instruction::transfer(from, &Pubkey::new_unique(), 1);
+        let mut tx = Transaction::new_with_payer(&[instruction], Some(from));
+        tx.message.recent_blockhash = Hash::new_unique();
+        tx
+    }
+
+    #[test]
+    fn signs_legacy_transactions() {
+        let adapter = connected_adapter();
+        let tx = transfer(&adapter.pubkey().unwrap());
+
+        let WalletTransaction::Legacy(signed) =
+            block_on(adapter.sign_transaction(tx.into())).unwrap()
+        else {
+            panic!("Expected a legacy transaction.");
+        };
+        assert!(signed.verify().is_ok());
+    }
+
+    #[test]
+    fn signs_versioned_transactions() {
+        let adapter = connected_adapter();
+        let pubkey = adapter.pubkey().unwrap();
+        let instruction = system_instruction::transfer(&pubkey, &Pubkey::new_unique(), 1);
+        let message =
+            v0::Message::try_compile(&pubkey, &[instruction], &[], Hash::new_unique()).unwrap();
+        let tx = VersionedTransaction {
+            signatures: vec![],
+            message: VersionedMessage::V0(message),
+        };
+
+        let signed = block_on(adapter.sign_all_transactions(vec![tx.into()])).unwrap();
+        let WalletTransaction::Versioned(signed) = &signed[0] else {
+            panic!("Expected a versioned transaction.");
+        };
+        assert!(signed.verify_with_results().iter().all(|valid| *valid));
+    }
+
+    #[test]
+    fn signs_verifiable_messages() {
+        let adapter = connected_adapter();
+        let pubkey = adapter.pubkey().unwrap();
+
+        let signature = block_on(adapter.sign_message(b"Hello, Solana")).unwrap();
+        assert_eq!(
+            verify_message(&pubkey, b"Hello, Solana", &signature),
+            Ok(())
+        );
+        assert_eq!(
+            verify_message(&pubkey, b"Hello, Anvil", &signature),
+            Err(WalletError::SignMessage)
+        );
+    }
+
+    #[test]
+    fn rejects_requests_when_disconnected() {
+        let adapter = connected_adapter();
+        let tx = transfer(&adapter.pubkey().unwrap());
+        block_on(adapter.disconnect());
+
+        assert_eq!(adapter.pubkey(), None);
+        assert_eq!(
+            block_on(adapter.sign_transaction(tx.clone().into())),
+            Err(WalletError::NotConnected)
+        );
+        assert_eq!(
+            block_on(adapter.sign_all_transactions(vec![tx.clone().into()])),
+            Err(WalletError::NotConnected)
+        );
+        assert_eq!(
+            block_on(adapter.sign_and_send_transaction(tx.into())),
+            Err(WalletError::NotConnected)
+        );
+        assert_eq!(
+            block_on(adapter.sign_message(b"Hello, Solana")),
+            Err(WalletError::NotConnected)
+        );
+    }
+
+    #[test]
+    fn requires_an_endpoint_to_send() {
+        let adapter = connected_adapter();
+        let tx = transfer(&adapter.pubkey().unwrap());
+
+        assert_eq!(
+            block_on(adapter.sign_and_send_transaction(tx.into())),
+            Err(WalletError::Config)
+        );
+    }
+}
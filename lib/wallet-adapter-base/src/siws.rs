@@ -0,0 +1,473 @@
+use crate::{verify_message, ConnectOptions, WalletAdapterBase, WalletError};
+use solana_client_wasm::solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::str::FromStr;
+
+const HEADER_SUFFIX: &str = " wants you to sign in with your Solana account:";
+const RESOURCES_HEADER: &str = "Resources:";
+const RESOURCE_PREFIX: &str = "- ";
+
+/// The optional fields of a sign-in message, in the order they appear in it.
+const FIELD_PREFIXES: [&str; 8] = [
+    "URI: ",
+    "Version: ",
+    "Chain ID: ",
+    "Nonce: ",
+    "Issued At: ",
+    "Expiration Time: ",
+    "Not Before: ",
+    "Request ID: ",
+];
+
+/// The input to a Sign-In With Solana request.
+///
+/// Every field is optional, the wallet (or the `signMessage` fallback) fills in the domain
+/// and address when they are omitted. Datetimes are ISO 8601 strings.
+///
+/// ## Standard
+/// This is based on the `SolanaSignInInput` interface of the Solana wallet standard.
+///
+/// Read: https://github.com/phantom/sign-in-with-solana
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SignInInput {
+    pub domain: Option<String>,
+    pub address: Option<Pubkey>,
+    pub statement: Option<String>,
+    pub uri: Option<String>,
+    pub version: Option<String>,
+    pub chain_id: Option<String>,
+    pub nonce: Option<String>,
+    pub issued_at: Option<String>,
+    pub expiration_time: Option<String>,
+    pub not_before: Option<String>,
+    pub request_id: Option<String>,
+    pub resources: Vec<String>,
+}
+
+impl SignInInput {
+    /// Builds the message to sign, using the given domain and address when the input omits them.
+    pub fn to_message(&self, domain: &str, address: Pubkey) -> SignInMessage {
+        SignInMessage {
+            domain: self.domain.clone().unwrap_or(domain.to_string()),
+            address: self.address.unwrap_or(address),
+            statement: self.statement.clone(),
+            uri: self.uri.clone(),
+            version: self.version.clone(),
+            chain_id: self.chain_id.clone(),
+            nonce: self.nonce.clone(),
+            issued_at: self.issued_at.clone(),
+            expiration_time: self.expiration_time.clone(),
+            not_before: self.not_before.clone(),
+            request_id: self.request_id.clone(),
+            resources: self.resources.clone(),
+        }
+    }
+}
+
+/// The output of a Sign-In With Solana request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignInOutput {
+    /// The account that signed in.
+    pub account: Pubkey,
+    /// The exact message bytes that were signed.
+    pub signed_message: Vec<u8>,
+    pub signature: Signature,
+}
+
+/// A Sign-In With Solana message.
+///
+/// `to_string()` produces the canonical message text and `from_str()` parses it back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignInMessage {
+    pub domain: String,
+    pub address: Pubkey,
+    pub statement: Option<String>,
+    pub uri: Option<String>,
+    pub version: Option<String>,
+    pub chain_id: Option<String>,
+    pub nonce: Option<String>,
+    pub issued_at: Option<String>,
+    pub expiration_time: Option<String>,
+    pub not_before: Option<String>,
+    pub request_id: Option<String>,
+    pub resources: Vec<String>,
+}
+
+impl SignInMessage {
+    fn fields(&self) -> [&Option<String>; 8] {
+        [
+            &self.uri,
+            &self.version,
+            &self.chain_id,
+            &self.nonce,
+            &self.issued_at,
+            &self.expiration_time,
+            &self.not_before,
+            &self.request_id,
+        ]
+    }
+
+    fn field_mut(&mut self, index: usize) -> &mut Option<String> {
+        match index {
+            0 => &mut self.uri,
+            1 => &mut self.version,
+            2 => &mut self.chain_id,
+            3 => &mut self.nonce,
+            4 => &mut self.issued_at,
+            5 => &mut self.expiration_time,
+            6 => &mut self.not_before,
+            _ => &mut self.request_id,
+        }
+    }
+}
+
+impl std::fmt::Display for SignInMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}\n{}", self.domain, HEADER_SUFFIX, self.address)?;
+
+        if let Some(statement) = non_empty(&self.statement) {
+            write!(f, "\n\n{}", statement)?;
+        }
+
+        let mut fields: Vec<String> = FIELD_PREFIXES
+            .iter()
+            .zip(self.fields())
+            .filter_map(|(prefix, value)| non_empty(value).map(|v| format!("{}{}", prefix, v)))
+            .collect();
+        if !self.resources.is_empty() {
+            fields.push(RESOURCES_HEADER.to_string());
+            for resource in &self.resources {
+                fields.push(format!("{}{}", RESOURCE_PREFIX, resource));
+            }
+        }
+        if !fields.is_empty() {
+            write!(f, "\n\n{}", fields.join("\n"))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for SignInMessage {
+    type Err = WalletError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s.split('\n').collect();
+
+        let domain = lines
+            .first()
+            .and_then(|l| l.strip_suffix(HEADER_SUFFIX))
+            .filter(|d| !d.is_empty())
+            .ok_or(WalletError::SignIn)?;
+        let address = lines
+            .get(1)
+            .and_then(|a| Pubkey::from_str(a).ok())
+            .ok_or(WalletError::SignIn)?;
+        let mut message = Self {
+            domain: domain.to_string(),
+            address,
+            statement: None,
+            uri: None,
+            version: None,
+            chain_id: None,
+            nonce: None,
+            issued_at: None,
+            expiration_time: None,
+            not_before: None,
+            request_id: None,
+            resources: vec![],
+        };
+
+        // Each section after the address is preceded by a blank line
+        let mut rest = &lines[2..];
+        if rest.is_empty() {
+            return Ok(message);
+        }
+        rest = skip_separator(rest)?;
+
+        if let Some(line) = rest.first() {
+            if !is_field(line) {
+                message.statement = Some(line.to_string());
+                rest = &rest[1..];
+                if !rest.is_empty() {
+                    rest = skip_separator(rest)?;
+                }
+            }
+        }
+
+        // Fields must appear in canonical order, resources always come last
+        let mut next_field = 0;
+        for (i, line) in rest.iter().enumerate() {
+            if *line == RESOURCES_HEADER {
+                message.resources = rest[i + 1..]
+                    .iter()
+                    .map(|r| r.strip_prefix(RESOURCE_PREFIX).map(|r| r.to_string()))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or(WalletError::SignIn)?;
+                break;
+            }
+            let (index, value) = FIELD_PREFIXES[next_field..]
+                .iter()
+                .enumerate()
+                .find_map(|(j, prefix)| line.strip_prefix(prefix).map(|v| (next_field + j, v)))
+                .ok_or(WalletError::SignIn)?;
+            *message.field_mut(index) = Some(value.to_string());
+            next_field = index + 1;
+        }
+
+        Ok(message)
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().filter(|v| !v.is_empty())
+}
+
+fn is_field(line: &str) -> bool {
+    line == RESOURCES_HEADER || FIELD_PREFIXES.iter().any(|p| line.starts_with(p))
+}
+
+fn skip_separator<'a>(lines: &'a [&'a str]) -> Result<&'a [&'a str], WalletError> {
+    match lines.split_first() {
+        Some((&"", rest)) if !rest.is_empty() => Ok(rest),
+        _ => Err(WalletError::SignIn),
+    }
+}
+
+/// Signs in by building the message locally and signing it with `sign_message`,
+/// for wallets that do not implement `signIn` natively.
+pub async fn sign_in_with_message<A: WalletAdapterBase + ?Sized>(
+    adapter: &A,
+    input: &SignInInput,
+) -> Result<SignInOutput, WalletError> {
+    let account = match adapter.pubkey() {
+        Some(account) => account,
+        None => adapter.connect(ConnectOptions::default()).await?,
+    };
+    let domain = input
+        .domain
+        .clone()
+        .or_else(|| web_sys::window().and_then(|w| w.location().host().ok()))
+        .ok_or(WalletError::SignIn)?;
+
+    let signed_message = input.to_message(&domain, account).to_string().into_bytes();
+    let signature = adapter.sign_message(&signed_message).await?;
+
+    Ok(SignInOutput {
+        account,
+        signed_message,
+        signature,
+    })
+}
+
+/// Verifies that a sign-in output matches the input it was requested with,
+/// and that the message was signed by the account that signed in.
+pub fn verify_sign_in(input: &SignInInput, output: &SignInOutput) -> Result<(), WalletError> {
+    let text = std::str::from_utf8(&output.signed_message).map_err(|_| WalletError::SignIn)?;
+    let message = SignInMessage::from_str(text)?;
+
+    if message.address != output.account {
+        return Err(WalletError::SignIn);
+    }
+    if input.address.is_some_and(|a| a != message.address) {
+        return Err(WalletError::SignIn);
+    }
+    if input.domain.as_ref().is_some_and(|d| *d != message.domain) {
+        return Err(WalletError::SignIn);
+    }
+
+    // Wallets may add fields the input omitted, e.g. the nonce or issued at time
+    let provided_match =
+        |expected: &Option<String>, actual: &Option<String>| match non_empty(expected) {
+            Some(expected) => non_empty(actual) == Some(expected),
+            None => true,
+        };
+    let expected = input.to_message(&message.domain, message.address);
+    let fields_match = provided_match(&expected.statement, &message.statement)
+        && expected
+            .fields()
+            .iter()
+            .zip(message.fields())
+            .all(|(a, b)| provided_match(a, b))
+        && (expected.resources.is_empty() || expected.resources == message.resources);
+    if !fields_match {
+        return Err(WalletError::SignIn);
+    }
+
+    verify_message(&output.account, &output.signed_message, &output.signature)
+        .map_err(|_| WalletError::SignIn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_client_wasm::solana_sdk::signer::{keypair::Keypair, Signer};
+
+    fn full_message() -> SignInMessage {
+        SignInMessage {
+            domain: "anvil.so".to_string(),
+            address: Pubkey::new_unique(),
+            statement: Some("Sign in to Anvil".to_string()),
+            uri: Some("https://anvil.so/login".to_string()),
+            version: Some("1".to_string()),
+            chain_id: Some("mainnet".to_string()),
+            nonce: Some("oBbLoEldZs".to_string()),
+            issued_at: Some("2024-01-01T00:00:00.000Z".to_string()),
+            expiration_time: Some("2024-01-02T00:00:00.000Z".to_string()),
+            not_before: Some("2024-01-01T00:00:00.000Z".to_string()),
+            request_id: Some("1".to_string()),
+            resources: vec![
+                "https://anvil.so/terms".to_string(),
+                "ipfs://bafybeiemxf5abjwjbikoz4mc3a3dla6ual3jsgpdr4cjr3oz3evfyavhwq".to_string(),
+            ],
+        }
+    }
+
+    fn sign_in(keypair: &Keypair, message: &SignInMessage) -> SignInOutput {
+        let signed_message = message.to_string().into_bytes();
+        SignInOutput {
+            account: keypair.pubkey(),
+            signature: keypair.sign_message(&signed_message),
+            signed_message,
+        }
+    }
+
+    #[test]
+    fn round_trips_full_message() {
+        let message = full_message();
+        let text = message.to_string();
+
+        assert!(text.starts_with(&format!(
+            "anvil.so wants you to sign in with your Solana account:\n{}\n\nSign in to Anvil\n\nURI: ",
+            message.address
+        )));
+        assert!(text.ends_with("Resources:\n- https://anvil.so/terms\n- ipfs://bafybeiemxf5abjwjbikoz4mc3a3dla6ual3jsgpdr4cjr3oz3evfyavhwq"));
+        assert_eq!(SignInMessage::from_str(&text).unwrap(), message);
+    }
+
+    #[test]
+    fn round_trips_minimal_messages() {
+        let mut message = SignInInput::default().to_message("anvil.so", Pubkey::new_unique());
+        assert_eq!(
+            SignInMessage::from_str(&message.to_string()).unwrap(),
+            message
+        );
+
+        message.statement = Some("Sign in to Anvil".to_string());
+        assert_eq!(
+            SignInMessage::from_str(&message.to_string()).unwrap(),
+            message
+        );
+
+        message.statement = None;
+        message.nonce = Some("oBbLoEldZs".to_string());
+        assert_eq!(
+            SignInMessage::from_str(&message.to_string()).unwrap(),
+            message
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_messages() {
+        let address = Pubkey::new_unique();
+        let header = format!("anvil.so{}", HEADER_SUFFIX);
+        let malformed = [
+            String::new(),
+            format!("anvil.so wants you to sign in:\n{}", address),
+            format!("{}\n{}", HEADER_SUFFIX, address),
+            format!("{}\nnot-an-address", header),
+            format!("{}\n{}\nSign in to Anvil", header, address),
+            format!("{}\n{}\n", header, address),
+            format!("{}\n{}\n\nNonce: 1\nVersion: 1", header, address),
+            format!("{}\n{}\n\nNonce: 1\nUnknown: 1", header, address),
+            format!("{}\n{}\n\nResources:\nhttps://anvil.so", header, address),
+        ];
+
+        for text in malformed {
+            assert_eq!(
+                SignInMessage::from_str(&text),
+                Err(WalletError::SignIn),
+                "{text:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn verifies_matching_sign_in() {
+        let keypair = Keypair::new();
+        let mut message = full_message();
+        message.address = keypair.pubkey();
+        let input = SignInInput {
+            domain: Some(message.domain.clone()),
+            address: Some(message.address),
+            statement: message.statement.clone(),
+            uri: message.uri.clone(),
+            version: message.version.clone(),
+            chain_id: message.chain_id.clone(),
+            nonce: message.nonce.clone(),
+            issued_at: message.issued_at.clone(),
+            expiration_time: message.expiration_time.clone(),
+            not_before: message.not_before.clone(),
+            request_id: message.request_id.clone(),
+            resources: message.resources.clone(),
+        };
+
+        assert_eq!(verify_sign_in(&input, &sign_in(&keypair, &message)), Ok(()));
+    }
+
+    #[test]
+    fn allows_fields_the_input_omitted() {
+        let keypair = Keypair::new();
+        let mut message = full_message();
+        message.address = keypair.pubkey();
+        let input = SignInInput {
+            statement: message.statement.clone(),
+            ..Default::default()
+        };
+
+        assert_eq!(verify_sign_in(&input, &sign_in(&keypair, &message)), Ok(()));
+    }
+
+    #[test]
+    fn rejects_mismatched_sign_in() {
+        let keypair = Keypair::new();
+        let mut message = full_message();
+        message.address = keypair.pubkey();
+        let output = sign_in(&keypair, &message);
+
+        let mismatched = [
+            SignInInput {
+                domain: Some("evil.so".to_string()),
+                ..Default::default()
+            },
+            SignInInput {
+                address: Some(Pubkey::new_unique()),
+                ..Default::default()
+            },
+            SignInInput {
+                nonce: Some("another".to_string()),
+                ..Default::default()
+            },
+            SignInInput {
+                resources: vec!["https://anvil.so/terms".to_string()],
+                ..Default::default()
+            },
+        ];
+        for input in mismatched {
+            assert_eq!(verify_sign_in(&input, &output), Err(WalletError::SignIn));
+        }
+
+        let mut forged = output.clone();
+        forged.account = Pubkey::new_unique();
+        assert_eq!(
+            verify_sign_in(&SignInInput::default(), &forged),
+            Err(WalletError::SignIn)
+        );
+
+        let mut tampered = output;
+        tampered.signature = keypair.sign_message(b"something else");
+        assert_eq!(
+            verify_sign_in(&SignInInput::default(), &tampered),
+            Err(WalletError::SignIn)
+        );
+    }
+}
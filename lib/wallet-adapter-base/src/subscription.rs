@@ -0,0 +1,622 @@
+use crate::WithContext;
+use futures::{channel::mpsc, Stream, StreamExt};
+use gloo_events::EventListener;
+use serde_json::{json, Value};
+use solana_client_wasm::{
+    solana_sdk::{
+        account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature,
+    },
+    utils::{
+        rpc_config::{RpcAccountInfoConfig, RpcKeyedAccount, RpcTransactionLogsFilter},
+        rpc_response::{RpcLogsResponse, RpcSignatureResult, SlotInfo},
+    },
+};
+use solana_extra_wasm::account_decoder::{UiAccount, UiAccountEncoding};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    pin::Pin,
+    rc::{Rc, Weak},
+    task::{Context, Poll},
+};
+use wasm_bindgen::JsCast;
+use web_sys::{MessageEvent, WebSocket};
+
+/// How long to wait before reconnecting, in milliseconds, doubled after every failed attempt.
+const RECONNECT_DELAY_MS: u32 = 1_000;
+
+const MAX_RECONNECT_DELAY_MS: u32 = 30_000;
+
+/// The subscriptions of the Solana PubSub API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriptionKind {
+    Account,
+    Signature,
+    Slot,
+    Logs,
+    Program,
+}
+
+impl SubscriptionKind {
+    fn subscribe_method(&self) -> &'static str {
+        match self {
+            Self::Account => "accountSubscribe",
+            Self::Signature => "signatureSubscribe",
+            Self::Slot => "slotSubscribe",
+            Self::Logs => "logsSubscribe",
+            Self::Program => "programSubscribe",
+        }
+    }
+
+    fn unsubscribe_method(&self) -> &'static str {
+        match self {
+            Self::Account => "accountUnsubscribe",
+            Self::Signature => "signatureUnsubscribe",
+            Self::Slot => "slotUnsubscribe",
+            Self::Logs => "logsUnsubscribe",
+            Self::Program => "programUnsubscribe",
+        }
+    }
+}
+
+struct Subscription {
+    kind: SubscriptionKind,
+    params: Value,
+    /// The id the node gave the subscription, `None` until it confirmed it.
+    id: Option<u64>,
+    /// The subscribe request the subscription waits an answer for, answers to earlier ones are
+    /// stale.
+    request_id: Option<u32>,
+    listeners: Vec<(u64, mpsc::UnboundedSender<Value>)>,
+}
+
+struct Socket {
+    ws: WebSocket,
+    _listeners: [EventListener; 3],
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        let _ = self.ws.close();
+    }
+}
+
+#[derive(Default)]
+struct SubscriptionsState {
+    socket: Option<Socket>,
+    open: bool,
+    /// Bumped on every connection, events of the previous sockets are ignored.
+    generation: u32,
+    reconnect_delay_ms: u32,
+    /// The subscriptions by method and params.
+    subscriptions: HashMap<String, Subscription>,
+    /// The subscriptions by the id the node gave them.
+    ids: HashMap<u64, String>,
+    /// The subscribe requests waiting for an answer, by request id.
+    requests: HashMap<u32, String>,
+    next_request_id: u32,
+    next_listener_id: u64,
+}
+
+impl SubscriptionsState {
+    fn send(&mut self, method: &str, params: &Value) -> Option<u32> {
+        let socket = self.socket.as_ref().filter(|_| self.open)?;
+        self.next_request_id += 1;
+        let mut request = json!({
+            "jsonrpc": "2.0",
+            "id": self.next_request_id,
+            "method": method,
+        });
+        if !params.is_null() {
+            request["params"] = params.clone();
+        }
+        if let Err(e) = socket.ws.send_with_str(&request.to_string()) {
+            log::error!("Failed to send {} over the WebSocket. {:?}", method, e);
+            return None;
+        }
+        Some(self.next_request_id)
+    }
+
+    fn subscribe(&mut self, key: &str) {
+        let Some(subscription) = self.subscriptions.get(key) else {
+            return;
+        };
+        let (method, params) = (
+            subscription.kind.subscribe_method(),
+            subscription.params.clone(),
+        );
+        let request_id = self.send(method, &params);
+        if let Some(request_id) = request_id {
+            self.requests.insert(request_id, key.to_string());
+        }
+        if let Some(subscription) = self.subscriptions.get_mut(key) {
+            subscription.request_id = request_id;
+        }
+    }
+
+    fn unsubscribe(&mut self, kind: SubscriptionKind, id: u64) {
+        self.ids.remove(&id);
+        self.send(kind.unsubscribe_method(), &json!([id]));
+    }
+
+    /// Takes the socket once the last subscription is gone, it is closed when dropped.
+    fn take_unused_socket(&mut self) -> Option<Socket> {
+        if !self.subscriptions.is_empty() {
+            return None;
+        }
+        self.open = false;
+        self.socket.take()
+    }
+}
+
+/// Subscriptions to the PubSub WebSocket of a connection, see [`crate::Connection::subscriptions`].
+///
+/// Identical subscriptions share a single one on the node, which is unsubscribed once the last of
+/// its streams is dropped. The WebSocket is opened with the first subscription, closed with the
+/// last one, and reopened after it drops, resubscribing everything.
+///
+/// Clones share the same WebSocket.
+#[derive(Clone)]
+pub struct SubscriptionManager {
+    endpoint: String,
+    state: Rc<RefCell<SubscriptionsState>>,
+}
+
+impl SubscriptionManager {
+    pub fn new(ws_endpoint: &str) -> Self {
+        Self {
+            endpoint: ws_endpoint.to_string(),
+            state: Default::default(),
+        }
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Whether the WebSocket is open.
+    pub fn is_connected(&self) -> bool {
+        self.state.borrow().open
+    }
+
+    /// Notifies the account every time it changes.
+    pub fn account_subscribe(
+        &self,
+        address: &Pubkey,
+        commitment: CommitmentConfig,
+    ) -> SubscriptionStream<Account> {
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(commitment),
+            ..Default::default()
+        };
+        self.subscribe(
+            SubscriptionKind::Account,
+            json!([address.to_string(), config]),
+            |value| {
+                serde_json::from_value::<WithContext<UiAccount>>(value)
+                    .ok()?
+                    .value
+                    .decode()
+            },
+        )
+    }
+
+    /// Notifies once the transaction reaches the commitment, the stream ends afterwards.
+    pub fn signature_subscribe(
+        &self,
+        signature: &Signature,
+        commitment: CommitmentConfig,
+    ) -> SubscriptionStream<RpcSignatureResult> {
+        self.subscribe(
+            SubscriptionKind::Signature,
+            json!([signature.to_string(), commitment]),
+            |value| {
+                serde_json::from_value::<WithContext<RpcSignatureResult>>(value)
+                    .ok()
+                    .map(|result| result.value)
+            },
+        )
+    }
+
+    /// Notifies every slot processed by the node.
+    pub fn slot_subscribe(&self) -> SubscriptionStream<SlotInfo> {
+        self.subscribe(SubscriptionKind::Slot, Value::Null, |value| {
+            serde_json::from_value(value).ok()
+        })
+    }
+
+    /// Notifies the logs of the transactions matching the filter.
+    pub fn logs_subscribe(
+        &self,
+        filter: RpcTransactionLogsFilter,
+        commitment: CommitmentConfig,
+    ) -> SubscriptionStream<RpcLogsResponse> {
+        self.subscribe(
+            SubscriptionKind::Logs,
+            json!([filter, commitment]),
+            |value| {
+                serde_json::from_value::<WithContext<RpcLogsResponse>>(value)
+                    .ok()
+                    .map(|result| result.value)
+            },
+        )
+    }
+
+    /// Notifies the accounts owned by the program every time one of them changes.
+    pub fn program_subscribe(
+        &self,
+        program_id: &Pubkey,
+        commitment: CommitmentConfig,
+    ) -> SubscriptionStream<RpcKeyedAccount> {
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(commitment),
+            ..Default::default()
+        };
+        self.subscribe(
+            SubscriptionKind::Program,
+            json!([program_id.to_string(), config]),
+            |value| {
+                serde_json::from_value::<WithContext<RpcKeyedAccount>>(value)
+                    .ok()
+                    .map(|result| result.value)
+            },
+        )
+    }
+
+    fn subscribe<T>(
+        &self,
+        kind: SubscriptionKind,
+        params: Value,
+        parse: fn(Value) -> Option<T>,
+    ) -> SubscriptionStream<T> {
+        let key = format!("{}{}", kind.subscribe_method(), params);
+        let (sender, receiver) = mpsc::unbounded();
+        let (listener_id, connect) = {
+            let mut state = self.state.borrow_mut();
+            state.next_listener_id += 1;
+            let listener_id = state.next_listener_id;
+
+            match state.subscriptions.get_mut(&key) {
+                Some(subscription) => subscription.listeners.push((listener_id, sender)),
+                None => {
+                    state.subscriptions.insert(
+                        key.clone(),
+                        Subscription {
+                            kind,
+                            params,
+                            id: None,
+                            request_id: None,
+                            listeners: vec![(listener_id, sender)],
+                        },
+                    );
+                    state.subscribe(&key);
+                }
+            }
+            (listener_id, state.socket.is_none())
+        };
+
+        if connect {
+            self.connect();
+        }
+
+        SubscriptionStream {
+            receiver,
+            parse,
+            _guard: SubscriptionGuard {
+                manager: self.clone(),
+                key,
+                listener_id,
+            },
+        }
+    }
+
+    fn unsubscribe(&self, key: &str, listener_id: u64) {
+        let socket = {
+            let mut state = self.state.borrow_mut();
+            let Some(subscription) = state.subscriptions.get_mut(key) else {
+                return;
+            };
+            subscription.listeners.retain(|(id, _)| *id != listener_id);
+            if !subscription.listeners.is_empty() {
+                return;
+            }
+
+            let subscription = state.subscriptions.remove(key).unwrap();
+            if let Some(id) = subscription.id {
+                state.unsubscribe(subscription.kind, id);
+            }
+            state.take_unused_socket()
+        };
+        // Closed once the borrow is released
+        drop(socket);
+    }
+
+    fn connect(&self) {
+        let ws = match WebSocket::new(&self.endpoint) {
+            Ok(ws) => ws,
+            Err(e) => {
+                log::error!("Failed to open WebSocket {}. {:?}", self.endpoint, e);
+                return;
+            }
+        };
+
+        let mut state = self.state.borrow_mut();
+        state.generation += 1;
+        let generation = state.generation;
+
+        let weak = Rc::downgrade(&self.state);
+        let on_open = EventListener::new(&ws, "open", move |_| {
+            Self::handle_open(&weak, generation);
+        });
+        let weak = Rc::downgrade(&self.state);
+        let on_message = EventListener::new(&ws, "message", move |event| {
+            let Some(data) = event
+                .dyn_ref::<MessageEvent>()
+                .and_then(|event| event.data().as_string())
+            else {
+                return;
+            };
+            Self::handle_message(&weak, generation, &data);
+        });
+        let weak = Rc::downgrade(&self.state);
+        let endpoint = self.endpoint.clone();
+        let on_close = EventListener::new(&ws, "close", move |_| {
+            if let Some(state) = weak.upgrade() {
+                let manager = SubscriptionManager {
+                    endpoint: endpoint.clone(),
+                    state,
+                };
+                manager.handle_close(generation);
+            }
+        });
+
+        state.open = false;
+        state.socket = Some(Socket {
+            ws,
+            _listeners: [on_open, on_message, on_close],
+        });
+    }
+
+    fn handle_open(state: &Weak<RefCell<SubscriptionsState>>, generation: u32) {
+        let Some(state) = state.upgrade() else {
+            return;
+        };
+        let mut state = state.borrow_mut();
+        if state.generation != generation {
+            return;
+        }
+
+        log::info!(
+            "WebSocket open, subscribing to {} subscriptions.",
+            state.subscriptions.len()
+        );
+        state.open = true;
+        state.reconnect_delay_ms = 0;
+        let keys: Vec<String> = state.subscriptions.keys().cloned().collect();
+        for key in keys {
+            state.subscribe(&key);
+        }
+    }
+
+    fn handle_message(state: &Weak<RefCell<SubscriptionsState>>, generation: u32, data: &str) {
+        let Some(state) = state.upgrade() else {
+            return;
+        };
+        let mut state = state.borrow_mut();
+        if state.generation != generation {
+            return;
+        }
+        let Ok(message) = serde_json::from_str::<Value>(data) else {
+            return;
+        };
+
+        // The answer to a subscribe request
+        if let Some(request_id) = message.get("id").and_then(|id| id.as_u64()) {
+            let Some(key) = state.requests.remove(&(request_id as u32)) else {
+                return;
+            };
+            let Some(id) = message.get("result").and_then(|id| id.as_u64()) else {
+                log::warn!("Subscription {} failed. {}", key, message["error"]);
+                return;
+            };
+            match state.subscriptions.get_mut(&key) {
+                Some(subscription) if subscription.request_id == Some(request_id as u32) => {
+                    subscription.id = Some(id);
+                    subscription.request_id = None;
+                    state.ids.insert(id, key);
+                }
+                // Dropped while subscribing, or subscribed again since
+                _ => {
+                    let kind = kind_of(&key);
+                    if let Some(kind) = kind {
+                        state.unsubscribe(kind, id);
+                    }
+                }
+            }
+            return;
+        }
+
+        // A notification
+        let params = &message["params"];
+        let Some(key) = params["subscription"]
+            .as_u64()
+            .and_then(|id| state.ids.get(&id))
+            .cloned()
+        else {
+            return;
+        };
+        let Some(subscription) = state.subscriptions.get_mut(&key) else {
+            return;
+        };
+        let result = &params["result"];
+        subscription
+            .listeners
+            .retain(|(_, sender)| sender.unbounded_send(result.clone()).is_ok());
+
+        // The node drops signature subscriptions once notified, which ends their streams
+        if subscription.kind == SubscriptionKind::Signature {
+            let subscription = state.subscriptions.remove(&key).unwrap();
+            if let Some(id) = subscription.id {
+                state.ids.remove(&id);
+            }
+            // The socket is dropped outside of its own listener
+            if let Some(socket) = state.take_unused_socket() {
+                wasm_bindgen_futures::spawn_local(async move { drop(socket) });
+            }
+        }
+    }
+
+    fn handle_close(&self, generation: u32) {
+        let delay_ms = {
+            let mut state = self.state.borrow_mut();
+            if state.generation != generation {
+                return;
+            }
+            state.open = false;
+            state.ids.clear();
+            state.requests.clear();
+            for subscription in state.subscriptions.values_mut() {
+                subscription.id = None;
+                subscription.request_id = None;
+            }
+            state.reconnect_delay_ms =
+                (state.reconnect_delay_ms * 2).clamp(RECONNECT_DELAY_MS, MAX_RECONNECT_DELAY_MS);
+            state.reconnect_delay_ms
+        };
+
+        // The socket is dropped outside of its own listener
+        let manager = self.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let socket = {
+                let mut state = manager.state.borrow_mut();
+                if state.generation != generation {
+                    return;
+                }
+                state.socket.take()
+            };
+            drop(socket);
+
+            if manager.state.borrow().subscriptions.is_empty() {
+                return;
+            }
+            log::warn!("WebSocket closed, reconnecting in {}ms.", delay_ms);
+            gloo_timers::future::TimeoutFuture::new(delay_ms).await;
+
+            let reconnect = {
+                let state = manager.state.borrow();
+                state.generation == generation && !state.subscriptions.is_empty()
+            };
+            if reconnect {
+                manager.connect();
+            }
+        });
+    }
+}
+
+fn kind_of(key: &str) -> Option<SubscriptionKind> {
+    [
+        SubscriptionKind::Account,
+        SubscriptionKind::Signature,
+        SubscriptionKind::Slot,
+        SubscriptionKind::Logs,
+        SubscriptionKind::Program,
+    ]
+    .into_iter()
+    .find(|kind| key.starts_with(kind.subscribe_method()))
+}
+
+impl std::fmt::Debug for SubscriptionManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.state.borrow();
+        f.debug_struct("SubscriptionManager")
+            .field("endpoint", &self.endpoint)
+            .field("open", &state.open)
+            .field("subscriptions", &state.subscriptions.len())
+            .finish()
+    }
+}
+
+/// Releases a stream's share of its subscription.
+struct SubscriptionGuard {
+    manager: SubscriptionManager,
+    key: String,
+    listener_id: u64,
+}
+
+impl Drop for SubscriptionGuard {
+    fn drop(&mut self) {
+        self.manager.unsubscribe(&self.key, self.listener_id);
+    }
+}
+
+/// The notifications of a subscription, which is released when the stream is dropped.
+pub struct SubscriptionStream<T> {
+    receiver: mpsc::UnboundedReceiver<Value>,
+    parse: fn(Value) -> Option<T>,
+    _guard: SubscriptionGuard,
+}
+
+impl<T> Stream for SubscriptionStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        loop {
+            match self.receiver.poll_next_unpin(cx) {
+                Poll::Ready(Some(value)) => {
+                    // Notifications which do not parse are skipped
+                    if let Some(item) = (self.parse)(value) {
+                        return Poll::Ready(Some(item));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "accountSubscribe[\"11111111111111111111111111111111\"]";
+
+    /// A subscription waiting for the answer to `request_id`, after an earlier request.
+    fn state(request_id: u32) -> Rc<RefCell<SubscriptionsState>> {
+        let mut state = SubscriptionsState::default();
+        let (sender, _) = mpsc::unbounded();
+        state.subscriptions.insert(
+            KEY.to_string(),
+            Subscription {
+                kind: SubscriptionKind::Account,
+                params: json!(["11111111111111111111111111111111"]),
+                id: None,
+                request_id: Some(request_id),
+                listeners: vec![(1, sender)],
+            },
+        );
+        state.requests.insert(request_id - 1, KEY.to_string());
+        state.requests.insert(request_id, KEY.to_string());
+        Rc::new(RefCell::new(state))
+    }
+
+    fn answer(state: &Rc<RefCell<SubscriptionsState>>, request_id: u32, id: u64) {
+        let message = json!({ "jsonrpc": "2.0", "id": request_id, "result": id });
+        SubscriptionManager::handle_message(&Rc::downgrade(state), 0, &message.to_string());
+    }
+
+    #[test]
+    fn ignores_answers_to_earlier_subscribe_requests() {
+        let state = state(2);
+
+        answer(&state, 1, 10);
+        assert_eq!(state.borrow().subscriptions[KEY].id, None);
+        assert!(state.borrow().ids.is_empty());
+
+        answer(&state, 2, 11);
+        assert_eq!(state.borrow().subscriptions[KEY].id, Some(11));
+        assert_eq!(state.borrow().ids.get(&11).map(String::as_str), Some(KEY));
+    }
+}
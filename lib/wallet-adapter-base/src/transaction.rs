@@ -0,0 +1,75 @@
+use crate::WalletError;
+use solana_client_wasm::solana_sdk::{
+    signature::Signature,
+    transaction::{Transaction, TransactionVersion, VersionedTransaction},
+};
+
+/// A transaction that can be handed to a wallet for signing.
+///
+/// ## Standard
+/// This mirrors the `TransactionOrVersionedTransaction` type as it was implemented in the typescript adapter.
+///
+/// Read: https://github.com/anza-xyz/wallet-adapter/blob/master/packages/core/base/src/transaction.ts
+#[derive(Debug, Clone, PartialEq)]
+pub enum WalletTransaction {
+    Legacy(Transaction),
+    Versioned(VersionedTransaction),
+}
+
+impl WalletTransaction {
+    pub fn is_versioned(&self) -> bool {
+        matches!(self, Self::Versioned(_))
+    }
+
+    pub fn version(&self) -> TransactionVersion {
+        match self {
+            Self::Legacy(_) => TransactionVersion::LEGACY,
+            Self::Versioned(tx) => tx.version(),
+        }
+    }
+
+    pub fn signatures(&self) -> &[Signature] {
+        match self {
+            Self::Legacy(tx) => &tx.signatures,
+            Self::Versioned(tx) => &tx.signatures,
+        }
+    }
+
+    /// Serializes the transaction into its wire format.
+    pub fn serialize(&self) -> Result<Vec<u8>, WalletError> {
+        match self {
+            Self::Legacy(tx) => bincode::serialize(tx),
+            Self::Versioned(tx) => bincode::serialize(tx),
+        }
+        .map_err(|e| {
+            log::error!("Failed to serialize transaction. {:?}", e);
+            WalletError::SignTransaction
+        })
+    }
+
+    /// Deserializes a transaction returned by a wallet, keeping the same kind as `self`.
+    pub fn deserialize_as(&self, bytes: &[u8]) -> Result<Self, WalletError> {
+        match self {
+            Self::Legacy(_) => bincode::deserialize::<Transaction>(bytes).map(Self::Legacy),
+            Self::Versioned(_) => {
+                bincode::deserialize::<VersionedTransaction>(bytes).map(Self::Versioned)
+            }
+        }
+        .map_err(|e| {
+            log::error!("Failed to deserialize signed transaction. {:?}", e);
+            WalletError::SignTransaction
+        })
+    }
+}
+
+impl From<Transaction> for WalletTransaction {
+    fn from(value: Transaction) -> Self {
+        Self::Legacy(value)
+    }
+}
+
+impl From<VersionedTransaction> for WalletTransaction {
+    fn from(value: VersionedTransaction) -> Self {
+        Self::Versioned(value)
+    }
+}
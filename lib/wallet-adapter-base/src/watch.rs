@@ -0,0 +1,139 @@
+use crate::{
+    ConnectOptions, Event, EventBus, EventSubscription, SignInInput, SignInOutput, WalletAdapter,
+    WalletAdapterBase, WalletAdapterMetadata, WalletCapabilities, WalletError, WalletReadyState,
+    WalletTransaction,
+};
+use async_trait::async_trait;
+use solana_client_wasm::solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::sync::Mutex;
+
+pub const WATCH_WALLET_NAME: &str = "Watch";
+
+pub const WATCH_WALLET_ICON: &str = "data:image/svg+xml;base64,PHN2ZyB4bWxucz0iaHR0cDovL3d3dy53My5vcmcvMjAwMC9zdmciIHdpZHRoPSIyNCIgaGVpZ2h0PSIyNCIgdmlld0JveD0iMCAwIDI0IDI0IiBmaWxsPSJub25lIiBzdHJva2U9IiNmMWY1ZjkiIHN0cm9rZS13aWR0aD0iMS41IiBzdHJva2UtbGluZWNhcD0icm91bmQiIHN0cm9rZS1saW5lam9pbj0icm91bmQiPjxwYXRoIGQ9Ik0yIDEyczMuNS03IDEwLTcgMTAgNyAxMCA3LTMuNSA3LTEwIDctMTAtNy0xMC03eiIvPjxjaXJjbGUgY3g9IjEyIiBjeT0iMTIiIHI9IjMiLz48L3N2Zz4=";
+
+/// A read-only wallet adapter for an arbitrary address.
+///
+/// It reports itself as connected as soon as it is created, so the app can display the
+/// balances and history of the address, but every signing request fails with
+/// [`WalletError::WatchOnly`].
+pub struct WatchWalletAdapter {
+    pubkey: Pubkey,
+    connected: Mutex<bool>,
+    bus: EventBus,
+}
+
+impl std::fmt::Debug for WatchWalletAdapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WatchWalletAdapter")
+            .field("pubkey", &format!("{}", self.pubkey))
+            .field("connected", &self.connected)
+            .finish()
+    }
+}
+
+impl WatchWalletAdapter {
+    pub fn new(pubkey: Pubkey) -> Self {
+        Self {
+            pubkey,
+            connected: true.into(),
+            bus: EventBus::new(),
+        }
+    }
+}
+
+impl WalletAdapterMetadata for WatchWalletAdapter {
+    fn name(&self) -> &str {
+        WATCH_WALLET_NAME
+    }
+
+    fn url(&self) -> &str {
+        ""
+    }
+
+    fn icon(&self) -> &str {
+        WATCH_WALLET_ICON
+    }
+}
+
+#[async_trait(?Send)]
+impl WalletAdapterBase for WatchWalletAdapter {
+    fn is_name(&self) -> bool {
+        true
+    }
+
+    fn is_connected(&self) -> bool {
+        *self.connected.lock().unwrap()
+    }
+
+    fn ready_state(&self) -> WalletReadyState {
+        WalletReadyState::Installed
+    }
+
+    fn pubkey(&self) -> Option<Pubkey> {
+        if self.is_connected() {
+            Some(self.pubkey)
+        } else {
+            None
+        }
+    }
+
+    fn connecting(&self) -> bool {
+        false
+    }
+
+    async fn connect(&self, _: ConnectOptions) -> Result<Pubkey, WalletError> {
+        *self.connected.lock().unwrap() = true;
+        self.bus.emit(Event::Connect(self.pubkey));
+        Ok(self.pubkey)
+    }
+
+    async fn disconnect(&self) {
+        *self.connected.lock().unwrap() = false;
+        self.bus.emit(Event::Disconnect);
+    }
+
+    async fn sign_transaction(
+        &self,
+        _: WalletTransaction,
+    ) -> Result<WalletTransaction, WalletError> {
+        Err(WalletError::WatchOnly)
+    }
+
+    async fn sign_all_transactions(
+        &self,
+        _: Vec<WalletTransaction>,
+    ) -> Result<Vec<WalletTransaction>, WalletError> {
+        Err(WalletError::WatchOnly)
+    }
+
+    async fn sign_and_send_transaction(
+        &self,
+        _: WalletTransaction,
+    ) -> Result<Signature, WalletError> {
+        Err(WalletError::WatchOnly)
+    }
+
+    async fn sign_message(&self, _: &[u8]) -> Result<Signature, WalletError> {
+        Err(WalletError::WatchOnly)
+    }
+
+    async fn sign_in(&self, _: SignInInput) -> Result<SignInOutput, WalletError> {
+        Err(WalletError::WatchOnly)
+    }
+}
+
+#[async_trait(?Send)]
+impl WalletAdapter for WatchWalletAdapter {
+    fn priority_fees_supported(&self) -> bool {
+        false
+    }
+
+    /// A watched address cannot sign anything.
+    fn capabilities(&self) -> WalletCapabilities {
+        WalletCapabilities::default()
+    }
+
+    fn subscribe(&self) -> EventSubscription {
+        self.bus.subscribe()
+    }
+}
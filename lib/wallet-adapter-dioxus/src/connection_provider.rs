@@ -1,9 +1,17 @@
-use crate::ConnectionContextState;
+use crate::{ConnectionContextState, EndpointsContextState};
 use dioxus::prelude::*;
-use solana_wallet_adapter_base::Connection;
+use solana_wallet_adapter_base::{Connection, ConnectionConfig, EndpointPool, ResponseCache};
+
+/// How often the endpoints are health-checked, in seconds.
+const HEALTH_CHECK_INTERVAL_SECS: u32 = 15;
 
 /// A connection provider.
 ///
+/// The connection goes to the best of the endpoints, which are health-checked periodically.
+/// It is rebuilt whenever the endpoints or the config change, or when it fails over to another
+/// endpoint. The reads made through `connection.rpc()` are cached when a cache is given, the
+/// cache outlives the rebuilt connections.
+///
 /// ## Usage
 ///
 /// ```
@@ -11,12 +19,55 @@ use solana_wallet_adapter_base::Connection;
 /// // do something with `connection.rpc()`
 /// ```
 #[component]
-pub fn ConnectionProvider(endpoint: Signal<String>, children: Element) -> Element {
-    let connection = use_memo(move || ConnectionContextState {
-        connection: Connection::new(&endpoint()),
+pub fn ConnectionProvider(
+    endpoints: Signal<Vec<String>>,
+    config: Signal<ConnectionConfig>,
+    cache: Option<ResponseCache>,
+    children: Element,
+) -> Element {
+    let pool = use_memo(move || EndpointPool::new(&endpoints()));
+    let mut current = use_signal(|| None);
+    let mut statuses = use_signal(Vec::new);
+    let connection = use_memo(move || {
+        // The best endpoint is picked again on failover
+        current.read();
+        let connection = Connection::new_with_endpoints(pool(), config());
+        ConnectionContextState {
+            connection: match &cache {
+                Some(cache) => connection.with_cache(cache.clone()),
+                None => connection,
+            },
+        }
+    });
+
+    // Health checks, failover shows up in `current` which rebuilds the connection
+    use_future(move || async move {
+        let mut elapsed_secs = 0;
+        loop {
+            let connection = connection.peek().connection.clone();
+            if elapsed_secs % HEALTH_CHECK_INTERVAL_SECS == 0 {
+                connection.check_endpoints().await;
+            }
+
+            // Requests sent through the connection also report failures in between checks
+            let pool = connection.endpoints();
+            let pool_statuses = pool.statuses();
+            if *statuses.peek() != pool_statuses {
+                statuses.set(pool_statuses);
+            }
+            let best = pool.best();
+            if *current.peek() != best {
+                log::info!("Switching RPC endpoint to {:?}", best);
+                current.set(best);
+            }
+
+            gloo_timers::future::TimeoutFuture::new(1_000).await;
+            elapsed_secs += 1;
+        }
     });
 
-    use_context_provider(|| connection().connection);
+    use_context_provider(|| connection);
+    use_context_provider(|| EndpointsContextState { current, statuses });
 
     rsx! {
         {children}
@@ -1,12 +1,16 @@
 mod connection_provider;
 mod use_connection;
 mod use_local_storage;
+mod use_sign_in;
+mod use_subscription;
 mod use_wallet;
 mod wallet_provider;
 
 pub use connection_provider::*;
 pub use use_connection::*;
 pub use use_local_storage::*;
+pub use use_sign_in::*;
+pub use use_subscription::*;
 pub use use_wallet::*;
 pub use wallet_provider::*;
 
@@ -1,11 +1,31 @@
 use dioxus::prelude::*;
-use solana_wallet_adapter_base::Connection;
+use solana_wallet_adapter_base::{Connection, EndpointStatus};
 
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct ConnectionContextState {
     pub connection: Connection,
 }
 
+/// The RPC endpoints of the connection.
+#[derive(Clone, Copy, PartialEq)]
+pub struct EndpointsContextState {
+    /// The endpoint requests go to.
+    pub current: Signal<Option<String>>,
+    /// The health of every endpoint, as last measured.
+    pub statuses: Signal<Vec<EndpointStatus>>,
+}
+
+/// Returns the current connection, components re-render when it changes.
 pub fn use_connection() -> Connection {
+    let connection = use_connection_state();
+    return connection().connection;
+}
+
+/// Returns the connection memo, for futures that keep running when the connection changes.
+pub fn use_connection_state() -> Memo<ConnectionContextState> {
+    return use_context();
+}
+
+pub fn use_endpoints() -> EndpointsContextState {
     return use_context();
 }
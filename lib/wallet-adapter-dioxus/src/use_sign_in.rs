@@ -0,0 +1,75 @@
+use crate::{use_wallet, WalletContextState, WalletState};
+use dioxus::prelude::*;
+use solana_wallet_adapter_base::{verify_sign_in, SignInInput, SignInOutput, WalletError};
+
+/// The state of a Sign-In With Solana request made through the selected wallet.
+#[derive(Clone, Copy, PartialEq)]
+pub struct UseSignIn {
+    wallet: Signal<Option<WalletState>>,
+    /// The verified output of the last successful sign-in.
+    pub output: Signal<Option<SignInOutput>>,
+    /// The error of the last failed sign-in.
+    pub error: Signal<Option<WalletError>>,
+    pub signing_in: Signal<bool>,
+}
+
+impl UseSignIn {
+    /// Requests the selected wallet to sign in and verifies its output against `input`.
+    pub fn sign_in(&self, input: SignInInput) {
+        let wallet = self.wallet;
+        let mut output = self.output;
+        let mut error = self.error;
+        let mut signing_in = self.signing_in;
+
+        spawn(async move {
+            let Some(wallet) = wallet.peek().clone() else {
+                log::info!("Wallet not selected!");
+                error.set(Some(WalletError::NotReady));
+                return;
+            };
+
+            signing_in.set(true);
+            let result = match wallet.sign_in(input.clone()).await {
+                Ok(r) => verify_sign_in(&input, &r).map(|_| r),
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(r) => {
+                    log::info!("Signed in as {}", r.account);
+                    output.set(Some(r));
+                    error.set(None);
+                }
+                Err(e) => {
+                    log::error!("Failed to sign in: {:?}", e);
+                    output.set(None);
+                    error.set(Some(e));
+                }
+            }
+            signing_in.set(false);
+        });
+    }
+}
+
+/// Creates a Sign-In With Solana handle bound to the wallet in the `WalletProvider` context.
+///
+/// ## Usage
+///
+/// ```
+/// let sign_in = use_sign_in();
+/// // on click
+/// sign_in.sign_in(SignInInput::default());
+/// // read `sign_in.output()` once it resolves
+/// ```
+pub fn use_sign_in() -> UseSignIn {
+    let WalletContextState { wallet, .. } = use_wallet();
+    let output = use_signal(|| None);
+    let error = use_signal(|| None);
+    let signing_in = use_signal(|| false);
+
+    UseSignIn {
+        wallet,
+        output,
+        error,
+        signing_in,
+    }
+}
@@ -0,0 +1,62 @@
+use crate::use_connection_state;
+use dioxus::prelude::*;
+use futures::StreamExt;
+use solana_client_wasm::solana_sdk::{account::Account, clock::Slot, pubkey::Pubkey};
+
+/// Subscribes to an account, the signal holds its state as of the last change.
+///
+/// `None` until the account changes, or while `address` is `None`. The subscription follows the
+/// address, a signal or memo, and the connection, and is released along with the component. Each
+/// change invalidates the cached reads of the account.
+///
+/// ## Usage
+///
+/// ```
+/// let address = use_memo(use_reactive((&address,), |(address,)| Some(address)));
+/// let account = use_account_subscription(address);
+/// ```
+pub fn use_account_subscription(
+    address: impl Into<ReadOnlySignal<Option<Pubkey>>>,
+) -> Signal<Option<Account>> {
+    let address = address.into();
+    let connection = use_connection_state();
+    let mut account = use_signal(|| None);
+
+    let _ = use_resource(move || async move {
+        let connection = connection().connection;
+        let address = address();
+        account.set(None);
+        let Some(address) = address else {
+            return;
+        };
+
+        let mut updates = connection
+            .subscriptions()
+            .account_subscribe(&address, connection.commitment());
+        while let Some(update) = updates.next().await {
+            // Reads of the account cached before the change are outdated
+            if let Some(cache) = connection.cache() {
+                cache.invalidate(&address);
+            }
+            account.set(Some(update));
+        }
+    });
+
+    account
+}
+
+/// The last slot processed by the node, `None` until the first one is notified.
+pub fn use_slot() -> Signal<Option<Slot>> {
+    let connection = use_connection_state();
+    let mut slot = use_signal(|| None);
+
+    let _ = use_resource(move || async move {
+        let connection = connection().connection;
+        let mut updates = connection.subscriptions().slot_subscribe();
+        while let Some(update) = updates.next().await {
+            slot.set(Some(update.slot));
+        }
+    });
+
+    slot
+}
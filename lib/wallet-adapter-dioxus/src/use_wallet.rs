@@ -1,9 +1,12 @@
 use std::sync::Arc;
 
 use dioxus::prelude::*;
-use solana_client_wasm::solana_sdk::pubkey::Pubkey;
+use solana_client_wasm::solana_sdk::{pubkey::Pubkey, signature::Signature};
 use solana_wallet_adapter::Wallet;
-use solana_wallet_adapter_base::{WalletAdapter, WalletReadyState};
+use solana_wallet_adapter_base::{
+    ConnectOptions, RedirectResponse, SignInInput, SignInOutput, WalletAdapter, WalletCapabilities,
+    WalletError, WalletReadyState, WalletTransaction,
+};
 
 pub struct WalletState {
     pub(crate) adapter: Option<Arc<dyn WalletAdapter>>,
@@ -44,9 +47,17 @@ impl WalletState {
         self.adapter.as_ref().map_or(false, |a| a.is_connected())
     }
 
-    pub async fn connect(&self, rpc_endpoint: Option<&str>) {
-        if let Some(adapter) = &self.adapter {
-            adapter.connect(rpc_endpoint).await
+    /// What the wallet supports, e.g. whether transactions using lookup tables can be signed.
+    pub fn capabilities(&self) -> WalletCapabilities {
+        self.adapter
+            .as_ref()
+            .map_or(WalletCapabilities::default(), |a| a.capabilities())
+    }
+
+    pub async fn connect(&self, options: ConnectOptions) -> Result<Pubkey, WalletError> {
+        match &self.adapter {
+            Some(adapter) => adapter.connect(options).await,
+            None => Err(WalletError::NotReady),
         }
     }
 
@@ -55,6 +66,50 @@ impl WalletState {
             adapter.disconnect().await
         }
     }
+
+    pub async fn sign_transaction(
+        &self,
+        transaction: WalletTransaction,
+    ) -> Result<WalletTransaction, WalletError> {
+        match &self.adapter {
+            Some(adapter) => adapter.sign_transaction(transaction).await,
+            None => Err(WalletError::NotReady),
+        }
+    }
+
+    pub async fn sign_all_transactions(
+        &self,
+        transactions: Vec<WalletTransaction>,
+    ) -> Result<Vec<WalletTransaction>, WalletError> {
+        match &self.adapter {
+            Some(adapter) => adapter.sign_all_transactions(transactions).await,
+            None => Err(WalletError::NotReady),
+        }
+    }
+
+    pub async fn sign_and_send_transaction(
+        &self,
+        transaction: WalletTransaction,
+    ) -> Result<Signature, WalletError> {
+        match &self.adapter {
+            Some(adapter) => adapter.sign_and_send_transaction(transaction).await,
+            None => Err(WalletError::NotReady),
+        }
+    }
+
+    pub async fn sign_message(&self, message: &[u8]) -> Result<Signature, WalletError> {
+        match &self.adapter {
+            Some(adapter) => adapter.sign_message(message).await,
+            None => Err(WalletError::NotReady),
+        }
+    }
+
+    pub async fn sign_in(&self, input: SignInInput) -> Result<SignInOutput, WalletError> {
+        match &self.adapter {
+            Some(adapter) => adapter.sign_in(input).await,
+            None => Err(WalletError::NotReady),
+        }
+    }
 }
 
 impl Clone for WalletState {
@@ -121,13 +176,56 @@ impl PartialEq for WalletState {
 }
 
 pub struct WalletContextState {
+    /// Whether the provider silently reconnects the persisted wallet on mount.
     pub auto_connect: bool,
-    pub wallets: Vec<Wallet>,
+    pub wallets: Signal<Vec<Wallet>>,
     pub wallet: Signal<Option<WalletState>>,
     pub pubkey: Signal<Option<Pubkey>>,
     pub connecting: Signal<bool>,
     pub connected: Signal<bool>,
     pub disconnecting: Signal<bool>,
+    /// The address watched by the watch-only wallet, persisted to local storage.
+    pub watch_address: Signal<Option<String>>,
+    /// The answer of a wallet app that redirected back to the app, e.g. a transaction signed
+    /// through a universal link. Take it once handled.
+    pub redirect_response: Signal<Option<Result<RedirectResponse, WalletError>>>,
+}
+
+impl WalletContextState {
+    /// Connects the selected wallet, flagging `connecting` while the request is pending.
+    pub async fn connect(&self, options: ConnectOptions) -> Result<Pubkey, WalletError> {
+        let Some(wallet) = self.wallet.peek().clone() else {
+            return Err(WalletError::NotReady);
+        };
+        let mut connecting = self.connecting;
+        let mut connected = self.connected;
+        let mut pubkey = self.pubkey;
+
+        connecting.set(true);
+        let result = wallet.connect(options).await;
+        if let Ok(pk) = &result {
+            connected.set(true);
+            pubkey.set(Some(*pk));
+        }
+        connecting.set(false);
+        result
+    }
+
+    /// Disconnects the selected wallet, flagging `disconnecting` while the request is pending.
+    pub async fn disconnect(&self) {
+        let Some(wallet) = self.wallet.peek().clone() else {
+            return;
+        };
+        let mut disconnecting = self.disconnecting;
+        let mut connected = self.connected;
+        let mut pubkey = self.pubkey;
+
+        disconnecting.set(true);
+        wallet.disconnect().await;
+        connected.set(false);
+        pubkey.set(None);
+        disconnecting.set(false);
+    }
 }
 
 impl Clone for WalletContextState {
@@ -140,6 +238,8 @@ impl Clone for WalletContextState {
             connected: self.connected.clone(),
             connecting: self.connecting.clone(),
             disconnecting: self.disconnecting.clone(),
+            watch_address: self.watch_address.clone(),
+            redirect_response: self.redirect_response.clone(),
         }
     }
 
@@ -151,19 +251,26 @@ impl Clone for WalletContextState {
         self.connected = source.connected.clone();
         self.connecting = source.connecting.clone();
         self.disconnecting = source.disconnecting.clone();
+        self.watch_address = source.watch_address.clone();
+        self.redirect_response = source.redirect_response.clone();
     }
 }
 
+// Every field is a signal handle, so the state can be moved into event handlers freely
+impl Copy for WalletContextState {}
+
 impl Default for WalletContextState {
     fn default() -> Self {
         Self {
             auto_connect: false,
-            wallets: vec![],
+            wallets: use_signal(|| vec![]),
             wallet: use_signal(|| None),
             pubkey: use_signal(|| None),
             connected: use_signal(|| false),
             connecting: use_signal(|| false),
             disconnecting: use_signal(|| false),
+            watch_address: use_signal(|| None),
+            redirect_response: use_signal(|| None),
         }
     }
 }
@@ -178,6 +285,11 @@ impl std::fmt::Debug for WalletContextState {
             .field("connected", &format!("{:?}", self.connected))
             .field("connecting", &format!("{:?}", self.connecting))
             .field("disconnecting", &format!("{:?}", self.disconnecting))
+            .field("watch_address", &format!("{:?}", self.watch_address))
+            .field(
+                "redirect_response",
+                &format!("{:?}", self.redirect_response),
+            )
             .finish()
     }
 }
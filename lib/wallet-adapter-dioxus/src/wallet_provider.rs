@@ -1,65 +1,149 @@
-use std::sync::Arc;
-use crate::{use_local_storage_opt, WalletContextState, WalletState};
+use std::{str::FromStr, sync::Arc};
+use crate::{use_local_storage_opt, ConnectionContextState, WalletContextState, WalletState};
 use dioxus::prelude::*;
-use solana_wallet_adapter::{IntoWalletAdapter, Wallet};
-use solana_wallet_adapter_base::{Event,WalletReadyState, WalletAdapter};
+use futures::StreamExt;
+use solana_wallet_adapter::{
+    standard::subscribe_standard_wallets, IntoWallet, IntoWalletAdapter, IntoWalletName, Wallet,
+    WalletRegistry,
+};
+use solana_client_wasm::solana_sdk::pubkey::Pubkey;
+use solana_wallet_adapter_base::{ConnectOptions, Event,WalletReadyState, WalletAdapter, WATCH_WALLET_NAME};
 
 pub const WALLET_NAME_KEY: &'static str = "wallet_name";
+pub const WATCH_ADDRESS_KEY: &'static str = "watch_address";
 
 #[component]
 pub fn WalletProvider(
-    wallets: Vec<Wallet>,
+    registry: WalletRegistry,
     auto_connect: bool,
     local_storage_key: Option<String>,
     children: Element,
 ) -> Element {
-    let wallet_states: Vec<_> = wallets
-        .iter()
-        .map(|w| WalletState {
-            adapter: Some(w.into_wallet_adapter()),
+    let wallets: Vec<Wallet> = registry.adapters().map(|a| a.as_wallet()).collect();
+    // The answer of the wallet app that opened the app again, if any, along with its name
+    let redirect = use_hook(|| {
+        registry.adapters().find_map(|adapter| {
+            adapter
+                .take_redirect_response()
+                .map(|response| (adapter.name().to_string(), response))
         })
-        .filter(|ws| ws.ready_state() != WalletReadyState::Unsupported)
-        .collect(); 
+    });
+    let mut registry = use_signal(|| registry);
+    // Bumped whenever an adapter reports a new ready state
+    let mut ready_state_changes = use_signal(|| 0usize);
+    let wallet_states = use_memo(move || {
+        ready_state_changes();
+        registry
+            .read()
+            .adapters()
+            .map(|adapter| WalletState {
+                adapter: Some(adapter.clone()),
+            })
+            .filter(|ws| ws.ready_state() != WalletReadyState::Unsupported)
+            .collect::<Vec<_>>()
+    });
+    let mut wallets = use_signal(|| wallets);
 
     let mut wallet_name =
         use_local_storage_opt::<String>(local_storage_key.unwrap_or(WALLET_NAME_KEY.to_string()));
+    let watch_address = use_local_storage_opt::<String>(WATCH_ADDRESS_KEY.to_string());
     let mut maybe_wallet_state = use_signal(|| None);
     let mut maybe_adapter: Signal<Option<Arc<dyn WalletAdapter>>> = use_signal(|| None);
     let mut maybe_pubkey = use_signal(|| None);
     let mut connected = use_signal(|| false);
-    let maybe_selected_wallet = use_signal(|| wallet_name());
-    let connecting = use_signal(|| false);
+    // The wallet that redirected back is the one the request was made with
+    let maybe_selected_wallet = use_signal(|| {
+        redirect
+            .as_ref()
+            .map(|(name, _)| name.clone())
+            .or_else(|| wallet_name())
+    });
+    let redirect_response = use_signal(|| {
+        redirect.map(|(name, response)| {
+            log::info!("{} answered through a redirect: {:?}", name, response);
+            response
+        })
+    });
+    // Silent connects sign for the cluster of the app connection, when there is one
+    let connection = try_use_context::<Memo<ConnectionContextState>>();
+    let endpoint = move || connection.map(|c| c.peek().connection.endpoint().to_string());
+    let mut connecting = use_signal(|| false);
     let disconnecting = use_signal(|| false);
+    let mut auto_connect_attempted = use_signal(|| false);
 
-    // Wallet event handling
-    use_future(move || async move {
-        loop {
-            if let Some(adapter) = maybe_adapter() {
-                // Spawn event listener
-                log::info!("Listening to events..");
-
-                adapter
-                    .on_event(&mut |msg| match msg {
-                        Event::Default => (),
-                        Event::Connect(pk) => {
-                            log::info!("Wallet Pubkey: {:?}", pk);
-                            // At this point we are connected
-                            connected.set(true);
-                            maybe_pubkey.set(Some(pk));
-                        }
-                        Event::Disconnect => {
-                            // At this point we are disconnected from the wallet
-                            connected.set(false);
-                            maybe_pubkey.set(None);
-                        }
-                        Event::Error(e) => {
-                            log::error!("Wallet error: {:?}", e);
+    // Wallet event handling, restarted with a fresh subscription whenever the adapter changes
+    let _ = use_resource(move || async move {
+        let Some(adapter) = maybe_adapter() else {
+            return;
+        };
+        log::info!("Listening to events..");
+
+        let mut events = adapter.subscribe();
+        while let Some(msg) = events.next().await {
+            match msg {
+                Event::Default => (),
+                Event::Connect(pk) => {
+                    log::info!("Wallet Pubkey: {:?}", pk);
+                    // At this point we are connected
+                    connected.set(true);
+                    maybe_pubkey.set(Some(pk));
+                }
+                Event::Disconnect => {
+                    // At this point we are disconnected from the wallet
+                    connected.set(false);
+                    maybe_pubkey.set(None);
+                }
+                Event::Error(e) if e.is_user_rejected() => {
+                    log::info!("Wallet request rejected by the user: {}", e);
+                }
+                Event::Error(e) => {
+                    log::error!("Wallet error: {}", e);
+                }
+                Event::ReadyStateChange(_) => (),
+                Event::AccountChanged(Some(pk)) => {
+                    log::info!("Wallet account changed: {:?}", pk);
+                    maybe_pubkey.set(Some(pk));
+                }
+                Event::AccountChanged(None) => {
+                    // The new account is not connected to the app yet, try to reconnect silently
+                    maybe_pubkey.set(None);
+                    let adapter = adapter.clone();
+                    spawn(async move {
+                        match adapter.connect(ConnectOptions::trusted(endpoint().as_deref())).await {
+                            Ok(pk) => maybe_pubkey.set(Some(pk)),
+                            Err(e) => log::info!("Failed to reconnect changed account: {}", e),
                         }
-                        Event::ReadyStateChange(_) => (),
-                    })
-                    .await;
+                    });
+                }
+            }
+        }
+    });
+
+    // Ready state tracking, extensions may inject their provider after the page loaded
+    let _ = use_resource(move || async move {
+        let subscriptions: Vec<_> = registry.read().adapters().map(|a| a.subscribe()).collect();
+        let mut events = futures::stream::select_all(subscriptions);
+        while let Some(msg) = events.next().await {
+            if let Event::ReadyStateChange(ready_state) = msg {
+                log::info!("Wallet ready state changed: {:?}", ready_state);
+                *ready_state_changes.write() += 1;
             }
-            gloo_timers::future::TimeoutFuture::new(100).await;
+        }
+    });
+
+    // Wallet standard discovery, registered wallets replace the legacy adapter of the same name
+    use_future(move || async move {
+        let mut registrations = subscribe_standard_wallets();
+        while let Some(info) = registrations.next().await {
+            let wallet = Wallet::from(info);
+            let name = wallet.into_wallet_name().to_string();
+            log::info!("Discovered standard wallet: {:?}", name);
+
+            if !registry.write().register_wallet(&wallet) {
+                continue;
+            }
+            wallets.write().retain(|w| w.into_wallet_name() != name);
+            wallets.write().push(wallet);
         }
     });
 
@@ -69,10 +153,20 @@ pub fn WalletProvider(
         if let Some(selected_wallet) = maybe_selected_wallet() {
             log::info!("Wallet Name: {:?}", selected_wallet,);
 
-            let wallet_state = wallet_states
-                .iter()
-                .find(|w| w.name() == selected_wallet)
-                .map_or(None, |w| Some(w.clone()));
+            // The watch-only wallet is built from the persisted address rather than the wallet list
+            let wallet_state = if selected_wallet == WATCH_WALLET_NAME {
+                watch_address()
+                    .and_then(|a| Pubkey::from_str(&a).ok())
+                    .map(|pubkey| WalletState {
+                        adapter: Wallet::Watch(pubkey).into_wallet_adapter(),
+                    })
+            } else {
+                wallet_states
+                    .read()
+                    .iter()
+                    .find(|w| w.name() == selected_wallet)
+                    .map_or(None, |w| Some(w.clone()))
+            };
             log::info!("Wallet State: {:?}", wallet_state);
 
             if let Some(wallet_state) = &wallet_state {
@@ -84,33 +178,91 @@ pub fn WalletProvider(
                     let pubkey = adapter.pubkey();
                     log::info!("Selected Wallet Pubkey: {:?}", pubkey);
 
+                    // Events emitted before the subscription are missed, so sync the current state
+                    connected.set(adapter.is_connected());
                     maybe_pubkey.set(pubkey);
                 }
 
+                // End the session of the previous wallet, its listeners would keep emitting
+                let previous = maybe_adapter.peek().clone();
+                if let Some(previous) = previous.filter(|p| {
+                    wallet_state.adapter.as_ref().map_or(true, |a| {
+                        Arc::as_ptr(a) as *const () != Arc::as_ptr(p) as *const ()
+                    })
+                }) {
+                    if previous.is_connected() {
+                        log::info!("Disconnecting from {}..", previous.name());
+                        spawn(async move {
+                            previous.disconnect().await;
+                            log::info!(
+                                "{} listeners: {:?}",
+                                previous.name(),
+                                previous.listener_status()
+                            );
+                        });
+                    }
+                }
+
                 maybe_adapter.set(None);
                 maybe_adapter.set(wallet_state.adapter.clone());
             }
 
             maybe_wallet_state.set(wallet_state);
 
-            if let Some(wn) = prev_wallet_name {
-                if wn != selected_wallet {
-                    wallet_name.set(Some(selected_wallet));
-                }
+            // Persist the selection, auto-connect picks it up on the next visit
+            if prev_wallet_name.as_deref() != Some(selected_wallet.as_str()) {
+                wallet_name.set(Some(selected_wallet));
+            }
+        }
+    });
+
+    // Auto-connect, silently reconnects the persisted wallet once it has been detected
+    let _ = use_resource(move || async move {
+        // Re-run when a late-injected wallet becomes ready
+        ready_state_changes();
+        let Some(adapter) = maybe_adapter() else {
+            return;
+        };
+        if !auto_connect || *auto_connect_attempted.peek() {
+            return;
+        }
+        if wallet_name.peek().as_deref() != Some(adapter.name()) {
+            return;
+        }
+        if adapter.is_connected() || adapter.ready_state() != WalletReadyState::Installed {
+            return;
+        }
+        auto_connect_attempted.set(true);
+
+        log::info!("Auto-connecting to {}..", adapter.name());
+        connecting.set(true);
+        match adapter.connect(ConnectOptions::trusted(endpoint().as_deref())).await {
+            Ok(pk) => {
+                connected.set(true);
+                maybe_pubkey.set(Some(pk));
+            }
+            Err(e) => {
+                // The wallet does not trust the app yet, wait for the user to connect
+                log::info!("Auto-connect requires user interaction: {}", e);
+                connected.set(false);
+                maybe_pubkey.set(None);
             }
         }
+        connecting.set(false);
     });
 
     // Connect
     use_context_provider(|| maybe_selected_wallet);
     use_context_provider(|| WalletContextState {
-        auto_connect: false,
+        auto_connect,
         wallets,
         wallet: maybe_wallet_state,
         pubkey: maybe_pubkey,
         connected,
         connecting,
         disconnecting,
+        watch_address,
+        redirect_response,
     });
 
     rsx! {
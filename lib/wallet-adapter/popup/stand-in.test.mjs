@@ -0,0 +1,195 @@
+// Drives the stand-in page with the messages `PopupWindow` posts. Run with `node --test`.
+import assert from "node:assert/strict";
+import { readFileSync } from "node:fs";
+import { test } from "node:test";
+import vm from "node:vm";
+
+const BASE58_ALPHABET = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const APP_ORIGIN = "http://localhost:8080";
+
+const script = readFileSync(new URL("./stand-in.html", import.meta.url), "utf8").match(
+  /<script>([\s\S]*)<\/script>/,
+)[1];
+
+function fromBase58(text) {
+  const bytes = [];
+  for (const char of text) {
+    let carry = BASE58_ALPHABET.indexOf(char);
+    assert.ok(carry >= 0, `invalid base58 character ${char}`);
+    for (let i = 0; i < bytes.length; i++) {
+      carry += bytes[i] * 58;
+      bytes[i] = carry & 0xff;
+      carry >>= 8;
+    }
+    while (carry > 0) {
+      bytes.push(carry & 0xff);
+      carry >>= 8;
+    }
+  }
+  for (let i = 0; i < text.length && text[i] === "1"; i++) {
+    bytes.push(0);
+  }
+  return new Uint8Array(bytes.reverse());
+}
+
+// Loads the page as the popup of an app, returning a client posting requests to it.
+// Messages are cloned on their way, as browsers do between windows.
+async function openPopup({ search = "", fetch } = {}) {
+  const listeners = [];
+  let ready;
+  const readyMessage = new Promise((resolve) => (ready = resolve));
+  const window = {
+    addEventListener: (type, listener) => type === "message" && listeners.push(listener),
+    opener: {
+      postMessage: (message, targetOrigin) =>
+        ready({ message: structuredClone(message), targetOrigin }),
+    },
+  };
+  vm.runInNewContext(script, {
+    window,
+    location: { search },
+    crypto: globalThis.crypto,
+    URLSearchParams,
+    Uint8Array,
+    btoa,
+    fetch,
+  });
+  assert.deepEqual((await readyMessage).message, { type: "ready" });
+
+  let nextId = 0;
+  return async (method, params) => {
+    const id = ++nextId;
+    const response = new Promise((resolve) => {
+      const source = {
+        postMessage: (message, origin) => {
+          assert.equal(origin, APP_ORIGIN);
+          resolve(structuredClone(message));
+        },
+      };
+      for (const listener of listeners) {
+        listener({ data: structuredClone({ id, method, params }), source, origin: APP_ORIGIN });
+      }
+    });
+    const message = await response;
+    assert.equal(message.id, id);
+    return message;
+  };
+}
+
+async function verify(publicKey, signature, message) {
+  const key = await crypto.subtle.importKey("raw", publicKey, "Ed25519", false, ["verify"]);
+  return crypto.subtle.verify("Ed25519", key, signature, message);
+}
+
+// A transaction transferring from `payer`, with `signatures` empty slots.
+function transaction(payer, { versioned = false, signatures = 1 } = {}) {
+  const other = crypto.getRandomValues(new Uint8Array(32));
+  const blockhash = crypto.getRandomValues(new Uint8Array(32));
+  const message = [
+    ...(versioned ? [0x80] : []),
+    1,
+    0,
+    1,
+    2,
+    ...payer,
+    ...other,
+    ...blockhash,
+    0,
+    ...(versioned ? [0] : []),
+  ];
+  return {
+    message: new Uint8Array(message),
+    bytes: new Uint8Array([signatures, ...new Array(signatures * 64).fill(0), ...message]),
+  };
+}
+
+async function connect(request) {
+  const { result } = await request("connect", {});
+  return fromBase58(result.publicKey);
+}
+
+test("connects with a base58 public key", async () => {
+  const request = await openPopup();
+  const publicKey = await connect(request);
+  assert.equal(publicKey.length, 32);
+});
+
+test("signs messages", async () => {
+  const request = await openPopup();
+  const publicKey = await connect(request);
+  const message = new TextEncoder().encode("Hello, Solana");
+
+  const { result } = await request("signMessage", { message });
+  assert.ok(result.signature instanceof Uint8Array);
+  assert.ok(await verify(publicKey, result.signature, message));
+});
+
+test("signs legacy and versioned transactions", async () => {
+  const request = await openPopup();
+  const publicKey = await connect(request);
+  const legacy = transaction(publicKey);
+  const versioned = transaction(publicKey, { versioned: true, signatures: 0 });
+
+  const { result } = await request("signAllTransactions", {
+    transactions: [legacy.bytes, versioned.bytes],
+  });
+  for (const [i, signed] of result.transactions.entries()) {
+    const { message } = [legacy, versioned][i];
+    // A single signature slot, followed by the untouched message
+    assert.equal(signed[0], 1);
+    assert.deepEqual(signed.subarray(65), message);
+    assert.ok(await verify(publicKey, signed.subarray(1, 65), message));
+  }
+});
+
+test("rejects transactions it does not sign", async () => {
+  const request = await openPopup();
+  await connect(request);
+  const { bytes } = transaction(crypto.getRandomValues(new Uint8Array(32)));
+
+  const { error } = await request("signTransaction", { transaction: bytes });
+  assert.equal(error.code, -32003);
+});
+
+test("sends signed transactions to the endpoint given on connect", async () => {
+  let sent;
+  const request = await openPopup({
+    fetch: async (url, init) => {
+      sent = { url, body: JSON.parse(init.body) };
+      return { json: async () => ({ jsonrpc: "2.0", id: 1, result: "signature" }) };
+    },
+  });
+  const { result } = await request("connect", { network: "http://localhost:8899" });
+  const publicKey = fromBase58(result.publicKey);
+  const { bytes, message } = transaction(publicKey);
+
+  const response = await request("signAndSendTransaction", { transaction: bytes });
+  assert.deepEqual(response.result, { signature: "signature" });
+  assert.equal(sent.url, "http://localhost:8899");
+  assert.equal(sent.body.method, "sendTransaction");
+  const signed = Uint8Array.from(atob(sent.body.params[0]), (c) => c.charCodeAt(0));
+  assert.ok(await verify(publicKey, signed.subarray(1, 65), message));
+});
+
+test("fails requests before connecting", async () => {
+  const request = await openPopup();
+  const message = new TextEncoder().encode("Hello, Solana");
+
+  assert.equal((await request("signMessage", { message })).error.code, 4100);
+  await connect(request);
+  await request("disconnect");
+  assert.equal((await request("signMessage", { message })).error.code, 4100);
+});
+
+test("rejects every request when loaded with ?reject", async () => {
+  const request = await openPopup({ search: "?reject" });
+  const { error } = await request("connect", {});
+  assert.deepEqual(error, { code: 4001, message: "User rejected the request." });
+});
+
+test("rejects unknown methods and malformed params", async () => {
+  const request = await openPopup();
+  assert.equal((await request("signIn", {})).error.code, -32601);
+  await connect(request);
+  assert.equal((await request("signMessage", { message: "text" })).error.code, -32602);
+});
@@ -1,224 +1,43 @@
-#![allow(non_upper_case_globals)]
-use self::wasmgen::backpack;
 use crate::{
-    proxy::WalletEventListener, IntoPubkey, IntoWalletIcon, IntoWalletName, IntoWalletUrl, Wallet,
+    injected::{
+        provider_capabilities, wasmgen::InjectedSolana, InjectedProvider, InjectedWalletAdapter,
+    },
+    to_js_connect_options, WasmTransaction,
 };
 use async_trait::async_trait;
-use futures::StreamExt;
-use solana_client_wasm::solana_sdk::pubkey::Pubkey;
-use solana_wallet_adapter_base::{
-    Event, WalletAdapter, WalletAdapterBase, WalletAdapterMetadata, WalletReadyState,
-};
-use std::sync::Mutex;
-
-pub(crate) mod wasmgen {
-    use wasm_bindgen::{prelude::*, JsStatic};
-
-    use crate::{JsPublicKey, WalletEventEmitter};
-
-    #[wasm_bindgen]
-    extern "C" {
-        pub type Backpack;
-
-        pub static backpack: Backpack;
-
-        #[wasm_bindgen(method, getter=isConnected)]
-        pub fn is_connected(this: &Backpack) -> bool;
-
-        #[wasm_bindgen(method, getter=isBackpack)]
-        pub fn is_backpack(this: &Backpack) -> bool;
-
-        #[wasm_bindgen(method, getter=priorityFeesSupported)]
-        pub fn priority_fees_supported(this: &Backpack) -> bool;
-
-        #[wasm_bindgen(method, getter=publicKey)]
-        pub fn pubkey(this: &Backpack) -> JsPublicKey;
-
-        #[wasm_bindgen(method)]
-        pub fn on(this: &Backpack, e: &str, cb: &Closure<dyn FnMut(web_sys::Event)>) -> bool;
-
-        #[wasm_bindgen(method, catch, js_name=connect)]
-        pub async fn connect(this: &Backpack, endpoint: &str) -> Result<JsValue, JsValue>;
-        #[wasm_bindgen(method, js_name=disconnect)]
-        pub async fn disconnect(this: &Backpack);
-
-        #[wasm_bindgen(method, catch, js_name=signMessage)]
-        pub async fn sign_message(
-            this: &Backpack,
-            message: Vec<u8>,
-            pubkey: Option<JsPublicKey>,
-        ) -> Result<JsValue, JsValue>;
-    }
-
-    impl WalletEventEmitter for JsStatic<Backpack> {
-        fn on_connect(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) {
-            self.on("connect", closure);
-        }
-
-        fn on_disconnect(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) {
-            self.on("disconnect", closure);
-        }
-
-        fn on_error(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) {
-            self.on("error", closure);
-        }
-
-        fn on_ready_state_change(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) {
-            self.on("readyStateChange", closure);
-        }
-    }
-}
+use solana_wallet_adapter_base::{ConnectOptions, WalletCapabilities};
+use wasm_bindgen::JsValue;
 
 #[derive(Debug)]
-pub struct BackpackWalletAdapter {
-    connecting: Mutex<bool>,
-    connected: Mutex<bool>,
-    pubkey: Pubkey,
-    wrs: WalletReadyState,
-    wel: Mutex<WalletEventListener>,
-}
-
-impl Clone for BackpackWalletAdapter {
-    fn clone(&self) -> Self {
-        Self {
-            connecting: self.connecting().into(),
-            connected: Mutex::new(*self.connected.lock().unwrap()),
-            pubkey: self.pubkey.clone(),
-            wrs: self.wrs.clone(),
-            wel: self.wel.lock().unwrap().clone().into(),
-        }
-    }
-
-    fn clone_from(&mut self, source: &Self) {
-        self.connecting = source.connecting().into();
-        self.connected = Mutex::new(*source.connected.lock().unwrap());
-        self.pubkey = source.pubkey.clone();
-        self.wel = source.wel.lock().unwrap().clone().into();
-        self.wrs = source.wrs.clone();
-    }
-}
-
-impl PartialEq for BackpackWalletAdapter {
-    fn eq(&self, other: &Self) -> bool {
-        self.pubkey == other.pubkey
-            && self.connecting() == other.connecting()
-            && *self.connected.lock().unwrap() == *other.connected.lock().unwrap()
-    }
-
-    fn ne(&self, other: &Self) -> bool {
-        self.pubkey != other.pubkey
-            && self.connecting() != other.connecting()
-            && *self.connected.lock().unwrap() != *other.connected.lock().unwrap()
-    }
-}
-
-impl BackpackWalletAdapter {
-    pub fn new() -> Self {
-        let pubkey = backpack.pubkey();
-        let wel = WalletEventListener::new(&backpack);
-        let wrs = if let Some(window) = web_sys::window() {
-            if let Some(_) = window.document() {
-                if backpack.is_undefined() {
-                    WalletReadyState::NotDetected
-                } else {
-                    WalletReadyState::Installed
-                }
-            } else {
-                WalletReadyState::Unsupported
-            }
-        } else {
-            WalletReadyState::Unsupported
-        };
-        Self {
-            connecting: false.into(),
-            connected: backpack.is_connected().into(),
-            pubkey: if pubkey.is_null() {
-                Pubkey::default()
-            } else {
-                pubkey.to_pubkey()
-            },
-            wrs,
-            wel: Mutex::new(wel),
-        }
-    }
-}
-
-impl WalletAdapterMetadata for BackpackWalletAdapter {
-    fn name(&self) -> &str {
-        Wallet::Backpack.into_wallet_name()
-    }
-
-    fn url(&self) -> &str {
-        Wallet::Backpack.into_wallet_url()
-    }
-
-    fn icon(&self) -> &str {
-        Wallet::Backpack.into_wallet_icon()
-    }
-}
+pub struct BackpackProvider;
 
 #[async_trait(?Send)]
-impl WalletAdapterBase for BackpackWalletAdapter {
-    fn is_name(&self) -> bool {
-        backpack.is_backpack()
-    }
-
-    fn is_connected(&self) -> bool {
-        backpack.is_connected()
-    }
+impl InjectedProvider for BackpackProvider {
+    const NAME: &'static str = "Backpack";
+    const URL: &'static str = "https://backpack.app/";
+    const ICON: &'static str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAIAAAACACAYAAADDPmHLAAAACXBIWXMAAAsTAAALEwEAmpwYAAAAAXNSR0IArs4c6QAAAARnQU1BAACxjwv8YQUAAAbvSURBVHgB7Z1dUtxGEMf/LZH3fU0V4PUJQg4QVj5BnBOAT2BzAsMJAicwPoHJCRDrAxifgLVxVV73ObDqdEtsjKn4C8+0NDv9e7AxprRC85uvnp4RYYW5qKpxCVTcYKsgfiDfGjMwIsZIvh7d/lkmzAiYy5fzhultyZhdlagf1vU5VhjCiiGFXq01zYSJdqWgx/hB5AHN5I/6iuilyFBjxVgZAdqCZ34ORoVIqAzSOhxsvq6PsSIkL4A281LwL2IW/F1UhLKgRz/X9QyJUyBhuuae31gWviLjiPF1wxeX29vPkTjJtgAftrd3GHSMnmHw4eZ0uodESVKAoRT+kpQlSE6Ats/XZv/ONK5vZHC49+B1fYjESG4MUDKfYmCFr0ic4fmHqtpCYiQlgA66QsztIzFi5j+RGMl0AXebfgn0aOTuvGG8owIarZsXOj3ronlRuEYnn84CJLo4Lgi/QL/H/LHmy/RwI6GA0RoS4acFHi8kGieFXS/QhmijFfQXmH3uPy5lSkoLbIkYlfyzhuM4juM4juM4juMMj6TzATQ4JH9tlRqFk8BM2aV9RWHB9K5kzK/KLui0KqliSQmgBa4BIS54cpMD0OeawFye3jk19JdKkWq62OAFkEIfrTXNUxBV1okf38Ot3MGjlFqHwQrQZvQ22Cfw7xjg6t8XkZaBGzpKIXdwcAJojZeCP5SC30HipJBEOigBZLn3qdzSPlKr8V9hyEmkgxCgj8zefuD9jen0AAOidwE0i6ZhfjXgRI+gDK016DUjqE3ubPhNLoWvaDLJouHToaSP9SbA0DJ7LekyiviNPgP0TC9dQM6FfxeZ7eyuT6cv0RPmAmjTx11uXx/MiegEDd425cfcwWV+H4O3+uiO+pTAVIA2uMN8av6QiWr5TQ++JVlTc/tEiF3jOMScZGC43kME0VSA95PJhWXhM+Gt1Phn98nStZa1r9mB2SDQPqefjhayfnDfFG2J5882z84eynVM5u3thlONhRhj0gLc5PRfwAw62JjW+wjE5Xa1L0VkshO4kXt/EPDev4ZJCyBRvlcwggjHG4EfYHc9OoIBBWy3mEUX4H1V7Ur7ZvILaT8qy7FRduleF9jXc4RggOUWs/gtANs0nYquvMXaMaTXlQHlE1ggayLvf5OKY0DUMYDWfmpsBjZa+9enOmiLy+VkcmqxaNW2ZgX9GnsLXNQWoGj4KYzQ2g8LyG5WUDR4hshEE6CN+AFmg5lFiRMYcI0uKRQGyIAwegWKJkBjYO8tzq12C7efQ7CK2I00MomIxOsCiCcwQhaW3sEQ6W7sPi/yIDqKAHp8m2nIF7COoc9ghQw4NU8SkYgiQCmLKXCCUSziPc84XYBh83/DSiWR3qUo2tT4ONdGYDTub73cSzD/PNt0rojdQHAByoXxw0E7XfoFhsjnRduD+DnWIkkXXACJl1cwRoMmf3cbRaOjLRzDXnKZVj9GBIILUJBtbVzyj9HAU19AgR6I9VzDtwCgMXpAo2Yxp0v/Ybi49ennJtIFEPMY/TCKHTvv+aTSUQzBgwrQ92YHbQVi3UN3GAVZhrf/jzECE1SAq/7n4yOJ074KPSBcJoii598vxgwrqAByg70HZJZbr0JJ0G5XZz5Z1e1rYccA5TAicqEk0O5ECl/3LvYys7mLTLHHCEzS7wz6Esv3+nyYTF58rwha63XAl8PG1aCnhesWq6EdOcKM3WvmXRHh+Gvv/tNVTJlJPC4a3RVEK72+sCSZ4+J/FBVhTUS43J7gJqFjrnl33A3sxtCa3nAWhX6bbAT4hJugCsNZ2TGA8224AJnjAmSOC5A5LkDmuACZ4wJkjguQOS5A5rgAmeMCZI4LkDkuQOa4AJnjAmSOC5A5LkDmuACZ4wJkjguQOWEFYJvz85xwBBWgKM1P68oKKsI/36ACdC9nsDlWPTsIJ5t1Hfw01OBjgI1p/YwLegIibw0CwESz9gUYZ2d/wHEcx3Ecx3Ecx3Ecx3HuS5QjfdrXxTHv3JzEkd2xKwHR9xPNuKGjzdf1MSIQXAA9XUsuuw8nKPpK3PWzs+AvrgwqgP1LojOjoEf3fRv6Zy+JgBSLOGfaOx1NE/6o+rCrgeT9fWp4SljmuACZ4wJkjguQOS5A5rgAmeMCZI4LkDkuQOa4AJnjAmSOC5A5LkDmuACZ4wJkjguQOS5A5rgAmeMCZI4LkDkuQOa4AJnj5wRmTlABqHQBohKhggUVYAEEP8fO+UiMgziDCvCwrnU3aw0nOATMQu8LVIIPAq+JdAerdwWBaQ/fjEBwAaQVmMnN7sEJCB3EqP3tlRGJy6qqmPkFMcZw7sucmfZiHQ6hRBNgSXdaCHbA7KeFfBvz9pxlxtl1gcN2XBWRfwHK959XFRG6AgAAAABJRU5ErkJggg==";
+    const PATH: &'static [&'static str] = &["backpack"];
+    const FLAG: Option<&'static str> = Some("isBackpack");
 
-    fn ready_state(&self) -> WalletReadyState {
-        self.wrs.clone()
-    }
-
-    fn pubkey(&self) -> Option<Pubkey> {
-        let pubkey = backpack.pubkey();
-        if pubkey.is_null() {
-            None
-        } else {
-            Some(pubkey.to_pubkey())
+    /// Backpack connects to the given cluster, unless it only reconnects silently.
+    fn connect_args(options: &ConnectOptions) -> JsValue {
+        match &options.rpc_endpoint {
+            Some(endpoint) if !options.only_if_trusted => JsValue::from_str(endpoint),
+            _ => to_js_connect_options(options),
         }
     }
 
-    fn connecting(&self) -> bool {
-        *self.connecting.lock().unwrap()
+    fn capabilities(provider: &InjectedSolana) -> WalletCapabilities {
+        provider_capabilities(provider, Self::TRANSACTION_VERSIONS, "send")
     }
 
-    async fn connect(&self, rpc_endpoint: Option<&str>) {
-        *self.connecting.lock().unwrap() = true;
-        log::info!("Endpoint: {:?}", rpc_endpoint);
-        if let Some(endpoint) = rpc_endpoint {
-            match backpack.connect(endpoint).await {
-                Ok(r) => {
-                    log::info!("Successfully invoked connect. {:?}", r.as_string());
-                }
-                Err(e) => {
-                    log::error!("Error invoking connect. {:?}", e.as_string());
-                }
-            }
-        }
-        *self.connecting.lock().unwrap() = false;
-    }
-
-    async fn disconnect(&self) {
-        backpack.disconnect().await
+    /// Backpack names `signAndSendTransaction` `send`.
+    async fn sign_and_send_transaction(
+        provider: &InjectedSolana,
+        transaction: WasmTransaction,
+    ) -> Result<JsValue, JsValue> {
+        provider.send(transaction).await
     }
 }
 
-#[async_trait(?Send)]
-impl WalletAdapter for BackpackWalletAdapter {
-    fn priority_fees_supported(&self) -> bool {
-        backpack.priority_fees_supported()
-    }
-
-    async fn on_event(&self, f: &mut dyn FnMut(Event)) {
-        let mut wel = self.wel.lock().unwrap();
-        if let Some(ref mut receiver) = &mut wel.receiver {
-            loop {
-                if let Some(msg) = receiver.next().await {
-                    f(msg)
-                }
-            }
-        }
-    }
-}
+pub type BackpackWalletAdapter = InjectedWalletAdapter<BackpackProvider>;
@@ -0,0 +1,1139 @@
+use crate::injected::InjectedProvider;
+use async_trait::async_trait;
+use crypto_box::{
+    aead::{generic_array::GenericArray, Aead},
+    rand_core::OsRng,
+    PublicKey, SalsaBox, SecretKey,
+};
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use solana_client_wasm::solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::{Transaction, TransactionVersion, VersionedTransaction},
+};
+use solana_wallet_adapter_base::{
+    ConnectOptions, Event, EventBus, EventSubscription, ProviderError, ProviderErrorCode,
+    RedirectResponse, SignInInput, SignInOutput, WalletAdapter, WalletAdapterBase,
+    WalletAdapterMetadata, WalletCapabilities, WalletError, WalletReadyState, WalletTransaction,
+    DEFAULT_TRANSACTION_VERSIONS,
+};
+use std::{
+    marker::PhantomData,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+use url::Url;
+
+/// The query parameter added to redirect links, naming the wallet and the request it answers as
+/// `{wallet}:{method}`.
+pub const REDIRECT_PARAM: &str = "wallet_redirect";
+
+const NONCE_SIZE: usize = 24;
+
+/// Describes a mobile wallet that implements the universal-link protocol.
+///
+/// ## Protocol
+/// The app and the wallet exchange x25519 public keys on connect, every later payload is
+/// encrypted with a NaCl box keyed by the shared secret. Requests open the wallet through a
+/// universal link, the wallet answers by redirecting back to the `redirect_link` of the request.
+pub trait DeepLinkProvider: InjectedProvider {
+    /// The base of the universal links, requests open `{BASE_URL}{method}`.
+    const BASE_URL: &'static str;
+    /// The redirect parameter carrying the wallet's encryption public key, on connect.
+    const ENCRYPTION_PUBLIC_KEY_PARAM: &'static str;
+}
+
+/// The requests of the universal-link protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeepLinkMethod {
+    Connect,
+    Disconnect,
+    SignTransaction,
+    SignMessage,
+}
+
+impl DeepLinkMethod {
+    pub fn as_str(&self) -> &'static str {
+        use DeepLinkMethod::*;
+        match self {
+            Connect => "connect",
+            Disconnect => "disconnect",
+            SignTransaction => "signTransaction",
+            SignMessage => "signMessage",
+        }
+    }
+}
+
+impl FromStr for DeepLinkMethod {
+    type Err = WalletError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use DeepLinkMethod::*;
+        match s {
+            "connect" => Ok(Connect),
+            "disconnect" => Ok(Disconnect),
+            "signTransaction" => Ok(SignTransaction),
+            "signMessage" => Ok(SignMessage),
+            _ => Err(WalletError::Config),
+        }
+    }
+}
+
+/// A session established with the wallet on connect.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeepLinkSession {
+    /// The wallet's x25519 public key, base58 encoded.
+    pub wallet_encryption_public_key: String,
+    /// The opaque session token, sent back with every request.
+    pub session: String,
+    /// The connected account, base58 encoded.
+    pub public_key: String,
+}
+
+impl DeepLinkSession {
+    pub fn pubkey(&self) -> Result<Pubkey, WalletError> {
+        Pubkey::from_str(&self.public_key).map_err(|_| WalletError::Pubkey)
+    }
+}
+
+/// A redirect back from the wallet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeepLinkRedirect {
+    pub method: DeepLinkMethod,
+    pub response: Result<RedirectResponse, WalletError>,
+    /// The app route the redirect points to, stripped of the protocol parameters.
+    pub route: String,
+}
+
+#[derive(Deserialize)]
+struct ConnectData {
+    public_key: String,
+    session: String,
+}
+
+#[derive(Deserialize)]
+struct SignTransactionData {
+    transaction: String,
+}
+
+#[derive(Deserialize)]
+struct SignMessageData {
+    signature: String,
+}
+
+/// The app side of the universal-link protocol.
+///
+/// Builds the request links and decrypts the redirects, it does not touch the browser.
+pub struct DeepLinkClient {
+    /// The name of the wallet, marking its redirects apart from the other wallets'.
+    wallet: String,
+    base_url: String,
+    wallet_key_param: String,
+    secret: SecretKey,
+    session: Option<DeepLinkSession>,
+    /// The version of the transaction sent to be signed, the signed one is read back as such.
+    pending_version: Option<TransactionVersion>,
+}
+
+impl DeepLinkClient {
+    pub fn new(wallet: &str, base_url: &str, wallet_key_param: &str, secret: SecretKey) -> Self {
+        Self {
+            wallet: wallet.to_string(),
+            base_url: base_url.to_string(),
+            wallet_key_param: wallet_key_param.to_string(),
+            secret,
+            session: None,
+            pending_version: None,
+        }
+    }
+
+    /// Creates a client with a fresh x25519 keypair.
+    pub fn generate(wallet: &str, base_url: &str, wallet_key_param: &str) -> Self {
+        Self::new(
+            wallet,
+            base_url,
+            wallet_key_param,
+            SecretKey::generate(&mut OsRng),
+        )
+    }
+
+    pub fn with_session(mut self, session: Option<DeepLinkSession>) -> Self {
+        self.session = session;
+        self
+    }
+
+    pub fn with_pending_version(mut self, pending_version: Option<TransactionVersion>) -> Self {
+        self.pending_version = pending_version;
+        self
+    }
+
+    pub fn pending_version(&self) -> Option<&TransactionVersion> {
+        self.pending_version.as_ref()
+    }
+
+    pub fn secret_key(&self) -> &SecretKey {
+        &self.secret
+    }
+
+    pub fn session(&self) -> Option<&DeepLinkSession> {
+        self.session.as_ref()
+    }
+
+    pub fn clear_session(&mut self) {
+        self.session = None;
+    }
+
+    /// The app's x25519 public key, base58 encoded.
+    pub fn dapp_encryption_public_key(&self) -> String {
+        bs58::encode(self.secret.public_key().as_bytes()).into_string()
+    }
+
+    /// `url`, marked as the redirect of this wallet answering `method`.
+    pub fn redirect_link(&self, url: &str, method: DeepLinkMethod) -> Result<String, WalletError> {
+        let mut url = Url::parse(url).map_err(|_| WalletError::Config)?;
+        url.query_pairs_mut().append_pair(
+            REDIRECT_PARAM,
+            &format!("{}:{}", self.wallet, method.as_str()),
+        );
+        Ok(url.into())
+    }
+
+    /// The link opening the wallet to connect the app.
+    pub fn connect_url(
+        &self,
+        app_url: &str,
+        cluster: &str,
+        redirect_link: &str,
+    ) -> Result<String, WalletError> {
+        let mut url = self.method_url(DeepLinkMethod::Connect)?;
+        url.query_pairs_mut()
+            .append_pair("app_url", app_url)
+            .append_pair(
+                "dapp_encryption_public_key",
+                &self.dapp_encryption_public_key(),
+            )
+            .append_pair("redirect_link", redirect_link)
+            .append_pair("cluster", cluster);
+        Ok(url.into())
+    }
+
+    /// The link ending the session in the wallet.
+    pub fn disconnect_url(&self, redirect_link: &str) -> Result<String, WalletError> {
+        let payload = serde_json::json!({ "session": self.session_token()? });
+        self.request_url(DeepLinkMethod::Disconnect, &payload, redirect_link)
+    }
+
+    /// The link requesting the wallet to sign a transaction.
+    pub fn sign_transaction_url(
+        &mut self,
+        transaction: &WalletTransaction,
+        redirect_link: &str,
+    ) -> Result<String, WalletError> {
+        let payload = serde_json::json!({
+            "transaction": bs58::encode(transaction.serialize()?).into_string(),
+            "session": self.session_token()?,
+        });
+        let link = self.request_url(DeepLinkMethod::SignTransaction, &payload, redirect_link)?;
+        self.pending_version = Some(transaction.version());
+        Ok(link)
+    }
+
+    /// The link requesting the wallet to sign an arbitrary message.
+    pub fn sign_message_url(
+        &self,
+        message: &[u8],
+        redirect_link: &str,
+    ) -> Result<String, WalletError> {
+        let payload = serde_json::json!({
+            "message": bs58::encode(message).into_string(),
+            "session": self.session_token()?,
+            "display": "utf8",
+        });
+        self.request_url(DeepLinkMethod::SignMessage, &payload, redirect_link)
+    }
+
+    /// Encrypts a JSON payload for the wallet, returning the base58 encoded nonce and payload.
+    pub fn encrypt_payload(
+        &self,
+        wallet_encryption_public_key: &str,
+        nonce: &[u8; NONCE_SIZE],
+        payload: &serde_json::Value,
+    ) -> Result<(String, String), WalletError> {
+        let shared = self.shared_box(wallet_encryption_public_key)?;
+        let plaintext = serde_json::to_vec(payload).map_err(|_| WalletError::Config)?;
+        let ciphertext = shared
+            .encrypt(GenericArray::from_slice(nonce), plaintext.as_slice())
+            .map_err(|_| WalletError::Keypair)?;
+        Ok((
+            bs58::encode(nonce).into_string(),
+            bs58::encode(ciphertext).into_string(),
+        ))
+    }
+
+    /// Decrypts a base58 encoded payload sent by the wallet.
+    pub fn decrypt_payload(
+        &self,
+        wallet_encryption_public_key: &str,
+        nonce: &str,
+        data: &str,
+    ) -> Result<serde_json::Value, WalletError> {
+        let shared = self.shared_box(wallet_encryption_public_key)?;
+        let nonce = decode_b58(nonce)?;
+        if nonce.len() != NONCE_SIZE {
+            return Err(WalletError::Config);
+        }
+        let plaintext = shared
+            .decrypt(
+                GenericArray::from_slice(&nonce),
+                decode_b58(data)?.as_slice(),
+            )
+            .map_err(|_| WalletError::Keypair)?;
+        serde_json::from_slice(&plaintext).map_err(|_| WalletError::Config)
+    }
+
+    /// Reads a redirect back from the wallet, `None` when the url is not a redirect of this
+    /// wallet.
+    ///
+    /// A successful connect starts a new session.
+    pub fn parse_redirect(&mut self, url: &str) -> Result<Option<DeepLinkRedirect>, WalletError> {
+        let url = Url::parse(url).map_err(|_| WalletError::Config)?;
+        let param = |key: &str| {
+            url.query_pairs()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.into_owned())
+        };
+        let Some(marker) = param(REDIRECT_PARAM) else {
+            return Ok(None);
+        };
+        // Other wallets' redirects are left to their own adapter
+        let Some(method) = marker
+            .strip_prefix(self.wallet.as_str())
+            .and_then(|method| method.strip_prefix(':'))
+        else {
+            return Ok(None);
+        };
+        let method = DeepLinkMethod::from_str(method)?;
+        let route = self.route(&url);
+
+        let response = if let Some(code) = param("errorCode") {
+            let code = code.parse::<i64>().unwrap_or_default();
+            let message = param("errorMessage").unwrap_or_default();
+            Err(WalletError::Provider(ProviderError::new(
+                ProviderErrorCode::from(code),
+                message,
+            )))
+        } else {
+            self.read_response(method, &param)
+        };
+
+        Ok(Some(DeepLinkRedirect {
+            method,
+            response,
+            route,
+        }))
+    }
+
+    fn read_response(
+        &mut self,
+        method: DeepLinkMethod,
+        param: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<RedirectResponse, WalletError> {
+        if method == DeepLinkMethod::Disconnect {
+            self.session = None;
+            return Ok(RedirectResponse::Disconnect);
+        }
+
+        let wallet_key = match method {
+            DeepLinkMethod::Connect => param(&self.wallet_key_param).ok_or(WalletError::Config)?,
+            _ => self
+                .session
+                .as_ref()
+                .map(|s| s.wallet_encryption_public_key.clone())
+                .ok_or(WalletError::NotConnected)?,
+        };
+        let nonce = param("nonce").ok_or(WalletError::Config)?;
+        let data = param("data").ok_or(WalletError::Config)?;
+        let data = self.decrypt_payload(&wallet_key, &nonce, &data)?;
+
+        match method {
+            DeepLinkMethod::Connect => {
+                let data: ConnectData =
+                    serde_json::from_value(data).map_err(|_| WalletError::Connection)?;
+                let session = DeepLinkSession {
+                    wallet_encryption_public_key: wallet_key,
+                    session: data.session,
+                    public_key: data.public_key,
+                };
+                let pubkey = session.pubkey()?;
+                self.session = Some(session);
+                Ok(RedirectResponse::Connect(pubkey))
+            }
+            DeepLinkMethod::SignTransaction => {
+                let data: SignTransactionData =
+                    serde_json::from_value(data).map_err(|_| WalletError::SignTransaction)?;
+                let bytes = decode_b58(&data.transaction)?;
+                // The signed transaction is handed back the way it was sent
+                let transaction = match self.pending_version.take() {
+                    Some(TransactionVersion::LEGACY) => {
+                        bincode::deserialize::<Transaction>(&bytes).map(WalletTransaction::Legacy)
+                    }
+                    _ => bincode::deserialize::<VersionedTransaction>(&bytes)
+                        .map(WalletTransaction::Versioned),
+                }
+                .map_err(|_| WalletError::SignTransaction)?;
+                Ok(RedirectResponse::SignTransaction(transaction))
+            }
+            DeepLinkMethod::SignMessage => {
+                let data: SignMessageData =
+                    serde_json::from_value(data).map_err(|_| WalletError::SignMessage)?;
+                let signature =
+                    Signature::from_str(&data.signature).map_err(|_| WalletError::SignMessage)?;
+                Ok(RedirectResponse::SignMessage(signature))
+            }
+            DeepLinkMethod::Disconnect => unreachable!("handled above"),
+        }
+    }
+
+    fn method_url(&self, method: DeepLinkMethod) -> Result<Url, WalletError> {
+        Url::parse(&format!("{}{}", self.base_url, method.as_str()))
+            .map_err(|_| WalletError::Config)
+    }
+
+    fn request_url(
+        &self,
+        method: DeepLinkMethod,
+        payload: &serde_json::Value,
+        redirect_link: &str,
+    ) -> Result<String, WalletError> {
+        let session = self.session.as_ref().ok_or(WalletError::NotConnected)?;
+        let nonce: [u8; NONCE_SIZE] = crypto_box::generate_nonce(&mut OsRng).into();
+        let (nonce, payload) =
+            self.encrypt_payload(&session.wallet_encryption_public_key, &nonce, payload)?;
+
+        let mut url = self.method_url(method)?;
+        url.query_pairs_mut()
+            .append_pair(
+                "dapp_encryption_public_key",
+                &self.dapp_encryption_public_key(),
+            )
+            .append_pair("nonce", &nonce)
+            .append_pair("redirect_link", redirect_link)
+            .append_pair("payload", &payload);
+        Ok(url.into())
+    }
+
+    fn session_token(&self) -> Result<&str, WalletError> {
+        self.session
+            .as_ref()
+            .map(|s| s.session.as_str())
+            .ok_or(WalletError::NotConnected)
+    }
+
+    fn shared_box(&self, wallet_encryption_public_key: &str) -> Result<SalsaBox, WalletError> {
+        let key: [u8; 32] = decode_b58(wallet_encryption_public_key)?
+            .try_into()
+            .map_err(|_| WalletError::Keypair)?;
+        Ok(SalsaBox::new(&PublicKey::from(key), &self.secret))
+    }
+
+    /// The path, query and fragment of `url`, without the parameters of the protocol.
+    fn route(&self, url: &Url) -> String {
+        let protocol_params = [
+            REDIRECT_PARAM,
+            self.wallet_key_param.as_str(),
+            "nonce",
+            "data",
+            "errorCode",
+            "errorMessage",
+        ];
+        let query = url
+            .query_pairs()
+            .filter(|(k, _)| !protocol_params.contains(&k.as_ref()))
+            .fold(
+                url::form_urlencoded::Serializer::new(String::new()),
+                |mut s, (k, v)| {
+                    s.append_pair(&k, &v);
+                    s
+                },
+            )
+            .finish();
+
+        let mut route = url.path().to_string();
+        if !query.is_empty() {
+            route.push('?');
+            route.push_str(&query);
+        }
+        if let Some(fragment) = url.fragment() {
+            route.push('#');
+            route.push_str(fragment);
+        }
+        route
+    }
+}
+
+fn decode_b58(value: &str) -> Result<Vec<u8>, WalletError> {
+    bs58::decode(value)
+        .into_vec()
+        .map_err(|_| WalletError::Config)
+}
+
+/// What the adapter keeps in local storage across the redirects.
+#[derive(Serialize, Deserialize)]
+struct PersistedState {
+    secret_key: String,
+    session: Option<DeepLinkSession>,
+    #[serde(default)]
+    pending_version: Option<TransactionVersion>,
+}
+
+/// The cluster wallet apps connect to, shared by the adapters and updated as the app switches
+/// clusters.
+///
+/// `None` while the app is on a cluster wallet apps can't connect to, e.g. a local validator.
+#[derive(Debug, Clone, Default)]
+pub struct DeepLinkCluster(Arc<Mutex<Option<String>>>);
+
+impl DeepLinkCluster {
+    /// `cluster` is one of `mainnet-beta`, `testnet` or `devnet`.
+    pub fn new(cluster: Option<&str>) -> Self {
+        Self(Arc::new(Mutex::new(cluster.map(str::to_string))))
+    }
+
+    pub fn get(&self) -> Option<String> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// Sets the cluster of the next connects, the established sessions are left as they are.
+    pub fn set(&self, cluster: Option<&str>) {
+        *self.0.lock().unwrap() = cluster.map(str::to_string);
+    }
+}
+
+/// Whether the app runs in a mobile browser, where wallets are apps rather than extensions.
+pub fn is_mobile_browser() -> bool {
+    let Ok(user_agent) = gloo_utils::window().navigator().user_agent() else {
+        return false;
+    };
+    ["Android", "iPhone", "iPad", "iPod"]
+        .iter()
+        .any(|device| user_agent.contains(device))
+}
+
+/// A wallet adapter talking to a mobile wallet app through universal links.
+///
+/// Every request leaves the app, so requests resolve with [`WalletError::Redirected`] once the
+/// wallet has been opened. The wallet answers by redirecting back to the app, the adapter reads
+/// the redirect when it is created, restores the app route and keeps the response until it is
+/// taken with [`WalletAdapter::take_redirect_response`].
+pub struct DeepLinkWalletAdapter<P: DeepLinkProvider> {
+    client: Arc<Mutex<DeepLinkClient>>,
+    app_url: String,
+    cluster: DeepLinkCluster,
+    response: Arc<Mutex<Option<Result<RedirectResponse, WalletError>>>>,
+    bus: EventBus,
+    _provider: PhantomData<P>,
+}
+
+impl<P: DeepLinkProvider> std::fmt::Debug for DeepLinkWalletAdapter<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeepLinkWalletAdapter")
+            .field("name", &P::NAME)
+            .field("app_url", &self.app_url)
+            .field("cluster", &self.cluster)
+            .field("connected", &self.is_connected())
+            .finish()
+    }
+}
+
+impl<P: DeepLinkProvider> DeepLinkWalletAdapter<P> {
+    /// Creates the adapter, reading the redirect back from the wallet if the app was opened by one.
+    ///
+    /// `app_url` is shown by the wallet when connecting, `cluster` is the one it connects to.
+    pub fn new(app_url: &str, cluster: DeepLinkCluster) -> Self {
+        let client = match LocalStorage::get::<PersistedState>(Self::storage_key()) {
+            Ok(state) => decode_b58(&state.secret_key)
+                .ok()
+                .and_then(|key| <[u8; 32]>::try_from(key).ok())
+                .map(|key| {
+                    DeepLinkClient::new(
+                        P::NAME,
+                        P::BASE_URL,
+                        P::ENCRYPTION_PUBLIC_KEY_PARAM,
+                        key.into(),
+                    )
+                    .with_session(state.session)
+                    .with_pending_version(state.pending_version)
+                }),
+            Err(_) => None,
+        }
+        .unwrap_or_else(|| {
+            DeepLinkClient::generate(P::NAME, P::BASE_URL, P::ENCRYPTION_PUBLIC_KEY_PARAM)
+        });
+
+        let adapter = Self {
+            client: Arc::new(Mutex::new(client)),
+            app_url: app_url.to_string(),
+            cluster,
+            response: Arc::new(Mutex::new(None)),
+            bus: EventBus::new(),
+            _provider: PhantomData,
+        };
+        adapter.handle_redirect();
+        adapter.persist();
+        adapter
+    }
+
+    fn storage_key() -> String {
+        format!("deeplink_{}", P::NAME.to_lowercase())
+    }
+
+    fn persist(&self) {
+        let client = self.client.lock().unwrap();
+        let state = PersistedState {
+            secret_key: bs58::encode(client.secret_key().as_bytes()).into_string(),
+            session: client.session().cloned(),
+            pending_version: client.pending_version().cloned(),
+        };
+        if let Err(e) = LocalStorage::set(Self::storage_key(), state) {
+            log::error!("Failed to persist {} session: {:?}", P::NAME, e);
+        }
+    }
+
+    fn handle_redirect(&self) {
+        let Ok(href) = gloo_utils::window().location().href() else {
+            return;
+        };
+        let redirect = match self.client.lock().unwrap().parse_redirect(&href) {
+            Ok(Some(redirect)) => redirect,
+            Ok(None) => return,
+            Err(e) => {
+                log::error!("Failed to read {} redirect: {}", P::NAME, e);
+                return;
+            }
+        };
+        log::info!("{} answered {:?}", P::NAME, redirect.method);
+
+        // Hand the route back to the app router, without the protocol parameters
+        let history = gloo_utils::window().history();
+        if let Err(e) = history.and_then(|h| {
+            h.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&redirect.route))
+        }) {
+            log::error!("Failed to restore app route: {:?}", e);
+        }
+        *self.response.lock().unwrap() = Some(redirect.response);
+    }
+
+    /// The current page, marked as the redirect of `method`.
+    fn redirect_link(&self, method: DeepLinkMethod) -> Result<String, WalletError> {
+        let href = gloo_utils::window()
+            .location()
+            .href()
+            .map_err(|_| WalletError::Config)?;
+        self.client.lock().unwrap().redirect_link(&href, method)
+    }
+
+    fn open(&self, link: &str) -> Result<(), WalletError> {
+        self.persist();
+        gloo_utils::window()
+            .location()
+            .set_href(link)
+            .map_err(|_| WalletError::WindowBlocked)
+    }
+
+    fn unsupported(method: &str) -> WalletError {
+        WalletError::Provider(ProviderError::new(
+            ProviderErrorCode::UnsupportedMethod,
+            format!("{} is not supported through universal links", method),
+        ))
+    }
+}
+
+impl<P: DeepLinkProvider> WalletAdapterMetadata for DeepLinkWalletAdapter<P> {
+    fn name(&self) -> &str {
+        P::NAME
+    }
+
+    fn url(&self) -> &str {
+        P::URL
+    }
+
+    fn icon(&self) -> &str {
+        P::ICON
+    }
+}
+
+#[async_trait(?Send)]
+impl<P: DeepLinkProvider> WalletAdapterBase for DeepLinkWalletAdapter<P> {
+    fn is_name(&self) -> bool {
+        true
+    }
+
+    fn is_connected(&self) -> bool {
+        self.client.lock().unwrap().session().is_some()
+    }
+
+    fn ready_state(&self) -> WalletReadyState {
+        WalletReadyState::Loadable
+    }
+
+    fn pubkey(&self) -> Option<Pubkey> {
+        self.client
+            .lock()
+            .unwrap()
+            .session()
+            .and_then(|s| s.pubkey().ok())
+    }
+
+    fn connecting(&self) -> bool {
+        false
+    }
+
+    async fn connect(&self, options: ConnectOptions) -> Result<Pubkey, WalletError> {
+        if let Some(pubkey) = self.pubkey() {
+            return Ok(pubkey);
+        }
+        // Opening the wallet always needs the user
+        if options.only_if_trusted {
+            return Err(WalletError::NotConnected);
+        }
+        // Wallet apps only connect to the public clusters
+        let Some(cluster) = self.cluster.get() else {
+            log::error!("{} can't connect to a custom cluster", P::NAME);
+            return Err(WalletError::Config);
+        };
+        let redirect_link = self.redirect_link(DeepLinkMethod::Connect)?;
+        let link =
+            self.client
+                .lock()
+                .unwrap()
+                .connect_url(&self.app_url, &cluster, &redirect_link)?;
+        self.open(&link)?;
+        Err(WalletError::Redirected)
+    }
+
+    /// Forgets the session, without opening the wallet.
+    async fn disconnect(&self) {
+        self.client.lock().unwrap().clear_session();
+        self.persist();
+        self.bus.emit(Event::Disconnect);
+    }
+
+    async fn sign_transaction(
+        &self,
+        transaction: WalletTransaction,
+    ) -> Result<WalletTransaction, WalletError> {
+        let redirect_link = self.redirect_link(DeepLinkMethod::SignTransaction)?;
+        let link = self
+            .client
+            .lock()
+            .unwrap()
+            .sign_transaction_url(&transaction, &redirect_link)?;
+        self.open(&link)?;
+        Err(WalletError::Redirected)
+    }
+
+    async fn sign_all_transactions(
+        &self,
+        _: Vec<WalletTransaction>,
+    ) -> Result<Vec<WalletTransaction>, WalletError> {
+        Err(Self::unsupported("signAllTransactions"))
+    }
+
+    async fn sign_and_send_transaction(
+        &self,
+        _: WalletTransaction,
+    ) -> Result<Signature, WalletError> {
+        Err(Self::unsupported("signAndSendTransaction"))
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature, WalletError> {
+        let redirect_link = self.redirect_link(DeepLinkMethod::SignMessage)?;
+        let link = self
+            .client
+            .lock()
+            .unwrap()
+            .sign_message_url(message, &redirect_link)?;
+        self.open(&link)?;
+        Err(WalletError::Redirected)
+    }
+
+    async fn sign_in(&self, _: SignInInput) -> Result<SignInOutput, WalletError> {
+        Err(Self::unsupported("signIn"))
+    }
+}
+
+#[async_trait(?Send)]
+impl<P: DeepLinkProvider> WalletAdapter for DeepLinkWalletAdapter<P> {
+    fn priority_fees_supported(&self) -> bool {
+        false
+    }
+
+    /// Only the requests that return through a redirect are supported.
+    fn capabilities(&self) -> WalletCapabilities {
+        WalletCapabilities {
+            supported_transaction_versions: DEFAULT_TRANSACTION_VERSIONS.to_vec(),
+            sign_message: true,
+            ..Default::default()
+        }
+    }
+
+    fn subscribe(&self) -> EventSubscription {
+        self.bus.subscribe()
+    }
+
+    fn take_redirect_response(&self) -> Option<Result<RedirectResponse, WalletError>> {
+        self.response.lock().unwrap().take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use solana_client_wasm::solana_sdk::{
+        hash::Hash,
+        message::{v0, VersionedMessage},
+        signer::{keypair::Keypair, Signer},
+        system_instruction,
+    };
+
+    const BASE_URL: &str = "https://phantom.app/ul/v1/";
+    const WALLET_KEY_PARAM: &str = "phantom_encryption_public_key";
+    const REDIRECT: &str = "https://anvil.so/a/11111111111111111111111111111111";
+
+    fn client() -> DeepLinkClient {
+        DeepLinkClient::generate("Phantom", BASE_URL, WALLET_KEY_PARAM)
+    }
+
+    fn nonce() -> [u8; NONCE_SIZE] {
+        crypto_box::generate_nonce(&mut OsRng).into()
+    }
+
+    /// The redirect a wallet sends back, with the payload encrypted for the app.
+    fn redirect(
+        wallet: &DeepLinkClient,
+        app: &DeepLinkClient,
+        method: DeepLinkMethod,
+        payload: &Value,
+    ) -> String {
+        let (nonce, data) = wallet
+            .encrypt_payload(&app.dapp_encryption_public_key(), &nonce(), payload)
+            .unwrap();
+        let mut url = Url::parse(REDIRECT).unwrap();
+        url.query_pairs_mut().append_pair("cluster", "devnet");
+        let mut url = Url::parse(&app.redirect_link(url.as_str(), method).unwrap()).unwrap();
+        url.query_pairs_mut()
+            .append_pair(WALLET_KEY_PARAM, &wallet.dapp_encryption_public_key())
+            .append_pair("nonce", &nonce)
+            .append_pair("data", &data);
+        url.set_fragment(Some("history"));
+        url.into()
+    }
+
+    /// Connects the app to the wallet, returning the connected account.
+    fn connect(app: &mut DeepLinkClient, wallet: &DeepLinkClient) -> Pubkey {
+        let account = Pubkey::new_unique();
+        let payload = json!({ "public_key": account.to_string(), "session": "session-token" });
+        let url = redirect(wallet, app, DeepLinkMethod::Connect, &payload);
+        app.parse_redirect(&url).unwrap().unwrap();
+        account
+    }
+
+    /// The decrypted payload of a request link, as the wallet reads it.
+    fn request_payload(wallet: &DeepLinkClient, link: &str) -> Value {
+        let url = Url::parse(link).unwrap();
+        let param = |key: &str| {
+            url.query_pairs()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.into_owned())
+                .unwrap()
+        };
+        wallet
+            .decrypt_payload(
+                &param("dapp_encryption_public_key"),
+                &param("nonce"),
+                &param("payload"),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn round_trips_encrypted_payloads() {
+        let app = client();
+        let wallet = client();
+        let payload = json!({ "session": "session-token", "message": "hello" });
+        let nonce = nonce();
+
+        let (encoded_nonce, data) = app
+            .encrypt_payload(&wallet.dapp_encryption_public_key(), &nonce, &payload)
+            .unwrap();
+        assert_eq!(decode_b58(&encoded_nonce).unwrap(), nonce.to_vec());
+        assert_eq!(
+            wallet
+                .decrypt_payload(&app.dapp_encryption_public_key(), &encoded_nonce, &data)
+                .unwrap(),
+            payload
+        );
+    }
+
+    #[test]
+    fn rejects_tampered_payloads() {
+        let app = client();
+        let wallet = client();
+        let (nonce, data) = app
+            .encrypt_payload(&wallet.dapp_encryption_public_key(), &nonce(), &json!({}))
+            .unwrap();
+        let app_key = app.dapp_encryption_public_key();
+
+        // Another app's key, a short nonce, a modified payload and invalid base58
+        assert_eq!(
+            wallet.decrypt_payload(&client().dapp_encryption_public_key(), &nonce, &data),
+            Err(WalletError::Keypair)
+        );
+        assert_eq!(
+            wallet.decrypt_payload(&app_key, &bs58::encode([1u8; 12]).into_string(), &data),
+            Err(WalletError::Config)
+        );
+        let mut tampered = decode_b58(&data).unwrap();
+        tampered[0] ^= 1;
+        assert_eq!(
+            wallet.decrypt_payload(&app_key, &nonce, &bs58::encode(tampered).into_string()),
+            Err(WalletError::Keypair)
+        );
+        assert_eq!(
+            wallet.decrypt_payload(&app_key, &nonce, "0OIl"),
+            Err(WalletError::Config)
+        );
+    }
+
+    #[test]
+    fn builds_connect_links() {
+        let app = client();
+        let link = app
+            .connect_url("https://anvil.so", "devnet", REDIRECT)
+            .unwrap();
+        let url = Url::parse(&link).unwrap();
+        let params: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+
+        assert!(link.starts_with("https://phantom.app/ul/v1/connect?"));
+        assert_eq!(
+            params,
+            vec![
+                ("app_url".to_string(), "https://anvil.so".to_string()),
+                (
+                    "dapp_encryption_public_key".to_string(),
+                    app.dapp_encryption_public_key()
+                ),
+                ("redirect_link".to_string(), REDIRECT.to_string()),
+                ("cluster".to_string(), "devnet".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_connect_redirects() {
+        let mut app = client();
+        let wallet = client();
+        let account = Pubkey::new_unique();
+        let payload = json!({ "public_key": account.to_string(), "session": "session-token" });
+        let url = redirect(&wallet, &app, DeepLinkMethod::Connect, &payload);
+
+        let redirect = app.parse_redirect(&url).unwrap().unwrap();
+        assert_eq!(redirect.method, DeepLinkMethod::Connect);
+        assert_eq!(redirect.response, Ok(RedirectResponse::Connect(account)));
+        assert_eq!(
+            redirect.route,
+            "/a/11111111111111111111111111111111?cluster=devnet#history"
+        );
+        assert_eq!(
+            app.session(),
+            Some(&DeepLinkSession {
+                wallet_encryption_public_key: wallet.dapp_encryption_public_key(),
+                session: "session-token".to_string(),
+                public_key: account.to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn signs_messages_within_the_session() {
+        let mut app = client();
+        let wallet = client();
+        connect(&mut app, &wallet);
+
+        let link = app.sign_message_url(b"hello", REDIRECT).unwrap();
+        assert_eq!(
+            request_payload(&wallet, &link),
+            json!({
+                "message": bs58::encode(b"hello").into_string(),
+                "session": "session-token",
+                "display": "utf8",
+            })
+        );
+
+        let signature = Keypair::new().sign_message(b"hello");
+        let payload = json!({ "signature": signature.to_string() });
+        let url = redirect(&wallet, &app, DeepLinkMethod::SignMessage, &payload);
+        assert_eq!(
+            app.parse_redirect(&url).unwrap().unwrap().response,
+            Ok(RedirectResponse::SignMessage(signature))
+        );
+    }
+
+    #[test]
+    fn signs_transactions_within_the_session() {
+        let mut app = client();
+        let wallet = client();
+        let account = connect(&mut app, &wallet);
+
+        let instruction = system_instruction::transfer(&account, &Pubkey::new_unique(), 1);
+        let message =
+            v0::Message::try_compile(&account, &[instruction], &[], Hash::new_unique()).unwrap();
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::new_unique()],
+            message: VersionedMessage::V0(message),
+        };
+        let bytes = bincode::serialize(&transaction).unwrap();
+
+        let link = app
+            .sign_transaction_url(&transaction.clone().into(), REDIRECT)
+            .unwrap();
+        assert_eq!(
+            request_payload(&wallet, &link)["transaction"],
+            bs58::encode(&bytes).into_string()
+        );
+
+        let payload = json!({ "transaction": bs58::encode(&bytes).into_string() });
+        let url = redirect(&wallet, &app, DeepLinkMethod::SignTransaction, &payload);
+        assert_eq!(
+            app.parse_redirect(&url).unwrap().unwrap().response,
+            Ok(RedirectResponse::SignTransaction(transaction.into()))
+        );
+    }
+
+    #[test]
+    fn returns_legacy_transactions_as_legacy() {
+        let mut app = client();
+        let wallet = client();
+        let account = connect(&mut app, &wallet);
+
+        let instruction = system_instruction::transfer(&account, &Pubkey::new_unique(), 1);
+        let mut transaction = Transaction::new_with_payer(&[instruction], Some(&account));
+        app.sign_transaction_url(&WalletTransaction::Legacy(transaction.clone()), REDIRECT)
+            .unwrap();
+        assert_eq!(app.pending_version(), Some(&TransactionVersion::LEGACY));
+
+        transaction.signatures = vec![Signature::new_unique()];
+        let payload = json!({
+            "transaction": bs58::encode(bincode::serialize(&transaction).unwrap()).into_string(),
+        });
+        let url = redirect(&wallet, &app, DeepLinkMethod::SignTransaction, &payload);
+        assert_eq!(
+            app.parse_redirect(&url).unwrap().unwrap().response,
+            Ok(RedirectResponse::SignTransaction(
+                WalletTransaction::Legacy(transaction)
+            ))
+        );
+        assert_eq!(app.pending_version(), None);
+    }
+
+    #[test]
+    fn parses_error_redirects() {
+        let mut app = client();
+        let url = format!(
+            "{}?{}=Phantom:connect&errorCode=4001&errorMessage=User+rejected+the+request.",
+            REDIRECT, REDIRECT_PARAM
+        );
+
+        let redirect = app.parse_redirect(&url).unwrap().unwrap();
+        assert_eq!(redirect.method, DeepLinkMethod::Connect);
+        assert_eq!(
+            redirect.response,
+            Err(WalletError::Provider(ProviderError::new(
+                ProviderErrorCode::UserRejected,
+                "User rejected the request."
+            )))
+        );
+        assert_eq!(redirect.route, "/a/11111111111111111111111111111111");
+        assert_eq!(app.session(), None);
+    }
+
+    #[test]
+    fn ends_the_session_on_disconnect() {
+        let mut app = client();
+        let wallet = client();
+        connect(&mut app, &wallet);
+
+        let link = app.disconnect_url(REDIRECT).unwrap();
+        assert_eq!(
+            request_payload(&wallet, &link),
+            json!({ "session": "session-token" })
+        );
+
+        let url = app
+            .redirect_link(REDIRECT, DeepLinkMethod::Disconnect)
+            .unwrap();
+        assert_eq!(
+            app.parse_redirect(&url).unwrap().unwrap().response,
+            Ok(RedirectResponse::Disconnect)
+        );
+        assert_eq!(app.session(), None);
+        assert_eq!(app.disconnect_url(REDIRECT), Err(WalletError::NotConnected));
+    }
+
+    #[test]
+    fn ignores_other_urls() {
+        let mut app = client();
+        assert_eq!(app.parse_redirect(REDIRECT), Ok(None));
+        assert_eq!(
+            app.parse_redirect(&format!("{}?{}=Phantom:unknown", REDIRECT, REDIRECT_PARAM)),
+            Err(WalletError::Config)
+        );
+        assert_eq!(
+            app.parse_redirect(&format!("{}?{}=connect", REDIRECT, REDIRECT_PARAM)),
+            Ok(None)
+        );
+        assert_eq!(app.parse_redirect("not a url"), Err(WalletError::Config));
+    }
+
+    #[test]
+    fn ignores_redirects_of_other_wallets() {
+        let mut phantom = client();
+        let mut solflare =
+            DeepLinkClient::generate("Solflare", "https://solflare.com/ul/v1/", WALLET_KEY_PARAM);
+        let wallet = client();
+        connect(&mut phantom, &wallet);
+        let account = Pubkey::new_unique();
+        let payload = json!({ "public_key": account.to_string(), "session": "session-token" });
+        let url = redirect(&wallet, &solflare, DeepLinkMethod::Connect, &payload);
+        assert_eq!(
+            solflare.parse_redirect(&url).unwrap().unwrap().response,
+            Ok(RedirectResponse::Connect(account))
+        );
+
+        // Solflare's rejection and disconnect leave Phantom's session alone
+        let rejected = format!(
+            "{}&errorCode=4001&errorMessage=User+rejected+the+request.",
+            solflare
+                .redirect_link(REDIRECT, DeepLinkMethod::SignMessage)
+                .unwrap()
+        );
+        assert_eq!(phantom.parse_redirect(&rejected), Ok(None));
+        let disconnected = solflare
+            .redirect_link(REDIRECT, DeepLinkMethod::Disconnect)
+            .unwrap();
+        assert_eq!(phantom.parse_redirect(&disconnected), Ok(None));
+        assert!(phantom.session().is_some());
+
+        assert_eq!(
+            solflare
+                .parse_redirect(&disconnected)
+                .unwrap()
+                .unwrap()
+                .response,
+            Ok(RedirectResponse::Disconnect)
+        );
+        assert_eq!(solflare.session(), None);
+    }
+}
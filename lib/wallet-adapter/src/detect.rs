@@ -0,0 +1,78 @@
+use crate::proxy::WalletEventListener;
+use solana_wallet_adapter_base::{Event, WalletReadyState};
+use std::{
+    cell::Cell,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+/// How often a provider that has not been injected yet is looked up again.
+const DETECTION_INTERVAL_MS: u32 = 1_000;
+
+/// Detects the ready state of an injected provider.
+///
+/// Providers that are already injected are installed right away. Otherwise the provider is
+/// looked up again periodically until it shows up, at which point the ready state becomes
+/// [`WalletReadyState::Installed`] and a [`Event::ReadyStateChange`] is emitted.
+///
+/// The event listener is only bound once a session starts.
+///
+/// ## Standard
+/// This is based on the `scopePollingDetectionStrategy` of the typescript adapter.
+///
+/// Read: https://github.com/anza-xyz/wallet-adapter/blob/master/packages/core/base/src/adapter.ts
+pub(crate) fn detect_ready_state<P, F>(
+    provider: F,
+    wrs: Arc<Mutex<WalletReadyState>>,
+    wel: Arc<Mutex<WalletEventListener>>,
+) -> Option<DetectionWatcher>
+where
+    F: Fn() -> Option<P> + 'static,
+{
+    if web_sys::window().and_then(|w| w.document()).is_none() {
+        *wrs.lock().unwrap() = WalletReadyState::Unsupported;
+        return None;
+    }
+    if provider().is_some() {
+        *wrs.lock().unwrap() = WalletReadyState::Installed;
+        return None;
+    }
+    *wrs.lock().unwrap() = WalletReadyState::NotDetected;
+
+    let cancelled = Rc::new(Cell::new(false));
+    let watcher = DetectionWatcher {
+        _guard: Rc::new(CancelOnDrop(cancelled.clone())),
+    };
+    wasm_bindgen_futures::spawn_local(async move {
+        loop {
+            gloo_timers::future::TimeoutFuture::new(DETECTION_INTERVAL_MS).await;
+            if cancelled.get() {
+                return;
+            }
+            if provider().is_some() {
+                *wrs.lock().unwrap() = WalletReadyState::Installed;
+                wel.lock()
+                    .unwrap()
+                    .bus
+                    .emit(Event::ReadyStateChange(WalletReadyState::Installed));
+                return;
+            }
+        }
+    });
+    Some(watcher)
+}
+
+/// Keeps looking for a provider until the last clone is dropped.
+#[derive(Debug, Clone)]
+pub(crate) struct DetectionWatcher {
+    _guard: Rc<CancelOnDrop>,
+}
+
+#[derive(Debug)]
+struct CancelOnDrop(Rc<Cell<bool>>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.set(true);
+    }
+}
@@ -0,0 +1,14 @@
+use crate::injected::{InjectedProvider, InjectedWalletAdapter};
+
+#[derive(Debug)]
+pub struct ExodusProvider;
+
+impl InjectedProvider for ExodusProvider {
+    const NAME: &'static str = "Exodus";
+    const URL: &'static str = "https://www.exodus.com/";
+    const ICON: &'static str = "data:image/svg+xml;base64,PHN2ZyB3aWR0aD0iNDIiIGhlaWdodD0iMzgiIHZpZXdCb3g9IjAgMCA0MiAzOCIgZmlsbD0ibm9uZSIgeG1sbnM9Imh0dHA6Ly93d3cudzMub3JnLzIwMDAvc3ZnIj4KPHBhdGggZD0iTTM4LjMwMzYgMTEuNjUyOEwyMy45MjU4IDIuMjI0MjRWNy40OTU5MUwzMy4xNDk0IDEzLjQ4OThMMzIuMDY0MyAxNi45MjM3SDIzLjkyNThWMjEuMDc2M0gzMi4wNjQzTDMzLjE0OTQgMjQuNTEwMkwyMy45MjU4IDMwLjUwNDFWMzUuNzc1OEwzOC4zMDM2IDI2LjM3NzFMMzUuOTUyNSAxOS4wMTQ5TDM4LjMwMzYgMTEuNjUyOFoiIGZpbGw9InVybCgjcGFpbnQwX2xpbmVhcikiLz4KPHBhdGggZD0iTTkuODg1NSAyMS4wNzYzSDE3Ljk5MzhWMTYuOTIzN0g5Ljg1NTM1TDguODAwNTggMTMuNDg5OEwxOC4wMjM5IDcuNDk1OTFWMi4yMjQyNEwzLjY0NiAxMS42NTI4TDUuOTk3MDkgMTkuMDE0OUwzLjY0NiAyNi4zNzcxTDE4LjA1NDEgMzUuNzc1OFYzMC41MDQxTDguODAwNTggMjQuNTEwMkw5Ljg4NTUgMjEuMDc2M1oiIGZpbGw9InVybCgjcGFpbnQxX2xpbmVhcikiLz4KPGRlZnM+CjxsaW5lYXJHcmFkaWVudCBpZD0icGFpbnQwX2xpbmVhciIgeDE9IjMzLjQiIHkxPSIzOC4zIiB4Mj0iMjQuMSIgeTI9IjAuNCIgZ3JhZGllbnRVbml0cz0idXNlclNwYWNlT25Vc2UiPgo8c3RvcCBzdG9wLWNvbG9yPSIjMEI0NkY5Ii8+CjxzdG9wIG9mZnNldD0iMSIgc3RvcC1jb2xvcj0iI0JCRkJFMCIvPgo8L2xpbmVhckdyYWRpZW50Pgo8bGluZWFyR3JhZGllbnQgaWQ9InBhaW50MV9saW5lYXIiIHgxPSIzMy40IiB5MT0iMzguMyIgeDI9IjI0LjEiIHkyPSIwLjQiIGdyYWRpZW50VW5pdHM9InVzZXJTcGFjZU9uVXNlIj4KPHN0b3Agc3RvcC1jb2xvcj0iIzBCNDZGOSIvPgo8c3RvcCBvZmZzZXQ9IjEiIHN0b3AtY29sb3I9IiNCQkZCRTAiLz4KPC9saW5lYXJHcmFkaWVudD4KPC9kZWZzPgo8L3N2Zz4K";
+    const PATH: &'static [&'static str] = &["exodus", "solana"];
+    const FLAG: Option<&'static str> = Some("isExodus");
+}
+
+pub type ExodusWalletAdapter = InjectedWalletAdapter<ExodusProvider>;
@@ -0,0 +1,14 @@
+use crate::injected::{InjectedProvider, InjectedWalletAdapter};
+
+#[derive(Debug)]
+pub struct GlowProvider;
+
+impl InjectedProvider for GlowProvider {
+    const NAME: &'static str = "Glow";
+    const URL: &'static str = "https://glow.app/";
+    const ICON: &'static str = "data:image/svg+xml;base64,PHN2ZyB3aWR0aD0iNDAiIGhlaWdodD0iNDAiIHZpZXdCb3g9IjAgMCA0MCA0MCIgZmlsbD0ibm9uZSIgeG1sbnM9Imh0dHA6Ly93d3cudzMub3JnLzIwMDAvc3ZnIj4KPGNpcmNsZSBjeD0iMjAiIGN5PSIyMCIgcj0iMjAiIGZpbGw9InVybCgjcGFpbnQwX3JhZGlhbCkiLz4KPHBhdGggZmlsbC1ydWxlPSJldmVub2RkIiBjbGlwLXJ1bGU9ImV2ZW5vZGQiIGQ9Ik0yMCAzMUMyNi4wNzUxIDMxIDMxIDI2LjA3NTEgMzEgMjBDMzEgMTMuOTI0OSAyNi4wNzUxIDkgMjAgOUMxMy45MjQ5IDkgOSAxMy45MjQ5IDkgMjBDOSAyNi4wNzUxIDEzLjkyNDkgMzEgMjAgMzFaTTIwIDI2QzIzLjMxMzcgMjYgMjYgMjMuMzEzNyAyNiAyMEMyNiAxNi42ODYzIDIzLjMxMzcgMTQgMjAgMTRDMTYuNjg2MyAxNCAxNCAxNi42ODYzIDE0IDIwQzE0IDIzLjMxMzcgMTYuNjg2MyAyNiAyMCAyNloiIGZpbGw9IndoaXRlIi8+CjxkZWZzPgo8cmFkaWFsR3JhZGllbnQgaWQ9InBhaW50MF9yYWRpYWwiIGN4PSIwIiBjeT0iMCIgcj0iMSIgZ3JhZGllbnRVbml0cz0idXNlclNwYWNlT25Vc2UiIGdyYWRpZW50VHJhbnNmb3JtPSJ0cmFuc2xhdGUoMjAgMjApIHJvdGF0ZSg5MCkgc2NhbGUoMjApIj4KPHN0b3Agc3RvcC1jb2xvcj0iI0U3QUZGRiIvPgo8c3RvcCBvZmZzZXQ9IjEiIHN0b3AtY29sb3I9IiM4QTU1RkYiLz4KPC9yYWRpYWxHcmFkaWVudD4KPC9kZWZzPgo8L3N2Zz4K";
+    const PATH: &'static [&'static str] = &["glowSolana"];
+    const FLAG: Option<&'static str> = Some("isGlow");
+}
+
+pub type GlowWalletAdapter = InjectedWalletAdapter<GlowProvider>;
@@ -0,0 +1,499 @@
+use self::wasmgen::InjectedSolana;
+use crate::detect::{detect_ready_state, DetectionWatcher};
+use crate::{
+    from_js_message_signature, from_js_sign_in_output, from_js_signature, from_js_transaction,
+    from_js_transactions, has_method, injected_provider, proxy::WalletEventListener,
+    to_js_connect_options, to_js_sign_in_input, to_js_transactions, IntoPubkey,
+    IntoWasmTransaction, WasmTransaction,
+};
+use async_trait::async_trait;
+use solana_client_wasm::solana_sdk::{
+    pubkey::Pubkey, signature::Signature, transaction::TransactionVersion,
+};
+use solana_wallet_adapter_base::{
+    sign_in_with_message, with_timeout, ConnectOptions, EventSubscription, ListenerStatus,
+    SignInInput, SignInOutput, WalletAdapter, WalletAdapterBase, WalletAdapterMetadata,
+    WalletCapabilities, WalletError, WalletReadyState, WalletTransaction,
+    DEFAULT_TRANSACTION_VERSIONS,
+};
+use std::{
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
+use wasm_bindgen::JsValue;
+
+pub mod wasmgen {
+    use crate::{has_method, JsPublicKey, WalletEventEmitter, WasmTransaction};
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        /// The solana provider injected by a wallet extension.
+        #[derive(Debug, Clone)]
+        pub type InjectedSolana;
+
+        #[wasm_bindgen(method, getter=isConnected)]
+        pub fn is_connected(this: &InjectedSolana) -> bool;
+
+        #[wasm_bindgen(method, getter=priorityFeesSupported)]
+        pub fn priority_fees_supported(this: &InjectedSolana) -> bool;
+
+        #[wasm_bindgen(method, getter=publicKey)]
+        pub fn pubkey(this: &InjectedSolana) -> JsPublicKey;
+
+        #[wasm_bindgen(method, catch)]
+        pub fn on(
+            this: &InjectedSolana,
+            e: &str,
+            cb: &Closure<dyn FnMut(web_sys::Event)>,
+        ) -> Result<JsValue, JsValue>;
+
+        #[wasm_bindgen(method, catch)]
+        pub fn off(
+            this: &InjectedSolana,
+            e: &str,
+            cb: &Closure<dyn FnMut(web_sys::Event)>,
+        ) -> Result<JsValue, JsValue>;
+
+        #[wasm_bindgen(method, catch, js_name=removeListener)]
+        pub fn remove_listener(
+            this: &InjectedSolana,
+            e: &str,
+            cb: &Closure<dyn FnMut(web_sys::Event)>,
+        ) -> Result<JsValue, JsValue>;
+
+        #[wasm_bindgen(method, catch, js_name=connect)]
+        pub async fn connect(this: &InjectedSolana, options: &JsValue) -> Result<JsValue, JsValue>;
+        #[wasm_bindgen(method, js_name=disconnect)]
+        pub async fn disconnect(this: &InjectedSolana);
+
+        #[wasm_bindgen(method, catch, js_name=signMessage)]
+        pub async fn sign_message(
+            this: &InjectedSolana,
+            message: Vec<u8>,
+            pubkey: Option<JsPublicKey>,
+        ) -> Result<JsValue, JsValue>;
+
+        #[wasm_bindgen(method, catch, js_name=signTransaction)]
+        pub async fn sign_transaction(
+            this: &InjectedSolana,
+            transaction: WasmTransaction,
+        ) -> Result<JsValue, JsValue>;
+
+        #[wasm_bindgen(method, catch, js_name=signAllTransactions)]
+        pub async fn sign_all_transactions(
+            this: &InjectedSolana,
+            transactions: js_sys::Array,
+        ) -> Result<JsValue, JsValue>;
+
+        #[wasm_bindgen(method, catch, js_name=signAndSendTransaction)]
+        pub async fn sign_and_send_transaction(
+            this: &InjectedSolana,
+            transaction: WasmTransaction,
+        ) -> Result<JsValue, JsValue>;
+
+        #[wasm_bindgen(method, catch, js_name=send)]
+        pub async fn send(
+            this: &InjectedSolana,
+            transaction: WasmTransaction,
+        ) -> Result<JsValue, JsValue>;
+
+        #[wasm_bindgen(method, catch, js_name=signIn)]
+        pub async fn sign_in(this: &InjectedSolana, input: JsValue) -> Result<JsValue, JsValue>;
+    }
+
+    impl InjectedSolana {
+        /// Some providers do not expose `on`, and throw when it is called.
+        fn on_event(&self, e: &str, closure: &Closure<dyn FnMut(web_sys::Event)>) {
+            if !has_method(self, "on") {
+                return;
+            }
+            if let Err(err) = self.on(e, closure) {
+                log::error!("Error subscribing to {} events. {:?}", e, err.as_string());
+            }
+        }
+
+        /// Providers expose either `off` or the node-style `removeListener`, some expose neither.
+        fn off_event(&self, e: &str, closure: &Closure<dyn FnMut(web_sys::Event)>) -> bool {
+            let result = if has_method(self, "off") {
+                self.off(e, closure)
+            } else if has_method(self, "removeListener") {
+                self.remove_listener(e, closure)
+            } else {
+                return false;
+            };
+            match result {
+                Ok(_) => true,
+                Err(err) => {
+                    log::error!(
+                        "Error unsubscribing from {} events. {:?}",
+                        e,
+                        err.as_string()
+                    );
+                    false
+                }
+            }
+        }
+    }
+
+    impl WalletEventEmitter for InjectedSolana {
+        fn on_connect(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) {
+            self.on_event("connect", closure);
+        }
+
+        fn on_disconnect(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) {
+            self.on_event("disconnect", closure);
+        }
+
+        fn on_error(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) {
+            self.on_event("error", closure);
+        }
+
+        fn on_ready_state_change(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) {
+            self.on_event("readyStateChange", closure);
+        }
+
+        fn on_account_changed(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) {
+            self.on_event("accountChanged", closure);
+        }
+
+        fn off_connect(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) -> bool {
+            self.off_event("connect", closure)
+        }
+
+        fn off_disconnect(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) -> bool {
+            self.off_event("disconnect", closure)
+        }
+
+        fn off_error(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) -> bool {
+            self.off_event("error", closure)
+        }
+
+        fn off_ready_state_change(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) -> bool {
+            self.off_event("readyStateChange", closure)
+        }
+
+        fn off_account_changed(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) -> bool {
+            self.off_event("accountChanged", closure)
+        }
+    }
+}
+
+/// Describes a wallet extension that injects a solana provider into `window`.
+///
+/// Adding a wallet only takes its metadata, the [`InjectedWalletAdapter`] does the rest.
+#[async_trait(?Send)]
+pub trait InjectedProvider: std::fmt::Debug + 'static {
+    const NAME: &'static str;
+    const URL: &'static str;
+    const ICON: &'static str;
+    /// Where the provider is injected, e.g. `["phantom", "solana"]` for `window.phantom.solana`.
+    const PATH: &'static [&'static str];
+    /// The flag the provider sets on itself, e.g. `isPhantom`, if it sets one.
+    const FLAG: Option<&'static str>;
+    /// The transaction versions the wallet signs.
+    const TRANSACTION_VERSIONS: &'static [TransactionVersion] = DEFAULT_TRANSACTION_VERSIONS;
+
+    /// What the provider supports, read from the methods it exposes.
+    fn capabilities(provider: &InjectedSolana) -> WalletCapabilities {
+        provider_capabilities(
+            provider,
+            Self::TRANSACTION_VERSIONS,
+            "signAndSendTransaction",
+        )
+    }
+
+    /// The argument passed to the provider's `connect`.
+    fn connect_args(options: &ConnectOptions) -> JsValue {
+        to_js_connect_options(options)
+    }
+
+    /// Requests the provider to sign and send the transaction.
+    async fn sign_and_send_transaction(
+        provider: &InjectedSolana,
+        transaction: WasmTransaction,
+    ) -> Result<JsValue, JsValue> {
+        provider.sign_and_send_transaction(transaction).await
+    }
+}
+
+/// The capabilities of a provider, given the name of its `signAndSendTransaction` method.
+pub(crate) fn provider_capabilities(
+    provider: &InjectedSolana,
+    versions: &[TransactionVersion],
+    sign_and_send_method: &str,
+) -> WalletCapabilities {
+    let sign_message = has_method(provider, "signMessage");
+    WalletCapabilities {
+        supported_transaction_versions: versions.to_vec(),
+        sign_message,
+        // Signing in falls back to signing a message
+        sign_in: sign_message || has_method(provider, "signIn"),
+        sign_and_send_transaction: has_method(provider, sign_and_send_method),
+        sign_all_transactions: has_method(provider, "signAllTransactions"),
+    }
+}
+
+/// A wallet adapter for the provider injected by a wallet extension.
+#[derive(Debug)]
+pub struct InjectedWalletAdapter<P: InjectedProvider> {
+    connecting: Mutex<bool>,
+    connected: Mutex<bool>,
+    pubkey: Pubkey,
+    wrs: Arc<Mutex<WalletReadyState>>,
+    wel: Arc<Mutex<WalletEventListener>>,
+    _detection: Option<DetectionWatcher>,
+    _provider: PhantomData<P>,
+}
+
+impl<P: InjectedProvider> Clone for InjectedWalletAdapter<P> {
+    fn clone(&self) -> Self {
+        Self {
+            connecting: self.connecting().into(),
+            connected: Mutex::new(*self.connected.lock().unwrap()),
+            pubkey: self.pubkey,
+            wrs: self.wrs.clone(),
+            wel: self.wel.clone(),
+            _detection: self._detection.clone(),
+            _provider: PhantomData,
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.connecting = source.connecting().into();
+        self.connected = Mutex::new(*source.connected.lock().unwrap());
+        self.pubkey = source.pubkey;
+        self.wel = source.wel.clone();
+        self._detection = source._detection.clone();
+        self.wrs = source.wrs.clone();
+    }
+}
+
+impl<P: InjectedProvider> PartialEq for InjectedWalletAdapter<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.pubkey == other.pubkey
+            && self.connecting() == other.connecting()
+            && *self.connected.lock().unwrap() == *other.connected.lock().unwrap()
+    }
+}
+
+impl<P: InjectedProvider> InjectedWalletAdapter<P> {
+    pub fn new() -> Self {
+        let wrs = Arc::new(Mutex::new(WalletReadyState::default()));
+        let wel = Arc::new(Mutex::new(WalletEventListener::unbound()));
+        let detection = detect_ready_state(Self::lookup, wrs.clone(), wel.clone());
+        let pubkey = Self::lookup().map(|p| p.pubkey()).filter(|p| !p.is_null());
+        let connected = Self::lookup().filter(|p| p.is_connected());
+        // The wallet may have reconnected the app on its own, the session is already active
+        if let Some(p) = &connected {
+            wel.lock().unwrap().listen(p);
+        }
+
+        Self {
+            connecting: false.into(),
+            connected: connected.is_some().into(),
+            pubkey: pubkey.map_or(Pubkey::default(), |p| p.to_pubkey()),
+            wrs,
+            wel,
+            _detection: detection,
+            _provider: PhantomData,
+        }
+    }
+
+    /// The injected provider, when it carries the wallet's flag. Other wallets inject at the
+    /// same path and are not taken for this one.
+    fn lookup() -> Option<InjectedSolana> {
+        injected_provider::<InjectedSolana>(P::PATH).filter(|provider| match P::FLAG {
+            Some(flag) => {
+                js_sys::Reflect::get(provider, &flag.into()).map_or(false, |f| f.is_truthy())
+            }
+            None => true,
+        })
+    }
+
+    /// The injected provider, until it is detected requests fail with [`WalletError::NotReady`].
+    fn provider(&self) -> Result<InjectedSolana, WalletError> {
+        Self::lookup().ok_or(WalletError::NotReady)
+    }
+}
+
+impl<P: InjectedProvider> Default for InjectedWalletAdapter<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: InjectedProvider> WalletAdapterMetadata for InjectedWalletAdapter<P> {
+    fn name(&self) -> &str {
+        P::NAME
+    }
+
+    fn url(&self) -> &str {
+        P::URL
+    }
+
+    fn icon(&self) -> &str {
+        P::ICON
+    }
+}
+
+#[async_trait(?Send)]
+impl<P: InjectedProvider> WalletAdapterBase for InjectedWalletAdapter<P> {
+    fn is_name(&self) -> bool {
+        Self::lookup().is_some()
+    }
+
+    fn is_connected(&self) -> bool {
+        Self::lookup().map_or(false, |p| p.is_connected())
+    }
+
+    fn ready_state(&self) -> WalletReadyState {
+        self.wrs.lock().unwrap().clone()
+    }
+
+    fn pubkey(&self) -> Option<Pubkey> {
+        let pubkey = Self::lookup()?.pubkey();
+        if pubkey.is_null() {
+            None
+        } else {
+            Some(pubkey.to_pubkey())
+        }
+    }
+
+    fn connecting(&self) -> bool {
+        *self.connecting.lock().unwrap()
+    }
+
+    async fn connect(&self, options: ConnectOptions) -> Result<Pubkey, WalletError> {
+        let provider = self.provider()?;
+        *self.connecting.lock().unwrap() = true;
+        // Bound before connecting, the provider emits `connect` before the request resolves
+        self.wel.lock().unwrap().listen(&provider);
+        let result = with_timeout(
+            provider.connect(&P::connect_args(&options)),
+            options.timeout,
+        )
+        .await
+        .and_then(|r| r.map_err(|e| WalletError::from_js(&e, WalletError::Connection)))
+        .and_then(|_| self.pubkey().ok_or(WalletError::Pubkey));
+        *self.connecting.lock().unwrap() = false;
+
+        match &result {
+            Ok(pubkey) => log::info!("Successfully invoked connect. {:?}", pubkey),
+            Err(e) => {
+                log::error!("Error invoking connect. {}", e);
+                if !provider.is_connected() {
+                    self.wel.lock().unwrap().unlisten();
+                }
+            }
+        }
+        result
+    }
+
+    async fn disconnect(&self) {
+        if let Some(p) = Self::lookup() {
+            p.disconnect().await
+        }
+        // The provider emitted `disconnect` before the request resolved, the session is over
+        let status = self.wel.lock().unwrap().unlisten();
+        log::info!("Unbound {} event listeners. {:?}", P::NAME, status);
+    }
+
+    async fn sign_transaction(
+        &self,
+        transaction: WalletTransaction,
+    ) -> Result<WalletTransaction, WalletError> {
+        match self
+            .provider()?
+            .sign_transaction(transaction.to_wasm_transaction()?)
+            .await
+        {
+            Ok(r) => from_js_transaction(&transaction, r),
+            Err(e) => {
+                let error = WalletError::from_js(&e, WalletError::SignTransaction);
+                log::error!("Error invoking signTransaction. {}", error);
+                Err(error)
+            }
+        }
+    }
+
+    async fn sign_all_transactions(
+        &self,
+        transactions: Vec<WalletTransaction>,
+    ) -> Result<Vec<WalletTransaction>, WalletError> {
+        match self
+            .provider()?
+            .sign_all_transactions(to_js_transactions(&transactions)?)
+            .await
+        {
+            Ok(r) => from_js_transactions(&transactions, r),
+            Err(e) => {
+                let error = WalletError::from_js(&e, WalletError::SignTransaction);
+                log::error!("Error invoking signAllTransactions. {}", error);
+                Err(error)
+            }
+        }
+    }
+
+    async fn sign_and_send_transaction(
+        &self,
+        transaction: WalletTransaction,
+    ) -> Result<Signature, WalletError> {
+        match P::sign_and_send_transaction(&self.provider()?, transaction.to_wasm_transaction()?)
+            .await
+        {
+            Ok(r) => from_js_signature(r),
+            Err(e) => {
+                let error = WalletError::from_js(&e, WalletError::SendTransaction);
+                log::error!("Error invoking signAndSendTransaction. {}", error);
+                Err(error)
+            }
+        }
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature, WalletError> {
+        match self.provider()?.sign_message(message.to_vec(), None).await {
+            Ok(r) => from_js_message_signature(r),
+            Err(e) => {
+                let error = WalletError::from_js(&e, WalletError::SignMessage);
+                log::error!("Error invoking signMessage. {}", error);
+                Err(error)
+            }
+        }
+    }
+
+    async fn sign_in(&self, input: SignInInput) -> Result<SignInOutput, WalletError> {
+        let provider = self.provider()?;
+        if !has_method(&provider, "signIn") {
+            return sign_in_with_message(self, &input).await;
+        }
+        match provider.sign_in(to_js_sign_in_input(&input)?).await {
+            Ok(r) => from_js_sign_in_output(r),
+            Err(e) => {
+                let error = WalletError::from_js(&e, WalletError::SignIn);
+                log::error!("Error invoking signIn. {}", error);
+                Err(error)
+            }
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<P: InjectedProvider> WalletAdapter for InjectedWalletAdapter<P> {
+    fn priority_fees_supported(&self) -> bool {
+        Self::lookup().map_or(false, |p| p.priority_fees_supported())
+    }
+
+    /// Nothing is supported until the provider is detected.
+    fn capabilities(&self) -> WalletCapabilities {
+        Self::lookup().map_or(WalletCapabilities::default(), |p| P::capabilities(&p))
+    }
+
+    fn subscribe(&self) -> EventSubscription {
+        self.wel.lock().unwrap().subscribe()
+    }
+
+    fn listener_status(&self) -> ListenerStatus {
+        self.wel.lock().unwrap().status()
+    }
+}
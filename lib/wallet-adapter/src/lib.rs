@@ -1,38 +1,103 @@
+#[cfg(feature = "backpack")]
 pub mod backpack;
+#[cfg(feature = "coinbase")]
+pub mod coinbase;
+#[cfg(feature = "deeplink")]
+pub mod deeplink;
+#[cfg(feature = "exodus")]
+pub mod exodus;
+#[cfg(feature = "glow")]
+pub mod glow;
+mod detect;
+pub mod injected;
+#[cfg(feature = "nightly")]
 pub mod nightly;
+#[cfg(feature = "okx")]
+pub mod okx;
+#[cfg(feature = "phantom")]
 pub mod phantom;
+pub mod popup;
 mod proxy;
+pub mod registry;
+#[cfg(feature = "solflare")]
 pub mod solflare;
+#[cfg(feature = "squads")]
+pub mod squads;
+#[cfg(feature = "trust")]
+pub mod trust;
+pub mod standard;
 mod wasmgen;
 
 pub mod prelude {
-    pub use crate::{IntoPubkey, IntoWallet, IntoWalletAdapter, IntoWalletName};
+    pub use crate::{
+        IntoPubkey, IntoWallet, IntoWalletAdapter, IntoWalletName, IntoWasmTransaction,
+    };
 
     #[cfg(feature = "backpack")]
-    pub use crate::backpack::BackpackWalletAdapter;
+    pub use crate::backpack::{BackpackProvider, BackpackWalletAdapter};
+    #[cfg(feature = "coinbase")]
+    pub use crate::coinbase::{CoinbaseProvider, CoinbaseWalletAdapter};
+    #[cfg(feature = "exodus")]
+    pub use crate::exodus::{ExodusProvider, ExodusWalletAdapter};
+    #[cfg(feature = "glow")]
+    pub use crate::glow::{GlowProvider, GlowWalletAdapter};
     #[cfg(feature = "nightly")]
-    pub use crate::nightly::NightlyWalletAdapter;
+    pub use crate::nightly::{NightlyProvider, NightlyWalletAdapter};
+    #[cfg(feature = "okx")]
+    pub use crate::okx::{OkxProvider, OkxWalletAdapter};
     #[cfg(feature = "phantom")]
-    pub use crate::phantom::PhantomWalletAdapter;
+    pub use crate::phantom::{PhantomProvider, PhantomWalletAdapter};
     #[cfg(feature = "solflare")]
-    pub use crate::solflare::SolflareWalletAdapter;
+    pub use crate::solflare::{SolflareProvider, SolflareWalletAdapter, SolflareWebWalletAdapter};
+    #[cfg(feature = "trust")]
+    pub use crate::trust::{TrustProvider, TrustWalletAdapter};
+    #[cfg(feature = "deeplink")]
+    pub use crate::deeplink::{DeepLinkCluster, DeepLinkProvider, DeepLinkWalletAdapter};
+    #[cfg(all(feature = "deeplink", feature = "phantom"))]
+    pub use crate::phantom::PhantomDeepLinkAdapter;
+    #[cfg(all(feature = "deeplink", feature = "solflare"))]
+    pub use crate::solflare::SolflareDeepLinkAdapter;
+    pub use crate::injected::{InjectedProvider, InjectedWalletAdapter};
+    pub use crate::popup::{PopupProvider, PopupWalletAdapter, PopupWindow};
+    #[cfg(feature = "squads")]
+    pub use crate::squads::SquadsWalletAdapter;
+    pub use crate::registry::WalletRegistry;
+    pub use crate::standard::{StandardWalletAdapter, StandardWalletInfo};
 }
 
-use std::sync::Arc;
-use wasm_bindgen::prelude::Closure;
+use std::{str::FromStr, sync::Arc};
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+pub use registry::{RegisteredWalletInfo, WalletRegistry};
 pub use wasmgen::*;
 
 #[cfg(feature = "backpack")]
-use crate::backpack::BackpackWalletAdapter;
+use crate::backpack::{BackpackProvider, BackpackWalletAdapter};
+#[cfg(feature = "coinbase")]
+use crate::coinbase::{CoinbaseProvider, CoinbaseWalletAdapter};
+#[cfg(feature = "exodus")]
+use crate::exodus::{ExodusProvider, ExodusWalletAdapter};
+#[cfg(feature = "glow")]
+use crate::glow::{GlowProvider, GlowWalletAdapter};
 #[cfg(feature = "nightly")]
-use crate::nightly::NightlyWalletAdapter;
+use crate::nightly::{NightlyProvider, NightlyWalletAdapter};
+#[cfg(feature = "okx")]
+use crate::okx::{OkxProvider, OkxWalletAdapter};
 #[cfg(feature = "phantom")]
-use crate::phantom::PhantomWalletAdapter;
+use crate::phantom::{PhantomProvider, PhantomWalletAdapter};
 #[cfg(feature = "solflare")]
-use crate::solflare::SolflareWalletAdapter;
+use crate::solflare::{SolflareProvider, SolflareWalletAdapter};
+#[cfg(feature = "trust")]
+use crate::trust::{TrustProvider, TrustWalletAdapter};
+#[allow(unused_imports)]
+use crate::injected::InjectedProvider;
+use crate::standard::{find_standard_wallet, StandardWalletAdapter, StandardWalletInfo};
 
 use anchor_lang::prelude::Pubkey;
-use solana_wallet_adapter_base::WalletAdapter;
+use solana_client_wasm::solana_sdk::{signature::Signature, transaction::TransactionVersion};
+use solana_wallet_adapter_base::{
+    ConnectOptions, SignInInput, SignInOutput, WalletAdapter, WalletError, WalletTransaction, WatchWalletAdapter,
+    WATCH_WALLET_ICON, WATCH_WALLET_NAME,
+};
 
 #[derive(Debug, Default, Clone, PartialEq)]
 pub enum Wallet {
@@ -40,12 +105,28 @@ pub enum Wallet {
     Default,
     #[cfg(feature = "backpack")]
     Backpack,
+    #[cfg(feature = "coinbase")]
+    Coinbase,
+    #[cfg(feature = "exodus")]
+    Exodus,
+    #[cfg(feature = "glow")]
+    Glow,
     #[cfg(feature = "nightly")]
     Nightly,
+    #[cfg(feature = "okx")]
+    Okx,
     #[cfg(feature = "phantom")]
     Phantom,
     #[cfg(feature = "solflare")]
     Solflare,
+    #[cfg(feature = "trust")]
+    Trust,
+    /// A wallet discovered through the wallet standard.
+    Standard(StandardWalletInfo),
+    /// A wallet the app registered an adapter for, it is only available through the registry.
+    Registered(RegisteredWalletInfo),
+    /// A read-only wallet for the given address.
+    Watch(Pubkey),
 }
 
 pub trait IntoWalletName {
@@ -61,7 +142,8 @@ pub trait IntoWalletIcon {
 }
 
 pub trait IntoWalletAdapter {
-    fn into_wallet_adapter(&self) -> Arc<dyn WalletAdapter>;
+    /// Builds the adapter of the wallet, `None` when there is nothing to build it from.
+    fn into_wallet_adapter(&self) -> Option<Arc<dyn WalletAdapter>>;
 }
 
 pub trait IntoWallet {
@@ -69,19 +151,34 @@ pub trait IntoWallet {
 }
 
 impl IntoWalletAdapter for Wallet {
-    fn into_wallet_adapter(&self) -> Arc<dyn WalletAdapter> {
+    fn into_wallet_adapter(&self) -> Option<Arc<dyn WalletAdapter>> {
         use Wallet::*;
-        match self {
-            Default => panic!("cannot build default wallet adapter"),
+        let adapter: Arc<dyn WalletAdapter> = match self {
+            Default => return None,
             #[cfg(feature = "backpack")]
             Backpack => Arc::new(BackpackWalletAdapter::new()),
+            #[cfg(feature = "coinbase")]
+            Coinbase => Arc::new(CoinbaseWalletAdapter::new()),
+            #[cfg(feature = "exodus")]
+            Exodus => Arc::new(ExodusWalletAdapter::new()),
+            #[cfg(feature = "glow")]
+            Glow => Arc::new(GlowWalletAdapter::new()),
             #[cfg(feature = "nightly")]
             Nightly => Arc::new(NightlyWalletAdapter::new()),
+            #[cfg(feature = "okx")]
+            Okx => Arc::new(OkxWalletAdapter::new()),
             #[cfg(feature = "phantom")]
             Phantom => Arc::new(PhantomWalletAdapter::new()),
             #[cfg(feature = "solflare")]
             Solflare => Arc::new(SolflareWalletAdapter::new()),
-        }
+            #[cfg(feature = "trust")]
+            Trust => Arc::new(TrustWalletAdapter::new()),
+            // The wallet may have been persisted by a previous session and not be registered yet
+            Standard(info) => Arc::new(StandardWalletAdapter::new(&info.name)?),
+            Registered(_) => return None,
+            Watch(pubkey) => Arc::new(WatchWalletAdapter::new(*pubkey)),
+        };
+        Some(adapter)
     }
 }
 
@@ -90,14 +187,33 @@ impl IntoWallet for Arc<dyn WalletAdapter> {
         use Wallet::*;
         match self.name() {
             #[cfg(feature = "backpack")]
-            "Backpack" => Backpack,
+            BackpackProvider::NAME => Backpack,
+            #[cfg(feature = "coinbase")]
+            CoinbaseProvider::NAME => Coinbase,
+            #[cfg(feature = "exodus")]
+            ExodusProvider::NAME => Exodus,
+            #[cfg(feature = "glow")]
+            GlowProvider::NAME => Glow,
             #[cfg(feature = "nightly")]
-            "Nightly" => Nightly,
+            NightlyProvider::NAME => Nightly,
+            #[cfg(feature = "okx")]
+            OkxProvider::NAME => Okx,
             #[cfg(feature = "phantom")]
-            "Phantom" => Phantom,
+            PhantomProvider::NAME => Phantom,
             #[cfg(feature = "solflare")]
-            "Solflare" => Solflare,
-            _ => unreachable!(),
+            SolflareProvider::NAME => Solflare,
+            #[cfg(feature = "trust")]
+            TrustProvider::NAME => Trust,
+            WATCH_WALLET_NAME => Watch(self.pubkey().unwrap_or_default()),
+            name if find_standard_wallet(name).is_some() => Standard(StandardWalletInfo {
+                name: name.to_string(),
+                icon: self.icon().to_string(),
+            }),
+            name => Registered(RegisteredWalletInfo {
+                name: name.to_string(),
+                url: self.url().to_string(),
+                icon: self.icon().to_string(),
+            }),
         }
     }
 }
@@ -107,14 +223,28 @@ impl IntoWallet for &str {
         use Wallet::*;
         match self {
             #[cfg(feature = "backpack")]
-            &"Backpack" => Backpack,
+            &BackpackProvider::NAME => Backpack,
+            #[cfg(feature = "coinbase")]
+            &CoinbaseProvider::NAME => Coinbase,
+            #[cfg(feature = "exodus")]
+            &ExodusProvider::NAME => Exodus,
+            #[cfg(feature = "glow")]
+            &GlowProvider::NAME => Glow,
             #[cfg(feature = "nightly")]
-            &"Nightly" => Nightly,
+            &NightlyProvider::NAME => Nightly,
+            #[cfg(feature = "okx")]
+            &OkxProvider::NAME => Okx,
             #[cfg(feature = "phantom")]
-            &"Phantom" => Phantom,
+            &PhantomProvider::NAME => Phantom,
             #[cfg(feature = "solflare")]
-            &"Solflare" => Solflare,
-            _ => unreachable!(),
+            &SolflareProvider::NAME => Solflare,
+            #[cfg(feature = "trust")]
+            &TrustProvider::NAME => Trust,
+            // Unknown names, such as a stale name from local storage, map to the default wallet
+            name => match find_standard_wallet(name) {
+                Some(wallet) => Standard(StandardWalletInfo::from(&wallet)),
+                None => Default,
+            },
         }
     }
 }
@@ -125,13 +255,26 @@ impl IntoWalletName for Wallet {
         match self {
             Default => "Default",
             #[cfg(feature = "backpack")]
-            Backpack => "Backpack",
+            Backpack => BackpackProvider::NAME,
+            #[cfg(feature = "coinbase")]
+            Coinbase => CoinbaseProvider::NAME,
+            #[cfg(feature = "exodus")]
+            Exodus => ExodusProvider::NAME,
+            #[cfg(feature = "glow")]
+            Glow => GlowProvider::NAME,
             #[cfg(feature = "nightly")]
-            Nightly => "Nightly",
+            Nightly => NightlyProvider::NAME,
+            #[cfg(feature = "okx")]
+            Okx => OkxProvider::NAME,
             #[cfg(feature = "phantom")]
-            Phantom => "Phantom",
+            Phantom => PhantomProvider::NAME,
             #[cfg(feature = "solflare")]
-            Solflare => "Solflare",
+            Solflare => SolflareProvider::NAME,
+            #[cfg(feature = "trust")]
+            Trust => TrustProvider::NAME,
+            Standard(info) => &info.name,
+            Registered(info) => &info.name,
+            Watch(_) => WATCH_WALLET_NAME,
         }
     }
 }
@@ -142,13 +285,26 @@ impl IntoWalletIcon for Wallet {
         match self {
             Default => "Default",
             #[cfg(feature = "backpack")]
-            Backpack => "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAIAAAACACAYAAADDPmHLAAAACXBIWXMAAAsTAAALEwEAmpwYAAAAAXNSR0IArs4c6QAAAARnQU1BAACxjwv8YQUAAAbvSURBVHgB7Z1dUtxGEMf/LZH3fU0V4PUJQg4QVj5BnBOAT2BzAsMJAicwPoHJCRDrAxifgLVxVV73ObDqdEtsjKn4C8+0NDv9e7AxprRC85uvnp4RYYW5qKpxCVTcYKsgfiDfGjMwIsZIvh7d/lkmzAiYy5fzhultyZhdlagf1vU5VhjCiiGFXq01zYSJdqWgx/hB5AHN5I/6iuilyFBjxVgZAdqCZ34ORoVIqAzSOhxsvq6PsSIkL4A281LwL2IW/F1UhLKgRz/X9QyJUyBhuuae31gWviLjiPF1wxeX29vPkTjJtgAftrd3GHSMnmHw4eZ0uodESVKAoRT+kpQlSE6Ats/XZv/ONK5vZHC49+B1fYjESG4MUDKfYmCFr0ic4fmHqtpCYiQlgA66QsztIzFi5j+RGMl0AXebfgn0aOTuvGG8owIarZsXOj3ronlRuEYnn84CJLo4Lgi/QL/H/LHmy/RwI6GA0RoS4acFHi8kGieFXS/QhmijFfQXmH3uPy5lSkoLbIkYlfyzhuM4juM4juM4juMMj6TzATQ4JH9tlRqFk8BM2aV9RWHB9K5kzK/KLui0KqliSQmgBa4BIS54cpMD0OeawFye3jk19JdKkWq62OAFkEIfrTXNUxBV1okf38Ot3MGjlFqHwQrQZvQ22Cfw7xjg6t8XkZaBGzpKIXdwcAJojZeCP5SC30HipJBEOigBZLn3qdzSPlKr8V9hyEmkgxCgj8zefuD9jen0AAOidwE0i6ZhfjXgRI+gDK016DUjqE3ubPhNLoWvaDLJouHToaSP9SbA0DJ7LekyiviNPgP0TC9dQM6FfxeZ7eyuT6cv0RPmAmjTx11uXx/MiegEDd425cfcwWV+H4O3+uiO+pTAVIA2uMN8av6QiWr5TQ++JVlTc/tEiF3jOMScZGC43kME0VSA95PJhWXhM+Gt1Phn98nStZa1r9mB2SDQPqefjhayfnDfFG2J5882z84eynVM5u3thlONhRhj0gLc5PRfwAw62JjW+wjE5Xa1L0VkshO4kXt/EPDev4ZJCyBRvlcwggjHG4EfYHc9OoIBBWy3mEUX4H1V7Ur7ZvILaT8qy7FRduleF9jXc4RggOUWs/gtANs0nYquvMXaMaTXlQHlE1ggayLvf5OKY0DUMYDWfmpsBjZa+9enOmiLy+VkcmqxaNW2ZgX9GnsLXNQWoGj4KYzQ2g8LyG5WUDR4hshEE6CN+AFmg5lFiRMYcI0uKRQGyIAwegWKJkBjYO8tzq12C7efQ7CK2I00MomIxOsCiCcwQhaW3sEQ6W7sPi/yIDqKAHp8m2nIF7COoc9ghQw4NU8SkYgiQCmLKXCCUSziPc84XYBh83/DSiWR3qUo2tT4ONdGYDTub73cSzD/PNt0rojdQHAByoXxw0E7XfoFhsjnRduD+DnWIkkXXACJl1cwRoMmf3cbRaOjLRzDXnKZVj9GBIILUJBtbVzyj9HAU19AgR6I9VzDtwCgMXpAo2Yxp0v/Ybi49ennJtIFEPMY/TCKHTvv+aTSUQzBgwrQ92YHbQVi3UN3GAVZhrf/jzECE1SAq/7n4yOJ074KPSBcJoii598vxgwrqAByg70HZJZbr0JJ0G5XZz5Z1e1rYccA5TAicqEk0O5ECl/3LvYys7mLTLHHCEzS7wz6Esv3+nyYTF58rwha63XAl8PG1aCnhesWq6EdOcKM3WvmXRHh+Gvv/tNVTJlJPC4a3RVEK72+sCSZ4+J/FBVhTUS43J7gJqFjrnl33A3sxtCa3nAWhX6bbAT4hJugCsNZ2TGA8224AJnjAmSOC5A5LkDmuACZ4wJkjguQOS5A5rgAmeMCZI4LkDkuQOa4AJnjAmSOC5A5LkDmuACZ4wJkjguQOWEFYJvz85xwBBWgKM1P68oKKsI/36ACdC9nsDlWPTsIJ5t1Hfw01OBjgI1p/YwLegIibw0CwESz9gUYZ2d/wHEcx3Ecx3Ecx3Ecx3HuS5QjfdrXxTHv3JzEkd2xKwHR9xPNuKGjzdf1MSIQXAA9XUsuuw8nKPpK3PWzs+AvrgwqgP1LojOjoEf3fRv6Zy+JgBSLOGfaOx1NE/6o+rCrgeT9fWp4SljmuACZ4wJkjguQOS5A5rgAmeMCZI4LkDkuQOa4AJnjAmSOC5A5LkDmuACZ4wJkjguQOS5A5rgAmeMCZI4LkDkuQOa4AJnj5wRmTlABqHQBohKhggUVYAEEP8fO+UiMgziDCvCwrnU3aw0nOATMQu8LVIIPAq+JdAerdwWBaQ/fjEBwAaQVmMnN7sEJCB3EqP3tlRGJy6qqmPkFMcZw7sucmfZiHQ6hRBNgSXdaCHbA7KeFfBvz9pxlxtl1gcN2XBWRfwHK959XFRG6AgAAAABJRU5ErkJggg==",
+            Backpack => BackpackProvider::ICON,
+            #[cfg(feature = "coinbase")]
+            Coinbase => CoinbaseProvider::ICON,
+            #[cfg(feature = "exodus")]
+            Exodus => ExodusProvider::ICON,
+            #[cfg(feature = "glow")]
+            Glow => GlowProvider::ICON,
             #[cfg(feature = "nightly")]
-            Nightly => "data:image/svg+xml;base64,PHN2ZyB3aWR0aD0iOTYiIGhlaWdodD0iOTYiIHZpZXdCb3g9IjAgMCA5NiA5NiIgZmlsbD0ibm9uZSIgeG1sbnM9Imh0dHA6Ly93d3cudzMub3JnLzIwMDAvc3ZnIj4KPHBhdGggZD0iTTQ4IDk2Qzc0LjUwOTcgOTYgOTYgNzQuNTA5NyA5NiA0OEM5NiAyMS40OTAzIDc0LjUwOTcgMCA0OCAwQzIxLjQ5MDMgMCAwIDIxLjQ5MDMgMCA0OEMwIDc0LjUwOTcgMjEuNDkwMyA5NiA0OCA5NloiIGZpbGw9IiM2RDczRjgiLz4KPHBhdGggZD0iTTQ4IDg1LjYzNTZDNDggODUuNjM1NiA1Mi40NTMzIDg1LjYzNTYgNTUuNDQgODIuNTg2N0M1OC45MTU1IDc5LjI4MDEgNTcuMzUxMSA3NS40MzEyIDYyLjI3NTUgNzEuNDMxMkM2Ni45ODY2IDY3LjY0NDUgNzIuOTI0NCA3MC4zMzc5IDcyLjkyNDQgNzAuMzM3OUM3Ny4wMjIyIDYyLjEyNDUgNzQuNzkxMSA1Mi41NjkgNzQuNzkxMSA1Mi41NjlDODEuNzY4OCAzNC4yNTc5IDc1Ljk2NDQgMjEuMTU1NyA3NC40NDQ0IDE3LjM2MDFDNjkuNDQ4OCAyNC4zMzc5IDYzLjE5MTEgMjkuMTczNCA1NS43OTU1IDMyLjQwOUM1My4yMjY2IDMxLjcwNjggNTAuNTk1NSAzMS4zMzM0IDQ4IDMxLjM2MDFDNDUuNDEzMyAzMS4zMzM0IDQyLjc3MzMgMzEuNzA2OCA0MC4yMDQ0IDMyLjQwOUMzMi44MTc3IDI5LjE2NDUgMjYuNTUxMSAyNC4zMzc5IDIxLjU1NTUgMTcuMzYwMUMyMC4wMzU1IDIxLjE1NTcgMTQuMjMxMSAzNC4yNTc5IDIxLjIwODkgNTIuNTY5QzIxLjIwODkgNTIuNTY5IDE4Ljk3NzggNjIuMTI0NSAyMy4wNzU1IDcwLjMzNzlDMjMuMDc1NSA3MC4zMzc5IDI5LjAxMzMgNjcuNjQ0NSAzMy43MjQ0IDcxLjQzMTJDMzguNjU3NyA3NS40MzEyIDM3LjA4NDQgNzkuMjgwMSA0MC41NiA4Mi41ODY3QzQzLjU0NjYgODUuNjM1NiA0OCA4NS42MzU2IDQ4IDg1LjYzNTZaIiBmaWxsPSJ3aGl0ZSIvPgo8cGF0aCBkPSJNNDIuNDc5OSA2NS4yOThDNDIuMjkzMyA1OS4yMTggMzYuMzAyMSA1Ny4yNjI0IDMyLjIxMzMgNTkuODIyNEMzMi4yMTMzIDU5LjgyMjQgMzIuODUzMyA2Mi40MzU4IDM1LjgzOTkgNjMuNzUxM0MzOC4yNzU1IDY0LjgyNjkgMzkuMzI0NCA2My4zODY5IDQyLjQ3OTkgNjUuMjk4WiIgZmlsbD0iIzdCODFGOSIvPgo8cGF0aCBkPSJNMjIuNDk3NyAyMy4wOTM1QzIwLjA4ODggMzEuNTQ2OCAyMS4xMjg4IDQyLjI0MDIgMjQuOTMzMyA1MC4wMjY5QzI4LjgyNjYgNDcuMjcxMyAzMi45MTU1IDQzLjAxMzUgMzUuMDkzMyAzOC41MDY5QzI5Ljk2NDQgMzQuNzExMyAyNS42NjIyIDMxLjEwMjQgMjIuNDk3NyAyMy4wOTM1WiIgZmlsbD0iIzdCODFGOSIvPgo8cGF0aCBkPSJNNTMuNTE5OSA2NS4yOThDNTMuNzA2NiA1OS4yMTggNTkuNjk3NyA1Ny4yNjI0IDYzLjc4NjYgNTkuODIyNEM2My43ODY2IDU5LjgyMjQgNjMuMTQ2NiA2Mi40MzU4IDYwLjE1OTkgNjMuNzUxM0M1Ny43MjQzIDY0LjgyNjkgNTYuNjc1NSA2My4zODY5IDUzLjUxOTkgNjUuMjk4WiIgZmlsbD0iIzdCODFGOSIvPgo8cGF0aCBkPSJNNzMuNTAyMiAyMy4wOTM1Qzc1LjkxMTEgMzEuNTQ2OCA3NC44NzExIDQyLjI0MDIgNzEuMDY2NiA1MC4wMjY5QzY3LjE3MzMgNDcuMjcxMyA2My4wODQ0IDQzLjAxMzUgNjAuOTA2NiAzOC41MDY5QzY2LjAzNTUgMzQuNzExMyA3MC4zMzc3IDMxLjEwMjQgNzMuNTAyMiAyMy4wOTM1WiIgZmlsbD0iIzdCODFGOSIvPgo8cGF0aCBkPSJNNDcuOTk5OSA4NS4zMDY5QzUwLjE0MDQgODUuMzA2OSA1MS44NzU1IDgzLjc3ODcgNTEuODc1NSA4MS44OTM2QzUxLjg3NTUgODAuMDA4NCA1MC4xNDA0IDc4LjQ4MDIgNDcuOTk5OSA3OC40ODAyQzQ1Ljg1OTUgNzguNDgwMiA0NC4xMjQ0IDgwLjAwODQgNDQuMTI0NCA4MS44OTM2QzQ0LjEyNDQgODMuNzc4NyA0NS44NTk1IDg1LjMwNjkgNDcuOTk5OSA4NS4zMDY5WiIgZmlsbD0iIzdCODFGOSIvPgo8L3N2Zz4K",
+            Nightly => NightlyProvider::ICON,
+            #[cfg(feature = "okx")]
+            Okx => OkxProvider::ICON,
             #[cfg(feature = "phantom")]
-            Phantom => "data:image/svg+xml;base64,PHN2ZyB4bWxucz0iaHR0cDovL3d3dy53My5vcmcvMjAwMC9zdmciIHdpZHRoPSIxMDgiIGhlaWdodD0iMTA4IiB2aWV3Qm94PSIwIDAgMTA4IDEwOCIgZmlsbD0ibm9uZSI+CjxyZWN0IHdpZHRoPSIxMDgiIGhlaWdodD0iMTA4IiByeD0iMjYiIGZpbGw9IiNBQjlGRjIiLz4KPHBhdGggZmlsbC1ydWxlPSJldmVub2RkIiBjbGlwLXJ1bGU9ImV2ZW5vZGQiIGQ9Ik00Ni41MjY3IDY5LjkyMjlDNDIuMDA1NCA3Ni44NTA5IDM0LjQyOTIgODUuNjE4MiAyNC4zNDggODUuNjE4MkMxOS41ODI0IDg1LjYxODIgMTUgODMuNjU2MyAxNSA3NS4xMzQyQzE1IDUzLjQzMDUgNDQuNjMyNiAxOS44MzI3IDcyLjEyNjggMTkuODMyN0M4Ny43NjggMTkuODMyNyA5NCAzMC42ODQ2IDk0IDQzLjAwNzlDOTQgNTguODI1OCA4My43MzU1IDc2LjkxMjIgNzMuNTMyMSA3Ni45MTIyQzcwLjI5MzkgNzYuOTEyMiA2OC43MDUzIDc1LjEzNDIgNjguNzA1MyA3Mi4zMTRDNjguNzA1MyA3MS41NzgzIDY4LjgyNzUgNzAuNzgxMiA2OS4wNzE5IDY5LjkyMjlDNjUuNTg5MyA3NS44Njk5IDU4Ljg2ODUgODEuMzg3OCA1Mi41NzU0IDgxLjM4NzhDNDcuOTkzIDgxLjM4NzggNDUuNjcxMyA3OC41MDYzIDQ1LjY3MTMgNzQuNDU5OEM0NS42NzEzIDcyLjk4ODQgNDUuOTc2OCA3MS40NTU2IDQ2LjUyNjcgNjkuOTIyOVpNODMuNjc2MSA0Mi41Nzk0QzgzLjY3NjEgNDYuMTcwNCA4MS41NTc1IDQ3Ljk2NTggNzkuMTg3NSA0Ny45NjU4Qzc2Ljc4MTYgNDcuOTY1OCA3NC42OTg5IDQ2LjE3MDQgNzQuNjk4OSA0Mi41Nzk0Qzc0LjY5ODkgMzguOTg4NSA3Ni43ODE2IDM3LjE5MzEgNzkuMTg3NSAzNy4xOTMxQzgxLjU1NzUgMzcuMTkzMSA4My42NzYxIDM4Ljk4ODUgODMuNjc2MSA0Mi41Nzk0Wk03MC4yMTAzIDQyLjU3OTVDNzAuMjEwMyA0Ni4xNzA0IDY4LjA5MTYgNDcuOTY1OCA2NS43MjE2IDQ3Ljk2NThDNjMuMzE1NyA0Ny45NjU4IDYxLjIzMyA0Ni4xNzA0IDYxLjIzMyA0Mi41Nzk1QzYxLjIzMyAzOC45ODg1IDYzLjMxNTcgMzcuMTkzMSA2NS43MjE2IDM3LjE5MzFDNjguMDkxNiAzNy4xOTMxIDcwLjIxMDMgMzguOTg4NSA3MC4yMTAzIDQyLjU3OTVaIiBmaWxsPSIjRkZGREY4Ii8+Cjwvc3ZnPg==",
+            Phantom => PhantomProvider::ICON,
             #[cfg(feature = "solflare")]
-            Solflare => "data:image/svg+xml;base64,PHN2ZyBmaWxsPSJub25lIiBoZWlnaHQ9IjUwIiB2aWV3Qm94PSIwIDAgNTAgNTAiIHdpZHRoPSI1MCIgeG1sbnM9Imh0dHA6Ly93d3cudzMub3JnLzIwMDAvc3ZnIiB4bWxuczp4bGluaz0iaHR0cDovL3d3dy53My5vcmcvMTk5OS94bGluayI+PGxpbmVhckdyYWRpZW50IGlkPSJhIj48c3RvcCBvZmZzZXQ9IjAiIHN0b3AtY29sb3I9IiNmZmMxMGIiLz48c3RvcCBvZmZzZXQ9IjEiIHN0b3AtY29sb3I9IiNmYjNmMmUiLz48L2xpbmVhckdyYWRpZW50PjxsaW5lYXJHcmFkaWVudCBpZD0iYiIgZ3JhZGllbnRVbml0cz0idXNlclNwYWNlT25Vc2UiIHgxPSI2LjQ3ODM1IiB4Mj0iMzQuOTEwNyIgeGxpbms6aHJlZj0iI2EiIHkxPSI3LjkyIiB5Mj0iMzMuNjU5MyIvPjxyYWRpYWxHcmFkaWVudCBpZD0iYyIgY3g9IjAiIGN5PSIwIiBncmFkaWVudFRyYW5zZm9ybT0ibWF0cml4KDQuOTkyMTg4MzIgMTIuMDYzODc5NjMgLTEyLjE4MTEzNjU1IDUuMDQwNzEwNzQgMjIuNTIwMiAyMC42MTgzKSIgZ3JhZGllbnRVbml0cz0idXNlclNwYWNlT25Vc2UiIHI9IjEiIHhsaW5rOmhyZWY9IiNhIi8+PHBhdGggZD0ibTI1LjE3MDggNDcuOTEwNGMuNTI1IDAgLjk1MDcuNDIxLjk1MDcuOTQwM3MtLjQyNTcuOTQwMi0uOTUwNy45NDAyLS45NTA3LS40MjA5LS45NTA3LS45NDAyLjQyNTctLjk0MDMuOTUwNy0uOTQwM3ptLTEuMDMyOC00NC45MTU2NWMuNDY0Ni4wMzgzNi44Mzk4LjM5MDQuOTAyNy44NDY4MWwxLjEzMDcgOC4yMTU3NGMuMzc5OCAyLjcxNDMgMy42NTM1IDMuODkwNCA1LjY3NDMgMi4wNDU5bDExLjMyOTEtMTAuMzExNThjLjI3MzMtLjI0ODczLjY5ODktLjIzMTQ5Ljk1MDcuMDM4NTEuMjMwOS4yNDc3Mi4yMzc5LjYyNjk3LjAxNjEuODgyNzdsLTkuODc5MSAxMS4zOTU4Yy0xLjgxODcgMi4wOTQyLS40NzY4IDUuMzY0MyAyLjI5NTYgNS41OTc4bDguNzE2OC44NDAzYy40MzQxLjA0MTguNzUxNy40MjM0LjcwOTMuODUyNC0uMDM0OS4zNTM3LS4zMDc0LjYzOTUtLjY2MjguNjk0OWwtOS4xNTk0IDEuNDMwMmMtMi42NTkzLjM2MjUtMy44NjM2IDMuNTExNy0yLjEzMzkgNS41NTc2bDMuMjIgMy43OTYxYy4yNTk0LjMwNTguMjE4OC43NjE1LS4wOTA4IDEuMDE3OC0uMjYyMi4yMTcyLS42NDE5LjIyNTYtLjkxMzguMDIwM2wtMy45Njk0LTIuOTk3OGMtMi4xNDIxLTEuNjEwOS01LjIyOTctLjI0MTctNS40NTYxIDIuNDI0M2wtLjg3NDcgMTAuMzk3NmMtLjAzNjIuNDI5NS0uNDE3OC43NDg3LS44NTI1LjcxMy0uMzY5LS4wMzAzLS42NjcxLS4zMDk3LS43MTcxLS42NzIxbC0xLjM4NzEtMTAuMDQzN2MtLjM3MTctMi43MTQ0LTMuNjQ1NC0zLjg5MDQtNS42NzQzLTIuMDQ1OWwtMTIuMDUxOTUgMTAuOTc0Yy0uMjQ5NDcuMjI3MS0uNjM4MDkuMjExNC0uODY4LS4wMzUtLjIxMDk0LS4yMjYyLS4yMTczNS0uNTcyNC0uMDE0OTMtLjgwNmwxMC41MTgxOC0xMi4xMzg1YzEuODE4Ny0yLjA5NDIuNDg0OS01LjM2NDQtMi4yODc2LTUuNTk3OGwtOC43MTg3Mi0uODQwNWMtLjQzNDEzLS4wNDE4LS43NTE3Mi0uNDIzNS0uNzA5MzYtLjg1MjQuMDM0OTMtLjM1MzcuMzA3MzktLjYzOTQuNjYyNy0uNjk1bDkuMTUzMzgtMS40Mjk5YzIuNjU5NC0uMzYyNSAzLjg3MTgtMy41MTE3IDIuMTQyMS01LjU1NzZsLTIuMTkyLTIuNTg0MWMtLjMyMTctLjM3OTItLjI3MTMtLjk0NDMuMTEyNi0xLjI2MjEuMzI1My0uMjY5NC43OTYzLS4yNzk3IDEuMTMzNC0uMDI0OWwyLjY5MTggMi4wMzQ3YzIuMTQyMSAxLjYxMDkgNS4yMjk3LjI0MTcgNS40NTYxLTIuNDI0M2wuNzI0MS04LjU1OTk4Yy4wNDU3LS41NDA4LjUyNjUtLjk0MjU3IDEuMDczOS0uODk3Mzd6bS0yMy4xODczMyAyMC40Mzk2NWMuNTI1MDQgMCAuOTUwNjcuNDIxLjk1MDY3Ljk0MDNzLS40MjU2My45NDAzLS45NTA2Ny45NDAzYy0uNTI1MDQxIDAtLjk1MDY3LS40MjEtLjk1MDY3LS45NDAzcy40MjU2MjktLjk0MDMuOTUwNjctLjk0MDN6bTQ3LjY3OTczLS45NTQ3Yy41MjUgMCAuOTUwNy40MjEuOTUwNy45NDAzcy0uNDI1Ny45NDAyLS45NTA3Ljk0MDItLjk1MDctLjQyMDktLjk1MDctLjk0MDIuNDI1Ny0uOTQwMy45NTA3LS45NDAzem0tMjQuNjI5Ni0yMi40Nzk3Yy41MjUgMCAuOTUwNi40MjA5NzMuOTUwNi45NDAyNyAwIC41MTkzLS40MjU2Ljk0MDI3LS45NTA2Ljk0MDI3LS41MjUxIDAtLjk1MDctLjQyMDk3LS45NTA3LS45NDAyNyAwLS41MTkyOTcuNDI1Ni0uOTQwMjcuOTUwNy0uOTQwMjd6IiBmaWxsPSJ1cmwoI2IpIi8+PHBhdGggZD0ibTI0LjU3MSAzMi43NzkyYzQuOTU5NiAwIDguOTgwMi0zLjk3NjUgOC45ODAyLTguODgxOSAwLTQuOTA1My00LjAyMDYtOC44ODE5LTguOTgwMi04Ljg4MTlzLTguOTgwMiAzLjk3NjYtOC45ODAyIDguODgxOWMwIDQuOTA1NCA0LjAyMDYgOC44ODE5IDguOTgwMiA4Ljg4MTl6IiBmaWxsPSJ1cmwoI2MpIi8+PC9zdmc+",
+            Solflare => SolflareProvider::ICON,
+            #[cfg(feature = "trust")]
+            Trust => TrustProvider::ICON,
+            Standard(info) => &info.icon,
+            Registered(info) => &info.icon,
+            Watch(_) => WATCH_WALLET_ICON,
         }
     }
 }
@@ -159,13 +315,25 @@ impl IntoWalletUrl for Wallet {
         match self {
             Default => "Default",
             #[cfg(feature = "backpack")]
-            Backpack => "https://backpack.app/",
+            Backpack => BackpackProvider::URL,
+            #[cfg(feature = "coinbase")]
+            Coinbase => CoinbaseProvider::URL,
+            #[cfg(feature = "exodus")]
+            Exodus => ExodusProvider::URL,
+            #[cfg(feature = "glow")]
+            Glow => GlowProvider::URL,
             #[cfg(feature = "nightly")]
-            Nightly => "https://nightly.app/",
+            Nightly => NightlyProvider::URL,
+            #[cfg(feature = "okx")]
+            Okx => OkxProvider::URL,
             #[cfg(feature = "phantom")]
-            Phantom => "https://phantom.app/",
+            Phantom => PhantomProvider::URL,
             #[cfg(feature = "solflare")]
-            Solflare => "https://solflare.com/",
+            Solflare => SolflareProvider::URL,
+            #[cfg(feature = "trust")]
+            Trust => TrustProvider::URL,
+            Registered(info) => &info.url,
+            Standard(_) | Watch(_) => "",
         }
     }
 }
@@ -180,6 +348,201 @@ impl IntoPubkey for JsPublicKey {
     }
 }
 
+pub trait IntoWasmTransaction {
+    fn to_wasm_transaction(&self) -> Result<WasmTransaction, WalletError>;
+}
+
+impl IntoWasmTransaction for WalletTransaction {
+    fn to_wasm_transaction(&self) -> Result<WasmTransaction, WalletError> {
+        let version = match self.version() {
+            TransactionVersion::Legacy(_) => None,
+            TransactionVersion::Number(n) => Some(n),
+        };
+        Ok(WasmTransaction::new(self.serialize()?, version))
+    }
+}
+
+/// Reads back a transaction signed by a wallet provider, keeping the kind of the `original` transaction.
+pub(crate) fn from_js_transaction(
+    original: &WalletTransaction,
+    value: JsValue,
+) -> Result<WalletTransaction, WalletError> {
+    if value.is_undefined() || value.is_null() {
+        return Err(WalletError::SignTransaction);
+    }
+    // Legacy web3.js transactions refuse to serialize while signatures are missing
+    let config = js_sys::Object::new();
+    js_sys::Reflect::set(&config, &"requireAllSignatures".into(), &JsValue::FALSE)
+        .map_err(|_| WalletError::SignTransaction)?;
+    js_sys::Reflect::set(&config, &"verifySignatures".into(), &JsValue::FALSE)
+        .map_err(|_| WalletError::SignTransaction)?;
+    let bytes = value.unchecked_into::<JsTransaction>().serialize(&config);
+    original.deserialize_as(&bytes)
+}
+
+/// Reads back a batch of transactions signed by a wallet provider.
+pub(crate) fn from_js_transactions(
+    originals: &[WalletTransaction],
+    value: JsValue,
+) -> Result<Vec<WalletTransaction>, WalletError> {
+    let signed = value
+        .dyn_into::<js_sys::Array>()
+        .map_err(|_| WalletError::SignTransaction)?;
+    if signed.length() as usize != originals.len() {
+        log::error!(
+            "Wallet returned {} signed transactions, expected {}.",
+            signed.length(),
+            originals.len()
+        );
+        return Err(WalletError::SignTransaction);
+    }
+    originals
+        .iter()
+        .zip(signed.iter())
+        .map(|(original, value)| from_js_transaction(original, value))
+        .collect()
+}
+
+/// Builds the array of transactions handed to a provider's `signAllTransactions`.
+pub(crate) fn to_js_transactions(
+    transactions: &[WalletTransaction],
+) -> Result<js_sys::Array, WalletError> {
+    let array = js_sys::Array::new();
+    for transaction in transactions {
+        array.push(&transaction.to_wasm_transaction()?.into());
+    }
+    Ok(array)
+}
+
+/// Reads back the signature returned by a provider's `signAndSendTransaction`,
+/// which is either a base58 string or a `{ signature }` object.
+pub(crate) fn from_js_signature(value: JsValue) -> Result<Signature, WalletError> {
+    let signature = match value.as_string() {
+        Some(signature) => signature,
+        None => js_sys::Reflect::get(&value, &"signature".into())
+            .ok()
+            .and_then(|s| s.as_string())
+            .ok_or(WalletError::SendTransaction)?,
+    };
+    Signature::from_str(&signature).map_err(|e| {
+        log::error!("Failed to parse transaction signature: {:?}", e);
+        WalletError::SendTransaction
+    })
+}
+
+/// Reads back the signature returned by a provider's `signMessage`,
+/// which is either the raw signature bytes or a `{ signature }` object.
+pub(crate) fn from_js_message_signature(value: JsValue) -> Result<Signature, WalletError> {
+    let signature = if value.is_instance_of::<js_sys::Uint8Array>() {
+        value
+    } else {
+        js_sys::Reflect::get(&value, &"signature".into()).map_err(|_| WalletError::SignMessage)?
+    };
+    let bytes = signature
+        .dyn_into::<js_sys::Uint8Array>()
+        .map_err(|_| WalletError::SignMessage)?
+        .to_vec();
+    Signature::try_from(bytes).map_err(|_| {
+        log::error!("Wallet returned a malformed message signature.");
+        WalletError::SignMessage
+    })
+}
+
+/// Builds the options object handed to a provider's `connect`.
+pub(crate) fn to_js_connect_options(options: &ConnectOptions) -> JsValue {
+    let object = js_sys::Object::new();
+    if options.only_if_trusted {
+        let _ = js_sys::Reflect::set(&object, &"onlyIfTrusted".into(), &JsValue::TRUE);
+    }
+    object.into()
+}
+
+/// Looks up a provider injected into `window` by an extension, e.g. `["phantom", "solana"]`.
+///
+/// Extensions may inject their provider after the page loaded, so the lookup is repeated on
+/// every access instead of being cached like a `static` import.
+pub(crate) fn injected_provider<T: JsCast>(path: &[&str]) -> Option<T> {
+    let mut value: JsValue = web_sys::window()?.into();
+    for key in path {
+        value = js_sys::Reflect::get(&value, &(*key).into()).ok()?;
+        if value.is_undefined() || value.is_null() {
+            return None;
+        }
+    }
+    Some(value.unchecked_into())
+}
+
+/// Whether the provider object exposes a method with the given name.
+pub(crate) fn has_method(provider: &JsValue, name: &str) -> bool {
+    js_sys::Reflect::get(provider, &name.into()).map_or(false, |f| f.is_function())
+}
+
+/// Builds the `SolanaSignInInput` object handed to a provider's `signIn`.
+pub(crate) fn to_js_sign_in_input(input: &SignInInput) -> Result<JsValue, WalletError> {
+    let object = js_sys::Object::new();
+    let fields = [
+        ("domain", input.domain.clone()),
+        ("address", input.address.map(|a| a.to_string())),
+        ("statement", input.statement.clone()),
+        ("uri", input.uri.clone()),
+        ("version", input.version.clone()),
+        ("chainId", input.chain_id.clone()),
+        ("nonce", input.nonce.clone()),
+        ("issuedAt", input.issued_at.clone()),
+        ("expirationTime", input.expiration_time.clone()),
+        ("notBefore", input.not_before.clone()),
+        ("requestId", input.request_id.clone()),
+    ];
+    for (key, value) in fields {
+        if let Some(value) = value {
+            js_sys::Reflect::set(&object, &key.into(), &value.into())
+                .map_err(|_| WalletError::SignIn)?;
+        }
+    }
+    if !input.resources.is_empty() {
+        let resources: js_sys::Array = input.resources.iter().map(JsValue::from).collect();
+        js_sys::Reflect::set(&object, &"resources".into(), &resources)
+            .map_err(|_| WalletError::SignIn)?;
+    }
+    Ok(object.into())
+}
+
+/// Reads back the output of a provider's `signIn`.
+///
+/// Injected providers return the account as an `address` public key, while wallet standard
+/// implementations nest it in an `account` object.
+pub(crate) fn from_js_sign_in_output(value: JsValue) -> Result<SignInOutput, WalletError> {
+    let get = |target: &JsValue, key: &str| {
+        js_sys::Reflect::get(target, &key.into())
+            .ok()
+            .filter(|v| !v.is_undefined() && !v.is_null())
+    };
+    let to_bytes = |value: Option<JsValue>| {
+        value
+            .and_then(|v| v.dyn_into::<js_sys::Uint8Array>().ok())
+            .map(|v| v.to_vec())
+            .ok_or(WalletError::SignIn)
+    };
+
+    let address = get(&value, "address")
+        .or_else(|| get(&value, "account").and_then(|account| get(&account, "address")))
+        .ok_or(WalletError::SignIn)?;
+    let address = match address.as_string() {
+        Some(address) => address,
+        None => address.unchecked_into::<JsPublicKey>().to_string(),
+    };
+    let account = Pubkey::from_str(&address).map_err(|_| WalletError::SignIn)?;
+    let signed_message = to_bytes(get(&value, "signedMessage"))?;
+    let signature = Signature::try_from(to_bytes(get(&value, "signature"))?)
+        .map_err(|_| WalletError::SignIn)?;
+
+    Ok(SignInOutput {
+        account,
+        signed_message,
+        signature,
+    })
+}
+
 /// Defines shared logic regarding events that a wallet provider emits.
 pub(crate) trait WalletEventEmitter {
     /// On disconnect.
@@ -193,6 +556,24 @@ pub(crate) trait WalletEventEmitter {
 
     /// On ready state change.
     fn on_ready_state_change(&self, closure: &Closure<dyn FnMut(web_sys::Event)>);
+
+    /// On account changed.
+    fn on_account_changed(&self, closure: &Closure<dyn FnMut(web_sys::Event)>);
+
+    /// Off disconnect, returns whether the listener was removed.
+    fn off_disconnect(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) -> bool;
+
+    /// Off connect, returns whether the listener was removed.
+    fn off_connect(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) -> bool;
+
+    /// Off error, returns whether the listener was removed.
+    fn off_error(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) -> bool;
+
+    /// Off ready state change, returns whether the listener was removed.
+    fn off_ready_state_change(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) -> bool;
+
+    /// Off account changed, returns whether the listener was removed.
+    fn off_account_changed(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) -> bool;
 }
 
 lazy_static::lazy_static! {
@@ -200,11 +581,21 @@ lazy_static::lazy_static! {
     pub static ref WALLETS: Vec<Wallet> = vec![
         #[cfg(feature = "backpack")]
         Wallet::Backpack,
+        #[cfg(feature = "coinbase")]
+        Wallet::Coinbase,
+        #[cfg(feature = "exodus")]
+        Wallet::Exodus,
+        #[cfg(feature = "glow")]
+        Wallet::Glow,
         #[cfg(feature = "nightly")]
         Wallet::Nightly,
+        #[cfg(feature = "okx")]
+        Wallet::Okx,
         #[cfg(feature = "phantom")]
         Wallet::Phantom,
         #[cfg(feature = "solflare")]
-        Wallet::Solflare
+        Wallet::Solflare,
+        #[cfg(feature = "trust")]
+        Wallet::Trust,
     ];
 }
@@ -1,222 +1,14 @@
-#![allow(non_upper_case_globals)]
-use self::wasmgen::nightly;
-use crate::{
-    proxy::WalletEventListener, IntoPubkey, IntoWalletIcon, IntoWalletName, IntoWalletUrl, Wallet,
-};
-use async_trait::async_trait;
-use futures::StreamExt;
-use solana_client_wasm::solana_sdk::pubkey::Pubkey;
-use solana_wallet_adapter_base::{
-    Event, WalletAdapter, WalletAdapterBase, WalletAdapterMetadata, WalletReadyState,
-};
-use std::sync::Mutex;
-
-pub(crate) mod wasmgen {
-    use wasm_bindgen::prelude::*;
-
-    use crate::{JsPublicKey, WalletEventEmitter};
-
-    #[wasm_bindgen]
-    extern "C" {
-        pub type Nightly;
-        pub type NightlySolana;
-
-        pub static nightly: Nightly;
-
-        #[wasm_bindgen(method, getter=solana)]
-        pub fn solana(this: &Nightly) -> NightlySolana;
-
-        #[wasm_bindgen(method, getter=isConnected)]
-        pub fn is_connected(this: &NightlySolana) -> bool;
-
-        #[wasm_bindgen(method, getter=isNightly)]
-        pub fn is_nightly(this: &NightlySolana) -> bool;
-
-        #[wasm_bindgen(method, getter=priorityFeesSupported)]
-        pub fn priority_fees_supported(this: &NightlySolana) -> bool;
-
-        #[wasm_bindgen(method, getter=publicKey)]
-        pub fn pubkey(this: &NightlySolana) -> JsPublicKey;
-
-        // This does not seem to work
-        // #[wasm_bindgen(method, getter=onAccountChange)]
-        // pub fn on(this: &NightlySolana, e: &str, cb: &Closure<dyn FnMut(web_sys::Event)>) -> bool;
-
-        #[wasm_bindgen(method, catch, js_name=connect)]
-        pub async fn connect(this: &NightlySolana) -> Result<JsValue, JsValue>;
-        #[wasm_bindgen(method, js_name=disconnect)]
-        pub async fn disconnect(this: &NightlySolana);
-
-        #[wasm_bindgen(method, catch, js_name=signMessage)]
-        pub async fn sign_message(
-            this: &NightlySolana,
-            message: Vec<u8>,
-            pubkey: Option<JsPublicKey>,
-        ) -> Result<JsValue, JsValue>;
-    }
-
-    impl WalletEventEmitter for NightlySolana {
-        fn on_connect(&self, _: &Closure<dyn FnMut(web_sys::Event)>) {
-            //self.on("connect", closure);
-        }
-
-        fn on_disconnect(&self, _: &Closure<dyn FnMut(web_sys::Event)>) {
-            //self.on("disconnect", closure);
-        }
-
-        fn on_error(&self, _: &Closure<dyn FnMut(web_sys::Event)>) {
-            //self.on("error", closure);
-        }
-
-        fn on_ready_state_change(&self, _: &Closure<dyn FnMut(web_sys::Event)>) {
-            //self.on("readyStateChange", closure);
-        }
-    }
-}
+use crate::injected::{InjectedProvider, InjectedWalletAdapter};
 
 #[derive(Debug)]
-pub struct NightlyWalletAdapter {
-    connecting: Mutex<bool>,
-    connected: Mutex<bool>,
-    pubkey: Pubkey,
-    wrs: WalletReadyState,
-    wel: Mutex<WalletEventListener>,
-}
+pub struct NightlyProvider;
 
-impl Clone for NightlyWalletAdapter {
-    fn clone(&self) -> Self {
-        Self {
-            connecting: self.connecting().into(),
-            connected: Mutex::new(*self.connected.lock().unwrap()),
-            pubkey: self.pubkey.clone(),
-            wrs: self.wrs.clone(),
-            wel: self.wel.lock().unwrap().clone().into(),
-        }
-    }
-
-    fn clone_from(&mut self, source: &Self) {
-        self.connecting = source.connecting().into();
-        self.connected = Mutex::new(*source.connected.lock().unwrap());
-        self.pubkey = source.pubkey.clone();
-        self.wel = source.wel.lock().unwrap().clone().into();
-        self.wrs = source.wrs.clone();
-    }
+impl InjectedProvider for NightlyProvider {
+    const NAME: &'static str = "Nightly";
+    const URL: &'static str = "https://nightly.app/";
+    const ICON: &'static str = "data:image/svg+xml;base64,PHN2ZyB3aWR0aD0iOTYiIGhlaWdodD0iOTYiIHZpZXdCb3g9IjAgMCA5NiA5NiIgZmlsbD0ibm9uZSIgeG1sbnM9Imh0dHA6Ly93d3cudzMub3JnLzIwMDAvc3ZnIj4KPHBhdGggZD0iTTQ4IDk2Qzc0LjUwOTcgOTYgOTYgNzQuNTA5NyA5NiA0OEM5NiAyMS40OTAzIDc0LjUwOTcgMCA0OCAwQzIxLjQ5MDMgMCAwIDIxLjQ5MDMgMCA0OEMwIDc0LjUwOTcgMjEuNDkwMyA5NiA0OCA5NloiIGZpbGw9IiM2RDczRjgiLz4KPHBhdGggZD0iTTQ4IDg1LjYzNTZDNDggODUuNjM1NiA1Mi40NTMzIDg1LjYzNTYgNTUuNDQgODIuNTg2N0M1OC45MTU1IDc5LjI4MDEgNTcuMzUxMSA3NS40MzEyIDYyLjI3NTUgNzEuNDMxMkM2Ni45ODY2IDY3LjY0NDUgNzIuOTI0NCA3MC4zMzc5IDcyLjkyNDQgNzAuMzM3OUM3Ny4wMjIyIDYyLjEyNDUgNzQuNzkxMSA1Mi41NjkgNzQuNzkxMSA1Mi41NjlDODEuNzY4OCAzNC4yNTc5IDc1Ljk2NDQgMjEuMTU1NyA3NC40NDQ0IDE3LjM2MDFDNjkuNDQ4OCAyNC4zMzc5IDYzLjE5MTEgMjkuMTczNCA1NS43OTU1IDMyLjQwOUM1My4yMjY2IDMxLjcwNjggNTAuNTk1NSAzMS4zMzM0IDQ4IDMxLjM2MDFDNDUuNDEzMyAzMS4zMzM0IDQyLjc3MzMgMzEuNzA2OCA0MC4yMDQ0IDMyLjQwOUMzMi44MTc3IDI5LjE2NDUgMjYuNTUxMSAyNC4zMzc5IDIxLjU1NTUgMTcuMzYwMUMyMC4wMzU1IDIxLjE1NTcgMTQuMjMxMSAzNC4yNTc5IDIxLjIwODkgNTIuNTY5QzIxLjIwODkgNTIuNTY5IDE4Ljk3NzggNjIuMTI0NSAyMy4wNzU1IDcwLjMzNzlDMjMuMDc1NSA3MC4zMzc5IDI5LjAxMzMgNjcuNjQ0NSAzMy43MjQ0IDcxLjQzMTJDMzguNjU3NyA3NS40MzEyIDM3LjA4NDQgNzkuMjgwMSA0MC41NiA4Mi41ODY3QzQzLjU0NjYgODUuNjM1NiA0OCA4NS42MzU2IDQ4IDg1LjYzNTZaIiBmaWxsPSJ3aGl0ZSIvPgo8cGF0aCBkPSJNNDIuNDc5OSA2NS4yOThDNDIuMjkzMyA1OS4yMTggMzYuMzAyMSA1Ny4yNjI0IDMyLjIxMzMgNTkuODIyNEMzMi4yMTMzIDU5LjgyMjQgMzIuODUzMyA2Mi40MzU4IDM1LjgzOTkgNjMuNzUxM0MzOC4yNzU1IDY0LjgyNjkgMzkuMzI0NCA2My4zODY5IDQyLjQ3OTkgNjUuMjk4WiIgZmlsbD0iIzdCODFGOSIvPgo8cGF0aCBkPSJNMjIuNDk3NyAyMy4wOTM1QzIwLjA4ODggMzEuNTQ2OCAyMS4xMjg4IDQyLjI0MDIgMjQuOTMzMyA1MC4wMjY5QzI4LjgyNjYgNDcuMjcxMyAzMi45MTU1IDQzLjAxMzUgMzUuMDkzMyAzOC41MDY5QzI5Ljk2NDQgMzQuNzExMyAyNS42NjIyIDMxLjEwMjQgMjIuNDk3NyAyMy4wOTM1WiIgZmlsbD0iIzdCODFGOSIvPgo8cGF0aCBkPSJNNTMuNTE5OSA2NS4yOThDNTMuNzA2NiA1OS4yMTggNTkuNjk3NyA1Ny4yNjI0IDYzLjc4NjYgNTkuODIyNEM2My43ODY2IDU5LjgyMjQgNjMuMTQ2NiA2Mi40MzU4IDYwLjE1OTkgNjMuNzUxM0M1Ny43MjQzIDY0LjgyNjkgNTYuNjc1NSA2My4zODY5IDUzLjUxOTkgNjUuMjk4WiIgZmlsbD0iIzdCODFGOSIvPgo8cGF0aCBkPSJNNzMuNTAyMiAyMy4wOTM1Qzc1LjkxMTEgMzEuNTQ2OCA3NC44NzExIDQyLjI0MDIgNzEuMDY2NiA1MC4wMjY5QzY3LjE3MzMgNDcuMjcxMyA2My4wODQ0IDQzLjAxMzUgNjAuOTA2NiAzOC41MDY5QzY2LjAzNTUgMzQuNzExMyA3MC4zMzc3IDMxLjEwMjQgNzMuNTAyMiAyMy4wOTM1WiIgZmlsbD0iIzdCODFGOSIvPgo8cGF0aCBkPSJNNDcuOTk5OSA4NS4zMDY5QzUwLjE0MDQgODUuMzA2OSA1MS44NzU1IDgzLjc3ODcgNTEuODc1NSA4MS44OTM2QzUxLjg3NTUgODAuMDA4NCA1MC4xNDA0IDc4LjQ4MDIgNDcuOTk5OSA3OC40ODAyQzQ1Ljg1OTUgNzguNDgwMiA0NC4xMjQ0IDgwLjAwODQgNDQuMTI0NCA4MS44OTM2QzQ0LjEyNDQgODMuNzc4NyA0NS44NTk1IDg1LjMwNjkgNDcuOTk5OSA4NS4zMDY5WiIgZmlsbD0iIzdCODFGOSIvPgo8L3N2Zz4K";
+    const PATH: &'static [&'static str] = &["nightly", "solana"];
+    const FLAG: Option<&'static str> = Some("isNightly");
 }
 
-impl PartialEq for NightlyWalletAdapter {
-    fn eq(&self, other: &Self) -> bool {
-        self.pubkey == other.pubkey
-            && self.connecting() == other.connecting()
-            && *self.connected.lock().unwrap() == *other.connected.lock().unwrap()
-    }
-
-    fn ne(&self, other: &Self) -> bool {
-        self.pubkey != other.pubkey
-            && self.connecting() != other.connecting()
-            && *self.connected.lock().unwrap() != *other.connected.lock().unwrap()
-    }
-}
-
-impl NightlyWalletAdapter {
-    pub fn new() -> Self {
-        let pubkey = nightly.solana().pubkey();
-        let event_listener = WalletEventListener::new(&nightly.solana());
-        let ready_state = if let Some(window) = web_sys::window() {
-            if let Some(_) = window.document() {
-                WalletReadyState::NotDetected
-            } else {
-                WalletReadyState::Unsupported
-            }
-        } else {
-            WalletReadyState::Unsupported
-        };
-
-        Self {
-            connecting: false.into(),
-            connected: nightly.solana().is_connected().into(),
-            pubkey: if pubkey.is_null() {
-                Pubkey::default()
-            } else {
-                pubkey.to_pubkey()
-            },
-            wrs: ready_state,
-            wel: Mutex::new(event_listener),
-        }
-    }
-}
-
-impl WalletAdapterMetadata for NightlyWalletAdapter {
-    fn name(&self) -> &str {
-        Wallet::Nightly.into_wallet_name()
-    }
-
-    fn url(&self) -> &str {
-        Wallet::Nightly.into_wallet_url()
-    }
-
-    fn icon(&self) -> &str {
-        Wallet::Nightly.into_wallet_icon()
-    }
-}
-
-#[async_trait(?Send)]
-impl WalletAdapterBase for NightlyWalletAdapter {
-    fn is_name(&self) -> bool {
-        nightly.solana().is_nightly()
-    }
-
-    fn is_connected(&self) -> bool {
-        nightly.solana().is_connected()
-    }
-
-    fn ready_state(&self) -> WalletReadyState{
-        self.wrs.clone()
-    }
-
-    fn pubkey(&self) -> Option<Pubkey> {
-        if nightly.solana().pubkey().is_null() {
-            None
-        } else {
-            Some(nightly.solana().pubkey().to_pubkey())
-        }
-    }
-
-    fn connecting(&self) -> bool {
-        *self.connecting.lock().unwrap()
-    }
-
-    async fn connect(&self, _: Option<&str>) {
-        *self.connecting.lock().unwrap() = true;
-        match nightly.solana().connect().await {
-            Ok(r) => {
-                log::info!("Successfully invoked connect. {:?}", r.as_string());
-            }
-            Err(e) => {
-                log::error!("Error invoking connect. {:?}", e.as_string());
-            }
-        }
-        *self.connecting.lock().unwrap() = false;
-    }
-
-    async fn disconnect(&self) {
-        nightly.solana().disconnect().await
-    }
-}
-
-#[async_trait(?Send)]
-impl WalletAdapter for NightlyWalletAdapter {
-    fn priority_fees_supported(&self) -> bool {
-        nightly.solana().priority_fees_supported()
-    }
-
-    async fn on_event(&self, f: &mut dyn FnMut(Event)) {
-        let mut wel = self.wel.lock().unwrap();
-        if let Some(ref mut receiver) = &mut wel.receiver {
-            loop {
-                if let Some(msg) = receiver.next().await {
-                    f(msg)
-                }
-            }
-        }
-    }
-}
+pub type NightlyWalletAdapter = InjectedWalletAdapter<NightlyProvider>;
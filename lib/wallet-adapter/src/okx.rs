@@ -0,0 +1,15 @@
+use crate::injected::{InjectedProvider, InjectedWalletAdapter};
+
+/// OKX Wallet does not flag its solana provider, it is recognized by where it is injected.
+#[derive(Debug)]
+pub struct OkxProvider;
+
+impl InjectedProvider for OkxProvider {
+    const NAME: &'static str = "OKX Wallet";
+    const URL: &'static str = "https://www.okx.com/web3";
+    const ICON: &'static str = "data:image/svg+xml;base64,PHN2ZyB3aWR0aD0iNDAiIGhlaWdodD0iNDAiIHZpZXdCb3g9IjAgMCA0MCA0MCIgZmlsbD0ibm9uZSIgeG1sbnM9Imh0dHA6Ly93d3cudzMub3JnLzIwMDAvc3ZnIj4KPHJlY3Qgd2lkdGg9IjQwIiBoZWlnaHQ9IjQwIiByeD0iOCIgZmlsbD0iYmxhY2siLz4KPHBhdGggZmlsbC1ydWxlPSJldmVub2RkIiBjbGlwLXJ1bGU9ImV2ZW5vZGQiIGQ9Ik0yMy4zMzMzIDE2LjY2NjdIMTYuNjY2N1YyMy4zMzMzSDIzLjMzMzNWMTYuNjY2N1pNMTYuNjY2NyAxMEgxMFYxNi42NjY3SDE2LjY2NjdWMTBaTTMwIDEwSDIzLjMzMzNWMTYuNjY2N0gzMFYxMFpNMTYuNjY2NyAyMy4zMzMzSDEwVjMwSDE2LjY2NjdWMjMuMzMzM1pNMzAgMjMuMzMzM0gyMy4zMzMzVjMwSDMwVjIzLjMzMzNaIiBmaWxsPSJ3aGl0ZSIvPgo8L3N2Zz4K";
+    const PATH: &'static [&'static str] = &["okxwallet", "solana"];
+    const FLAG: Option<&'static str> = None;
+}
+
+pub type OkxWalletAdapter = InjectedWalletAdapter<OkxProvider>;
@@ -1,225 +1,26 @@
-#![allow(non_upper_case_globals)]
-use self::wasmgen::solana;
-use crate::{
-    proxy::WalletEventListener, IntoPubkey, IntoWalletIcon, IntoWalletName, IntoWalletUrl, Wallet,
-};
-use async_trait::async_trait;
-use futures::StreamExt;
-use solana_client_wasm::solana_sdk::pubkey::Pubkey;
-use solana_wallet_adapter_base::{
-    Event, WalletAdapter, WalletAdapterBase, WalletAdapterMetadata, WalletReadyState,
-};
-use std::sync::Mutex;
-
-pub(crate) mod wasmgen {
-    use crate::{JsPublicKey, WalletEventEmitter};
-    use wasm_bindgen::{prelude::*, JsStatic};
-
-    #[wasm_bindgen]
-    extern "C" {
-        pub type Solana;
-
-        // Phantom re-exports window.phantom.solana as window.solana
-        pub static solana: Solana;
-
-        #[wasm_bindgen(method, getter=isConnected)]
-        pub fn is_connected(this: &Solana) -> bool;
-
-        #[wasm_bindgen(method, getter=isPhantom)]
-        pub fn is_phantom(this: &Solana) -> bool;
-
-        #[wasm_bindgen(method, getter=priorityFeesSupported)]
-        pub fn priority_fees_supported(this: &Solana) -> bool;
-
-        #[wasm_bindgen(method, getter=_eventsCount)]
-        pub fn events_count(this: &Solana) -> JsValue;
-
-        #[wasm_bindgen(method, getter=publicKey)]
-        pub fn pubkey(this: &Solana) -> JsPublicKey;
-
-        #[wasm_bindgen(method)]
-        pub fn on(this: &Solana, e: &str, cb: &Closure<dyn FnMut(web_sys::Event)>) -> bool;
-
-        #[wasm_bindgen(method, catch, js_name=connect)]
-        pub async fn connect(this: &Solana) -> Result<JsValue, JsValue>;
-        #[wasm_bindgen(method, js_name=disconnect)]
-        pub async fn disconnect(this: &Solana);
-
-        #[wasm_bindgen(method, catch, js_name=signMessage)]
-        pub async fn sign_message(
-            this: &Solana,
-            message: Vec<u8>,
-            pubkey: Option<JsPublicKey>,
-        ) -> Result<JsValue, JsValue>;
-    }
-
-    impl WalletEventEmitter for JsStatic<Solana> {
-        fn on_connect(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) {
-            self.on("connect", closure);
-        }
-
-        fn on_disconnect(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) {
-            self.on("disconnect", closure);
-        }
-
-        fn on_error(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) {
-            self.on("error", closure);
-        }
-
-        fn on_ready_state_change(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) {
-            self.on("readyStateChange", closure);
-        }
-    }
-}
+#[cfg(feature = "deeplink")]
+use crate::deeplink::{DeepLinkProvider, DeepLinkWalletAdapter};
+use crate::injected::{InjectedProvider, InjectedWalletAdapter};
 
+/// Phantom re-exports `window.phantom.solana` as `window.solana`.
 #[derive(Debug)]
-pub struct PhantomWalletAdapter {
-    connecting: Mutex<bool>,
-    connected: Mutex<bool>,
-    pubkey: Pubkey,
-    wrs: WalletReadyState,
-    wel: Mutex<WalletEventListener>,
-}
-
-impl Clone for PhantomWalletAdapter {
-    fn clone(&self) -> Self {
-        Self {
-            connecting: self.connecting().into(),
-            connected: Mutex::new(*self.connected.lock().unwrap()),
-            pubkey: self.pubkey.clone(),
-            wrs: self.wrs.clone(),
-            wel: self.wel.lock().unwrap().clone().into(),
-        }
-    }
+pub struct PhantomProvider;
 
-    fn clone_from(&mut self, source: &Self) {
-        self.connecting = source.connecting().into();
-        self.connected = Mutex::new(*source.connected.lock().unwrap());
-        self.pubkey = source.pubkey.clone();
-        self.wel = source.wel.lock().unwrap().clone().into();
-        self.wrs = source.wrs.clone();
-    }
+impl InjectedProvider for PhantomProvider {
+    const NAME: &'static str = "Phantom";
+    const URL: &'static str = "https://phantom.app/";
+    const ICON: &'static str = "data:image/svg+xml;base64,PHN2ZyB4bWxucz0iaHR0cDovL3d3dy53My5vcmcvMjAwMC9zdmciIHdpZHRoPSIxMDgiIGhlaWdodD0iMTA4IiB2aWV3Qm94PSIwIDAgMTA4IDEwOCIgZmlsbD0ibm9uZSI+CjxyZWN0IHdpZHRoPSIxMDgiIGhlaWdodD0iMTA4IiByeD0iMjYiIGZpbGw9IiNBQjlGRjIiLz4KPHBhdGggZmlsbC1ydWxlPSJldmVub2RkIiBjbGlwLXJ1bGU9ImV2ZW5vZGQiIGQ9Ik00Ni41MjY3IDY5LjkyMjlDNDIuMDA1NCA3Ni44NTA5IDM0LjQyOTIgODUuNjE4MiAyNC4zNDggODUuNjE4MkMxOS41ODI0IDg1LjYxODIgMTUgODMuNjU2MyAxNSA3NS4xMzQyQzE1IDUzLjQzMDUgNDQuNjMyNiAxOS44MzI3IDcyLjEyNjggMTkuODMyN0M4Ny43NjggMTkuODMyNyA5NCAzMC42ODQ2IDk0IDQzLjAwNzlDOTQgNTguODI1OCA4My43MzU1IDc2LjkxMjIgNzMuNTMyMSA3Ni45MTIyQzcwLjI5MzkgNzYuOTEyMiA2OC43MDUzIDc1LjEzNDIgNjguNzA1MyA3Mi4zMTRDNjguNzA1MyA3MS41NzgzIDY4LjgyNzUgNzAuNzgxMiA2OS4wNzE5IDY5LjkyMjlDNjUuNTg5MyA3NS44Njk5IDU4Ljg2ODUgODEuMzg3OCA1Mi41NzU0IDgxLjM4NzhDNDcuOTkzIDgxLjM4NzggNDUuNjcxMyA3OC41MDYzIDQ1LjY3MTMgNzQuNDU5OEM0NS42NzEzIDcyLjk4ODQgNDUuOTc2OCA3MS40NTU2IDQ2LjUyNjcgNjkuOTIyOVpNODMuNjc2MSA0Mi41Nzk0QzgzLjY3NjEgNDYuMTcwNCA4MS41NTc1IDQ3Ljk2NTggNzkuMTg3NSA0Ny45NjU4Qzc2Ljc4MTYgNDcuOTY1OCA3NC42OTg5IDQ2LjE3MDQgNzQuNjk4OSA0Mi41Nzk0Qzc0LjY5ODkgMzguOTg4NSA3Ni43ODE2IDM3LjE5MzEgNzkuMTg3NSAzNy4xOTMxQzgxLjU1NzUgMzcuMTkzMSA4My42NzYxIDM4Ljk4ODUgODMuNjc2MSA0Mi41Nzk0Wk03MC4yMTAzIDQyLjU3OTVDNzAuMjEwMyA0Ni4xNzA0IDY4LjA5MTYgNDcuOTY1OCA2NS43MjE2IDQ3Ljk2NThDNjMuMzE1NyA0Ny45NjU4IDYxLjIzMyA0Ni4xNzA0IDYxLjIzMyA0Mi41Nzk1QzYxLjIzMyAzOC45ODg1IDYzLjMxNTcgMzcuMTkzMSA2NS43MjE2IDM3LjE5MzFDNjguMDkxNiAzNy4xOTMxIDcwLjIxMDMgMzguOTg4NSA3MC4yMTAzIDQyLjU3OTVaIiBmaWxsPSIjRkZGREY4Ii8+Cjwvc3ZnPg==";
+    const PATH: &'static [&'static str] = &["solana"];
+    const FLAG: Option<&'static str> = Some("isPhantom");
 }
 
-impl PartialEq for PhantomWalletAdapter {
-    fn eq(&self, other: &Self) -> bool {
-        self.pubkey == other.pubkey
-            && self.connecting() == other.connecting()
-            && *self.connected.lock().unwrap() == *other.connected.lock().unwrap()
-    }
+pub type PhantomWalletAdapter = InjectedWalletAdapter<PhantomProvider>;
 
-    fn ne(&self, other: &Self) -> bool {
-        self.pubkey != other.pubkey
-            && self.connecting() != other.connecting()
-            && *self.connected.lock().unwrap() != *other.connected.lock().unwrap()
-    }
+#[cfg(feature = "deeplink")]
+impl DeepLinkProvider for PhantomProvider {
+    const BASE_URL: &'static str = "https://phantom.app/ul/v1/";
+    const ENCRYPTION_PUBLIC_KEY_PARAM: &'static str = "phantom_encryption_public_key";
 }
 
-impl PhantomWalletAdapter {
-    pub fn new() -> Self {
-        let pubkey = solana.pubkey();
-        let wel = WalletEventListener::new(&solana);
-
-        let wrs = if let Some(window) = web_sys::window() {
-            if let Some(_) = window.document() {
-                if solana.is_undefined() {
-                    WalletReadyState::NotDetected
-                } else {
-                    WalletReadyState::Installed
-                }
-            } else {
-                WalletReadyState::Unsupported
-            }
-        } else {
-            WalletReadyState::Unsupported
-        };
-        Self {
-            connecting: false.into(),
-            connected: solana.is_connected().into(),
-            pubkey: if pubkey.is_null() {
-                Pubkey::default()
-            } else {
-                pubkey.to_pubkey()
-            },
-            wrs,
-            wel: Mutex::new(wel),
-        }
-    }
-}
-
-impl WalletAdapterMetadata for PhantomWalletAdapter {
-    fn name(&self) -> &str {
-        Wallet::Phantom.into_wallet_name()
-    }
-
-    fn url(&self) -> &str {
-        Wallet::Phantom.into_wallet_url()
-    }
-
-    fn icon(&self) -> &str {
-        Wallet::Phantom.into_wallet_icon()
-    }
-}
-
-#[async_trait(?Send)]
-impl WalletAdapterBase for PhantomWalletAdapter {
-    fn is_name(&self) -> bool {
-        solana.is_phantom()
-    }
-
-    fn is_connected(&self) -> bool {
-        solana.is_connected()
-    }
-
-    fn ready_state(&self) -> WalletReadyState{
-        self.wrs.clone()
-    }
-
-    fn pubkey(&self) -> Option<Pubkey> {
-        if solana.pubkey().is_null() {
-            None
-        } else {
-            Some(solana.pubkey().to_pubkey())
-        }
-    }
-
-    fn connecting(&self) -> bool {
-        *self.connecting.lock().unwrap()
-    }
-
-    async fn connect(&self, _: Option<&str>) {
-        *self.connecting.lock().unwrap() = true;
-        //solana.connect().await;
-        match solana.connect().await {
-            Ok(r) => {
-                log::info!("Successfully invoked connect. {:?}", r.as_string());
-            }
-            Err(e) => {
-                log::error!("Error invoking connect. {:?}", e.as_string());
-            }
-        }
-        *self.connecting.lock().unwrap() = false;
-    }
-
-    async fn disconnect(&self) {
-        solana.disconnect().await
-    }
-}
-
-#[async_trait(?Send)]
-impl WalletAdapter for PhantomWalletAdapter {
-    fn priority_fees_supported(&self) -> bool {
-        solana.priority_fees_supported()
-    }
-
-    async fn on_event(&self, f: &mut dyn FnMut(Event)) {
-        let mut wel = self.wel.lock().unwrap();
-        if let Some(ref mut receiver) = &mut wel.receiver {
-            loop {
-                if let Some(msg) = receiver.next().await {
-                    f(msg)
-                }
-            }
-        }
-    }
-}
+#[cfg(feature = "deeplink")]
+pub type PhantomDeepLinkAdapter = DeepLinkWalletAdapter<PhantomProvider>;
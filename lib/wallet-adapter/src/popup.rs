@@ -0,0 +1,548 @@
+use crate::{from_js_message_signature, from_js_signature, injected::InjectedProvider};
+use async_trait::async_trait;
+use futures_channel::oneshot;
+use gloo_events::EventListener;
+use solana_client_wasm::solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_wallet_adapter_base::{
+    sign_in_with_message, with_timeout, ConnectOptions, Event, EventBus, EventSubscription,
+    SignInInput, SignInOutput, WalletAdapter, WalletAdapterBase, WalletAdapterMetadata,
+    WalletCapabilities, WalletError, WalletReadyState, WalletTransaction,
+    DEFAULT_TRANSACTION_VERSIONS,
+};
+use std::{
+    cell::RefCell, collections::HashMap, marker::PhantomData, rc::Rc, str::FromStr, sync::Mutex,
+};
+use wasm_bindgen::{JsCast, JsValue};
+
+/// The name of the popup window, reused across requests.
+const POPUP_TARGET: &str = "solana_wallet_popup";
+const POPUP_FEATURES: &str = "width=420,height=680,resizable=yes,scrollbars=yes";
+/// How often the popup is checked for having been closed by the user.
+const CLOSED_POLL_INTERVAL_MS: u32 = 500;
+
+/// Describes a web wallet that can run in a popup window when its extension is not installed.
+pub trait PopupProvider: InjectedProvider {
+    /// The name the web wallet is listed under, next to the extension.
+    const POPUP_NAME: &'static str;
+    /// The page of the web wallet, loaded in the popup.
+    const POPUP_URL: &'static str;
+}
+
+struct PendingRequest {
+    sender: oneshot::Sender<Result<JsValue, WalletError>>,
+    fallback: WalletError,
+}
+
+#[derive(Default)]
+struct PopupState {
+    window: Option<web_sys::Window>,
+    /// Bumped for every window opened, so watchers of previous windows stop.
+    generation: u32,
+    ready: bool,
+    next_id: u32,
+    /// Requests posted before the wallet page reported it was ready.
+    queue: Vec<JsValue>,
+    pending: HashMap<u32, PendingRequest>,
+}
+
+impl PopupState {
+    /// Forgets the window, failing every pending request with [`WalletError::WindowClosed`].
+    fn reset(&mut self) {
+        self.window = None;
+        self.ready = false;
+        self.queue.clear();
+        for (_, request) in self.pending.drain() {
+            let _ = request.sender.send(Err(WalletError::WindowClosed));
+        }
+    }
+}
+
+/// A web wallet running in a popup window, talked to with `postMessage`.
+///
+/// ## Protocol
+/// Once loaded, the wallet page posts `{ type: "ready" }` to its opener. Requests are posted as
+/// `{ id, method, params }` and answered with `{ id, result }` or `{ id, error: { code, message } }`.
+/// Messages from any other origin than the wallet page's are ignored.
+///
+/// `popup/stand-in.html` implements the wallet side of the protocol with an in-memory keypair, it
+/// can replace the web wallet in development and tests when served from the configured url.
+pub struct PopupWindow {
+    url: String,
+    origin: String,
+    state: Rc<RefCell<PopupState>>,
+    on_closed: Rc<dyn Fn()>,
+    _listener: EventListener,
+}
+
+impl std::fmt::Debug for PopupWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PopupWindow")
+            .field("url", &self.url)
+            .field("open", &self.is_open())
+            .finish()
+    }
+}
+
+impl PopupWindow {
+    /// Prepares a popup for the wallet page at `url`, `on_closed` runs when the user closes it.
+    pub fn new<F: Fn() + 'static>(url: &str, on_closed: F) -> Result<Self, WalletError> {
+        let origin = web_sys::Url::new(url)
+            .map_err(|_| WalletError::Config)?
+            .origin();
+        let state = Rc::new(RefCell::new(PopupState::default()));
+
+        let listener = {
+            let state = state.clone();
+            let origin = origin.clone();
+            EventListener::new(&gloo_utils::window(), "message", move |event| {
+                if let Some(event) = event.dyn_ref::<web_sys::MessageEvent>() {
+                    if event.origin() == origin {
+                        Self::handle_message(&state, &origin, event.data());
+                    }
+                }
+            })
+        };
+
+        Ok(Self {
+            url: url.to_string(),
+            origin,
+            state,
+            on_closed: Rc::new(on_closed),
+            _listener: listener,
+        })
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.state
+            .borrow()
+            .window
+            .as_ref()
+            .map_or(false, |w| !w.closed().unwrap_or(true))
+    }
+
+    /// Opens the popup, or focuses it if it is already open.
+    ///
+    /// Browsers only allow popups in response to a user gesture, otherwise this fails with
+    /// [`WalletError::WindowBlocked`].
+    pub fn open(&self) -> Result<(), WalletError> {
+        if self.is_open() {
+            if let Some(window) = &self.state.borrow().window {
+                let _ = window.focus();
+            }
+            return Ok(());
+        }
+
+        let window = gloo_utils::window()
+            .open_with_url_and_target_and_features(&self.url, POPUP_TARGET, POPUP_FEATURES)
+            .ok()
+            .flatten()
+            .ok_or(WalletError::WindowBlocked)?;
+        {
+            let mut state = self.state.borrow_mut();
+            state.reset();
+            state.window = Some(window);
+            state.generation += 1;
+        }
+        self.watch_closed();
+        Ok(())
+    }
+
+    /// Closes the popup, failing every pending request with [`WalletError::WindowClosed`].
+    pub fn close(&self) {
+        let mut state = self.state.borrow_mut();
+        if let Some(window) = &state.window {
+            let _ = window.close();
+        }
+        state.reset();
+    }
+
+    /// Posts a request to the wallet page, opening the popup if needed, and awaits its response.
+    ///
+    /// Failures reported by the wallet become [`WalletError::Provider`] errors when they carry a
+    /// code, `fallback` otherwise.
+    pub async fn request(
+        &self,
+        method: &str,
+        params: JsValue,
+        fallback: WalletError,
+    ) -> Result<JsValue, WalletError> {
+        self.open()?;
+
+        let (sender, receiver) = oneshot::channel();
+        {
+            let mut state = self.state.borrow_mut();
+            state.next_id += 1;
+            let id = state.next_id;
+
+            let message = js_sys::Object::new();
+            set(&message, "id", &id.into())?;
+            set(&message, "method", &method.into())?;
+            set(&message, "params", &params)?;
+
+            state
+                .pending
+                .insert(id, PendingRequest { sender, fallback });
+            if state.ready {
+                Self::post(&state, &self.origin, &message);
+            } else {
+                state.queue.push(message.into());
+            }
+        }
+
+        receiver.await.unwrap_or(Err(WalletError::WindowClosed))
+    }
+
+    fn post(state: &PopupState, origin: &str, message: &JsValue) {
+        if let Some(window) = &state.window {
+            if let Err(e) = window.post_message(message, origin) {
+                log::error!("Failed to post message to wallet popup: {:?}", e);
+            }
+        }
+    }
+
+    fn handle_message(state: &Rc<RefCell<PopupState>>, origin: &str, data: JsValue) {
+        if get(&data, "type").as_string().as_deref() == Some("ready") {
+            let mut state = state.borrow_mut();
+            state.ready = true;
+            for message in std::mem::take(&mut state.queue) {
+                Self::post(&state, origin, &message);
+            }
+            return;
+        }
+
+        let Some(id) = get(&data, "id").as_f64() else {
+            return;
+        };
+        let Some(request) = state.borrow_mut().pending.remove(&(id as u32)) else {
+            return;
+        };
+        let error = get(&data, "error");
+        let response = if error.is_undefined() || error.is_null() {
+            Ok(get(&data, "result"))
+        } else {
+            Err(WalletError::from_js(&error, request.fallback))
+        };
+        let _ = request.sender.send(response);
+    }
+
+    /// Polls the popup until it is closed, browsers do not fire any event on the opener.
+    fn watch_closed(&self) {
+        let state = self.state.clone();
+        let on_closed = self.on_closed.clone();
+        let generation = state.borrow().generation;
+        wasm_bindgen_futures::spawn_local(async move {
+            loop {
+                gloo_timers::future::TimeoutFuture::new(CLOSED_POLL_INTERVAL_MS).await;
+                let closed = match &*state.borrow() {
+                    PopupState {
+                        window: Some(window),
+                        generation: current,
+                        ..
+                    } if *current == generation => window.closed().unwrap_or(true),
+                    // Closed through `close`, or replaced by a new window with its own watcher
+                    _ => return,
+                };
+                if closed {
+                    state.borrow_mut().reset();
+                    on_closed();
+                    return;
+                }
+            }
+        });
+    }
+}
+
+fn set(target: &js_sys::Object, key: &str, value: &JsValue) -> Result<(), WalletError> {
+    js_sys::Reflect::set(target, &key.into(), value)
+        .map(|_| ())
+        .map_err(|_| WalletError::Config)
+}
+
+fn get(target: &JsValue, key: &str) -> JsValue {
+    js_sys::Reflect::get(target, &key.into()).unwrap_or_default()
+}
+
+fn to_js_bytes(bytes: &[u8]) -> JsValue {
+    js_sys::Uint8Array::from(bytes).into()
+}
+
+fn from_js_bytes(value: JsValue, error: WalletError) -> Result<Vec<u8>, WalletError> {
+    value
+        .dyn_into::<js_sys::Uint8Array>()
+        .map(|b| b.to_vec())
+        .map_err(|_| error)
+}
+
+/// A wallet adapter for a web wallet running in a popup window.
+///
+/// A fallback for when the wallet extension is not installed, it is listed under its own name
+/// next to the extension. It connects by opening the popup and stays connected until the user
+/// closes it.
+pub struct PopupWalletAdapter<P: PopupProvider> {
+    popup: PopupWindow,
+    pubkey: Rc<RefCell<Option<Pubkey>>>,
+    connecting: Mutex<bool>,
+    bus: EventBus,
+    _provider: PhantomData<P>,
+}
+
+impl<P: PopupProvider> std::fmt::Debug for PopupWalletAdapter<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PopupWalletAdapter")
+            .field("name", &P::NAME)
+            .field("popup", &self.popup)
+            .field("pubkey", &self.pubkey.borrow().map(|p| p.to_string()))
+            .finish()
+    }
+}
+
+impl<P: PopupProvider> PopupWalletAdapter<P> {
+    pub fn new() -> Self {
+        Self::with_url(P::POPUP_URL).expect("popup url of a provider should be valid")
+    }
+
+    /// Creates the adapter for the wallet page at `url`, e.g. a local stand-in for the web wallet.
+    pub fn with_url(url: &str) -> Result<Self, WalletError> {
+        let pubkey = Rc::new(RefCell::new(None));
+        let bus = EventBus::new();
+
+        let popup = {
+            let pubkey = pubkey.clone();
+            let bus = bus.clone();
+            PopupWindow::new(url, move || {
+                // The web wallet session ends with its window
+                if pubkey.borrow_mut().take().is_some() {
+                    bus.emit(Event::Disconnect);
+                }
+            })?
+        };
+
+        Ok(Self {
+            popup,
+            pubkey,
+            connecting: false.into(),
+            bus,
+            _provider: PhantomData,
+        })
+    }
+
+    fn connected_pubkey(&self) -> Result<Pubkey, WalletError> {
+        self.pubkey.borrow().ok_or(WalletError::NotConnected)
+    }
+}
+
+impl<P: PopupProvider> Default for PopupWalletAdapter<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: PopupProvider> WalletAdapterMetadata for PopupWalletAdapter<P> {
+    fn name(&self) -> &str {
+        P::POPUP_NAME
+    }
+
+    fn url(&self) -> &str {
+        P::URL
+    }
+
+    fn icon(&self) -> &str {
+        P::ICON
+    }
+}
+
+#[async_trait(?Send)]
+impl<P: PopupProvider> WalletAdapterBase for PopupWalletAdapter<P> {
+    fn is_name(&self) -> bool {
+        true
+    }
+
+    fn is_connected(&self) -> bool {
+        self.pubkey.borrow().is_some()
+    }
+
+    fn ready_state(&self) -> WalletReadyState {
+        WalletReadyState::Loadable
+    }
+
+    fn pubkey(&self) -> Option<Pubkey> {
+        *self.pubkey.borrow()
+    }
+
+    fn connecting(&self) -> bool {
+        *self.connecting.lock().unwrap()
+    }
+
+    async fn connect(&self, options: ConnectOptions) -> Result<Pubkey, WalletError> {
+        if let Some(pubkey) = self.pubkey() {
+            return Ok(pubkey);
+        }
+        // Opening the popup always needs the user
+        if options.only_if_trusted {
+            return Err(WalletError::NotConnected);
+        }
+
+        let params = js_sys::Object::new();
+        if let Some(endpoint) = &options.rpc_endpoint {
+            set(&params, "network", &endpoint.into())?;
+        }
+
+        *self.connecting.lock().unwrap() = true;
+        let result = with_timeout(
+            self.popup
+                .request("connect", params.into(), WalletError::Connection),
+            options.timeout,
+        )
+        .await
+        .and_then(|r| r)
+        .and_then(|r| {
+            get(&r, "publicKey")
+                .as_string()
+                .and_then(|pk| Pubkey::from_str(&pk).ok())
+                .ok_or(WalletError::Pubkey)
+        });
+        *self.connecting.lock().unwrap() = false;
+
+        match &result {
+            Ok(pubkey) => {
+                log::info!("Connected to {} popup. {:?}", P::NAME, pubkey);
+                *self.pubkey.borrow_mut() = Some(*pubkey);
+                self.bus.emit(Event::Connect(*pubkey));
+            }
+            Err(e) => {
+                log::error!("Error connecting to {} popup. {}", P::NAME, e);
+                self.bus.emit(Event::Error(e.clone()));
+            }
+        }
+        result
+    }
+
+    async fn disconnect(&self) {
+        if self.popup.is_open() {
+            let _ = self
+                .popup
+                .request("disconnect", JsValue::UNDEFINED, WalletError::Disconnection)
+                .await;
+        }
+        self.popup.close();
+        if self.pubkey.borrow_mut().take().is_some() {
+            self.bus.emit(Event::Disconnect);
+        }
+    }
+
+    async fn sign_transaction(
+        &self,
+        transaction: WalletTransaction,
+    ) -> Result<WalletTransaction, WalletError> {
+        self.connected_pubkey()?;
+        let params = js_sys::Object::new();
+        set(
+            &params,
+            "transaction",
+            &to_js_bytes(&transaction.serialize()?),
+        )?;
+        let result = self
+            .popup
+            .request(
+                "signTransaction",
+                params.into(),
+                WalletError::SignTransaction,
+            )
+            .await?;
+        let bytes = from_js_bytes(get(&result, "transaction"), WalletError::SignTransaction)?;
+        transaction.deserialize_as(&bytes)
+    }
+
+    async fn sign_all_transactions(
+        &self,
+        transactions: Vec<WalletTransaction>,
+    ) -> Result<Vec<WalletTransaction>, WalletError> {
+        self.connected_pubkey()?;
+        let array = js_sys::Array::new();
+        for transaction in &transactions {
+            array.push(&to_js_bytes(&transaction.serialize()?));
+        }
+        let params = js_sys::Object::new();
+        set(&params, "transactions", &array)?;
+        let result = self
+            .popup
+            .request(
+                "signAllTransactions",
+                params.into(),
+                WalletError::SignTransaction,
+            )
+            .await?;
+
+        let signed = get(&result, "transactions")
+            .dyn_into::<js_sys::Array>()
+            .map_err(|_| WalletError::SignTransaction)?;
+        if signed.length() as usize != transactions.len() {
+            return Err(WalletError::SignTransaction);
+        }
+        transactions
+            .iter()
+            .zip(signed.iter())
+            .map(|(original, value)| {
+                original.deserialize_as(&from_js_bytes(value, WalletError::SignTransaction)?)
+            })
+            .collect()
+    }
+
+    async fn sign_and_send_transaction(
+        &self,
+        transaction: WalletTransaction,
+    ) -> Result<Signature, WalletError> {
+        self.connected_pubkey()?;
+        let params = js_sys::Object::new();
+        set(
+            &params,
+            "transaction",
+            &to_js_bytes(&transaction.serialize()?),
+        )?;
+        let result = self
+            .popup
+            .request(
+                "signAndSendTransaction",
+                params.into(),
+                WalletError::SendTransaction,
+            )
+            .await?;
+        from_js_signature(result)
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature, WalletError> {
+        self.connected_pubkey()?;
+        let params = js_sys::Object::new();
+        set(&params, "message", &to_js_bytes(message))?;
+        let result = self
+            .popup
+            .request("signMessage", params.into(), WalletError::SignMessage)
+            .await?;
+        from_js_message_signature(result)
+    }
+
+    async fn sign_in(&self, input: SignInInput) -> Result<SignInOutput, WalletError> {
+        sign_in_with_message(self, &input).await
+    }
+}
+
+#[async_trait(?Send)]
+impl<P: PopupProvider> WalletAdapter for PopupWalletAdapter<P> {
+    fn priority_fees_supported(&self) -> bool {
+        false
+    }
+
+    fn capabilities(&self) -> WalletCapabilities {
+        WalletCapabilities {
+            supported_transaction_versions: DEFAULT_TRANSACTION_VERSIONS.to_vec(),
+            sign_message: true,
+            sign_in: true,
+            sign_and_send_transaction: true,
+            sign_all_transactions: true,
+        }
+    }
+
+    fn subscribe(&self) -> EventSubscription {
+        self.bus.subscribe()
+    }
+}
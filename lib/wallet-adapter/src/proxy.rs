@@ -1,16 +1,19 @@
-use futures::Stream;
-use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
 use solana_client_wasm::solana_sdk::pubkey::Pubkey;
-use solana_wallet_adapter_base::{Event, WalletReadyState};
-use std::pin::Pin;
+use solana_wallet_adapter_base::{
+    Event, EventBus, EventSubscription, ListenerStatus, ProviderError, ProviderErrorCode,
+    WalletError, WalletReadyState,
+};
+use std::cell::Cell;
+use std::rc::Rc;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
-use std::task::{Context, Poll};
 use wasm_bindgen::prelude::Closure;
 
 use crate::WalletEventEmitter;
 
 type EventHandler = Closure<dyn FnMut(web_sys::Event)>;
+/// The rust side of an event handler, it emits what the event means on the bus.
+type EventCallback = Arc<Mutex<dyn FnMut(web_sys::Event, &EventBus)>>;
 
 /// Handles the `disconnect()` event.
 ///
@@ -18,10 +21,10 @@ type EventHandler = Closure<dyn FnMut(web_sys::Event)>;
 /// This is based on the `WalletAdapterEvents` interface as it was implemented in the typescript adapter.
 ///
 /// Read: https://github.com/anza-xyz/wallet-adapter/blob/master/packages/core/base/src/adapter.ts#L8
-pub(crate) fn handle_disconnect_event(e: web_sys::Event, s: Arc<UnboundedSender<Event>>) {
+pub(crate) fn handle_disconnect_event(e: web_sys::Event, s: &EventBus) {
     if e.is_undefined() {
         log::info!("Disconnect event.");
-        s.unbounded_send(Event::Disconnect).unwrap();
+        s.emit(Event::Disconnect);
     }
 }
 
@@ -31,12 +34,12 @@ pub(crate) fn handle_disconnect_event(e: web_sys::Event, s: Arc<UnboundedSender<
 /// This is based on the `WalletAdapterEvents` interface as it was implemented in the typescript adapter.
 ///
 /// Read: https://github.com/anza-xyz/wallet-adapter/blob/master/packages/core/base/src/adapter.ts#L8
-pub(crate) fn handle_connect_event(e: web_sys::Event, s: Arc<UnboundedSender<Event>>) {
+pub(crate) fn handle_connect_event(e: web_sys::Event, s: &EventBus) {
     if let Some(pubkey_string) = e.to_string().as_string() {
         match Pubkey::from_str(&pubkey_string) {
             Ok(r) => {
                 log::info!("Connect event. Wallet Pubkey: {:?}", r);
-                s.unbounded_send(Event::Connect(r)).unwrap();
+                s.emit(Event::Connect(r));
             }
             Err(e) => {
                 log::error!("Connect event. Failed to parse wallet pubkey: {:?}", e);
@@ -51,11 +54,16 @@ pub(crate) fn handle_connect_event(e: web_sys::Event, s: Arc<UnboundedSender<Eve
 /// This is based on the `WalletAdapterEvents` interface as it was implemented in the typescript adapter.
 ///
 /// Read: https://github.com/anza-xyz/wallet-adapter/blob/master/packages/core/base/src/adapter.ts#L8
-pub(crate) fn handle_error_event(e: web_sys::Event, s: Arc<UnboundedSender<Event>>) {
-    if let Some(error) = e.to_string().as_string() {
-        log::info!("Error event. {}", error);
-        s.unbounded_send(Event::Disconnect).unwrap();
-    }
+pub(crate) fn handle_error_event(e: web_sys::Event, s: &EventBus) {
+    // Errors without a recognizable code are still reported by the wallet itself
+    let error = ProviderError::from_js(&e).unwrap_or_else(|| {
+        ProviderError::new(
+            ProviderErrorCode::Internal,
+            e.to_string().as_string().unwrap_or_default(),
+        )
+    });
+    log::info!("Error event. {}", error);
+    s.emit(Event::Error(WalletError::Provider(error)));
 }
 
 /// Handles the `ready_state_change(WalletReadyState)` event.
@@ -64,11 +72,47 @@ pub(crate) fn handle_error_event(e: web_sys::Event, s: Arc<UnboundedSender<Event
 /// This is based on the `WalletAdapterEvents` interface as it was implemented in the typescript adapter.
 ///
 /// Read: https://github.com/anza-xyz/wallet-adapter/blob/master/packages/core/base/src/adapter.ts#L8
-pub(crate) fn handle_ready_state_change_event(e: web_sys::Event, s: Arc<UnboundedSender<Event>>) {
+pub(crate) fn handle_ready_state_change_event(e: web_sys::Event, s: &EventBus) {
     if let Some(ready_state) = e.to_string().as_string() {
-        log::info!("Ready state change event. {}", ready_state);
-        s.unbounded_send(Event::ReadyStateChange(WalletReadyState::Loadable))
-            .unwrap();
+        match WalletReadyState::from_str(&ready_state) {
+            Ok(r) => {
+                log::info!("Ready state change event. {:?}", r);
+                s.emit(Event::ReadyStateChange(r));
+            }
+            Err(_) => {
+                log::error!(
+                    "Ready state change event. Unknown ready state: {}",
+                    ready_state
+                );
+            }
+        }
+    }
+}
+
+/// Handles the `accountChanged(PublicKey | null)` event.
+///
+/// ## Behavior
+/// Providers emit `null` when the user switches to an account that has not been connected
+/// to the app yet.
+pub(crate) fn handle_account_changed_event(e: web_sys::Event, s: &EventBus) {
+    if e.is_null() || e.is_undefined() {
+        log::info!("Account changed event. No account connected.");
+        s.emit(Event::AccountChanged(None));
+        return;
+    }
+    if let Some(pubkey_string) = e.to_string().as_string() {
+        match Pubkey::from_str(&pubkey_string) {
+            Ok(r) => {
+                log::info!("Account changed event. Wallet Pubkey: {:?}", r);
+                s.emit(Event::AccountChanged(Some(r)));
+            }
+            Err(e) => {
+                log::error!(
+                    "Account changed event. Failed to parse wallet pubkey: {:?}",
+                    e
+                );
+            }
+        }
     }
 }
 
@@ -77,46 +121,131 @@ pub(crate) struct WalletEventListener {
     pub(crate) dc_ep: Mutex<EventProxy>,
     pub(crate) c_ep: Mutex<EventProxy>,
     pub(crate) e_ep: Mutex<EventProxy>,
-    /// The event receiver.
-    pub(crate) receiver: Option<UnboundedReceiver<Event>>,
+    pub(crate) ac_ep: Mutex<EventProxy>,
+    /// The bus the provider events are published to, adapters may emit their own events on it.
+    pub(crate) bus: EventBus,
+    /// The provider the proxies are bound to, for the active session.
+    emitter: Mutex<Option<Box<dyn WalletEventEmitter>>>,
+    status: Mutex<ListenerStatus>,
 }
 
 impl WalletEventListener {
-    pub fn new(ee: &dyn WalletEventEmitter) -> Self {
-        // disconecct
-        let (sender, receiver) = unbounded();
-        let sender = Arc::new(sender);
+    /// Creates the event proxies without binding them to a provider, they are bound once a
+    /// session starts.
+    pub(crate) fn unbound() -> Self {
+        let bus = EventBus::new();
 
+        // disconnect
         let dc_ep = Mutex::new(EventProxy::new(
-            sender.clone(),
+            bus.clone(),
             Arc::new(Mutex::new(handle_disconnect_event)),
         ));
-        ee.on_disconnect(&dc_ep.lock().unwrap().handler.as_ref().unwrap());
         // readyStateChange
         let rsc_ep = Mutex::new(EventProxy::new(
-            sender.clone(),
+            bus.clone(),
             Arc::new(Mutex::new(handle_ready_state_change_event)),
         ));
-        ee.on_ready_state_change(&rsc_ep.lock().unwrap().handler.as_ref().unwrap());
         // connect
         let c_ep = Mutex::new(EventProxy::new(
-            sender.clone(),
+            bus.clone(),
             Arc::new(Mutex::new(handle_connect_event)),
         ));
-        ee.on_connect(&c_ep.lock().unwrap().handler.as_ref().unwrap());
         // error
         let e_ep = Mutex::new(EventProxy::new(
-            sender.clone(),
+            bus.clone(),
             Arc::new(Mutex::new(handle_error_event)),
         ));
-        ee.on_error(&e_ep.lock().unwrap().handler.as_ref().unwrap());
+        // accountChanged
+        let ac_ep = Mutex::new(EventProxy::new(
+            bus.clone(),
+            Arc::new(Mutex::new(handle_account_changed_event)),
+        ));
         Self {
             e_ep,
+            ac_ep,
             dc_ep,
             c_ep,
             rsc_ep,
-            receiver: Some(receiver),
+            bus,
+            emitter: Mutex::new(None),
+            status: Mutex::new(ListenerStatus::Unbound),
+        }
+    }
+
+    /// Binds the event proxies to the provider, unbinding them from the previous one first.
+    pub(crate) fn listen<E: WalletEventEmitter + Clone + 'static>(&self, ee: &E) {
+        // Binding twice would deliver every event twice
+        self.unlisten();
+
+        if let Some(handler) = &self.dc_ep.lock().unwrap().handler {
+            ee.on_disconnect(handler);
+        }
+        if let Some(handler) = &self.rsc_ep.lock().unwrap().handler {
+            ee.on_ready_state_change(handler);
         }
+        if let Some(handler) = &self.c_ep.lock().unwrap().handler {
+            ee.on_connect(handler);
+        }
+        if let Some(handler) = &self.e_ep.lock().unwrap().handler {
+            ee.on_error(handler);
+        }
+        if let Some(handler) = &self.ac_ep.lock().unwrap().handler {
+            ee.on_account_changed(handler);
+        }
+        *self.emitter.lock().unwrap() = Some(Box::new(ee.clone()));
+        *self.status.lock().unwrap() = ListenerStatus::Bound;
+    }
+
+    /// Unbinds the event proxies from the provider, returning whether they could be removed.
+    ///
+    /// Proxies the provider cannot remove are muted and left to the provider, fresh proxies
+    /// take their place so that the next session does not share them.
+    pub(crate) fn unlisten(&self) -> ListenerStatus {
+        let Some(ee) = self.emitter.lock().unwrap().take() else {
+            return self.status();
+        };
+        let removed = [
+            release(&self.dc_ep, |h| ee.off_disconnect(h)),
+            release(&self.rsc_ep, |h| ee.off_ready_state_change(h)),
+            release(&self.c_ep, |h| ee.off_connect(h)),
+            release(&self.e_ep, |h| ee.off_error(h)),
+            release(&self.ac_ep, |h| ee.off_account_changed(h)),
+        ];
+        let status = if removed.iter().all(|r| *r) {
+            ListenerStatus::Removed
+        } else {
+            log::warn!("The provider kept some event listeners, they have been muted.");
+            ListenerStatus::Retained
+        };
+        *self.status.lock().unwrap() = status;
+        status
+    }
+
+    pub(crate) fn status(&self) -> ListenerStatus {
+        *self.status.lock().unwrap()
+    }
+
+    /// Subscribes to the events of the provider.
+    pub(crate) fn subscribe(&self) -> EventSubscription {
+        self.bus.subscribe()
+    }
+}
+
+/// Removes the proxy's handler from the provider, renewing the proxy when that fails.
+fn release(ep: &Mutex<EventProxy>, off: impl FnOnce(&EventHandler) -> bool) -> bool {
+    let mut ep = ep.lock().unwrap();
+    let removed = ep.handler.as_ref().map_or(true, off);
+    if !removed {
+        ep.renew();
+    }
+    removed
+}
+
+/// The proxies are unbound when the last adapter holding them is dropped, the provider would
+/// otherwise invoke dropped closures.
+impl Drop for WalletEventListener {
+    fn drop(&mut self) {
+        self.unlisten();
     }
 }
 
@@ -127,10 +256,14 @@ impl std::fmt::Debug for WalletEventListener {
             .field("c_ep", &format!("{:?}", self.c_ep))
             .field("dc_ep", &format!("{:?}", self.dc_ep))
             .field("e_ep", &format!("{:?}", self.e_ep))
+            .field("ac_ep", &format!("{:?}", self.ac_ep))
+            .field("bus", &self.bus)
+            .field("status", &self.status())
             .finish()
     }
 }
 
+/// Clones share the bus but are not bound to the provider.
 impl Clone for WalletEventListener {
     fn clone(&self) -> Self {
         Self {
@@ -138,27 +271,22 @@ impl Clone for WalletEventListener {
             c_ep: self.c_ep.lock().unwrap().clone().into(),
             dc_ep: self.dc_ep.lock().unwrap().clone().into(),
             e_ep: self.e_ep.lock().unwrap().clone().into(),
-            receiver: None,
+            ac_ep: self.ac_ep.lock().unwrap().clone().into(),
+            bus: self.bus.clone(),
+            emitter: Mutex::new(None),
+            status: Mutex::new(ListenerStatus::Unbound),
         }
     }
 
     fn clone_from(&mut self, source: &Self) {
+        self.unlisten();
         self.rsc_ep = source.rsc_ep.lock().unwrap().clone().into();
         self.c_ep = source.c_ep.lock().unwrap().clone().into();
         self.dc_ep = source.dc_ep.lock().unwrap().clone().into();
         self.e_ep = source.e_ep.lock().unwrap().clone().into();
-        self.receiver = None;
-    }
-}
-
-impl Stream for WalletEventListener {
-    type Item = Event;
-    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        if let Some(ref mut receiver) = &mut self.receiver {
-            Pin::new(receiver).poll_next(cx)
-        } else {
-            Poll::Ready(None)
-        }
+        self.ac_ep = source.ac_ep.lock().unwrap().clone().into();
+        self.bus = source.bus.clone();
+        self.status = Mutex::new(ListenerStatus::Unbound);
     }
 }
 
@@ -174,6 +302,10 @@ impl Stream for WalletEventListener {
 pub(crate) struct EventProxy {
     /// The rust closure that gets passed into js.
     pub(crate) handler: Option<EventHandler>,
+    /// Cleared once the handler outlives its session, it then ignores the events.
+    active: Rc<Cell<bool>>,
+    bus: EventBus,
+    f: EventCallback,
 }
 
 impl std::fmt::Debug for EventProxy {
@@ -185,24 +317,41 @@ impl std::fmt::Debug for EventProxy {
 }
 
 impl EventProxy {
-    pub fn new(
-        sender: Arc<UnboundedSender<Event>>,
-        f: Arc<Mutex<dyn FnMut(web_sys::Event, Arc<UnboundedSender<Event>>)>>,
-    ) -> Self {
-        let handler = Closure::new(move |e: web_sys::Event| {
-            let sender_c = sender.clone();
-            log::info!("web_sys::Event {:?}", e);
-            f.lock().unwrap()(e, sender_c);
-        });
+    pub fn new(bus: EventBus, f: EventCallback) -> Self {
+        let active = Rc::new(Cell::new(true));
+        let handler = {
+            let (active, bus, f) = (active.clone(), bus.clone(), f.clone());
+            Closure::new(move |e: web_sys::Event| {
+                if !active.get() {
+                    return;
+                }
+                log::info!("web_sys::Event {:?}", e);
+                f.lock().unwrap()(e, &bus);
+            })
+        };
 
         Self {
             handler: Some(handler),
+            active,
+            bus,
+            f,
+        }
+    }
+
+    /// Mutes the handler and leaves it to the provider, which may still invoke it, replacing
+    /// it by a fresh one.
+    fn renew(&mut self) {
+        self.active.set(false);
+        if let Some(handler) = self.handler.take() {
+            handler.forget();
         }
+        *self = Self::new(self.bus.clone(), self.f.clone());
     }
 }
 
+/// Clones get a handler of their own, the handler itself cannot be shared.
 impl Clone for EventProxy {
     fn clone(&self) -> Self {
-        Self { handler: None }
+        Self::new(self.bus.clone(), self.f.clone())
     }
 }
@@ -0,0 +1,180 @@
+#[cfg(feature = "deeplink")]
+use crate::deeplink::{DeepLinkCluster, DeepLinkProvider, DeepLinkWalletAdapter};
+use crate::popup::{PopupProvider, PopupWalletAdapter};
+use crate::{IntoWalletAdapter, Wallet, WALLETS};
+#[cfg(feature = "deeplink")]
+use solana_wallet_adapter_base::WalletReadyState;
+use solana_wallet_adapter_base::{WalletAdapter, WalletName};
+use std::sync::Arc;
+
+/// Describes a wallet only the registry knows, such as a keypair, popup, deep link or multisig
+/// adapter registered by the app.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegisteredWalletInfo {
+    pub name: String,
+    pub url: String,
+    pub icon: String,
+}
+
+/// The wallet adapters available to the app, keyed by wallet name.
+///
+/// Adapters keep the order they were registered in, which is the order wallets are listed in.
+/// Registering an adapter under a name that is already taken replaces the previous adapter in place,
+/// which lets apps override the built-in adapters with their own.
+#[derive(Default, Clone)]
+pub struct WalletRegistry {
+    adapters: Vec<(WalletName, Arc<dyn WalletAdapter>)>,
+}
+
+impl WalletRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry holding the adapters of the integrated wallets enabled through cargo features.
+    pub fn with_builtin() -> Self {
+        let mut registry = Self::new();
+        for wallet in WALLETS.iter() {
+            registry.register_wallet(wallet);
+        }
+        registry
+    }
+
+    /// Adds the web wallets, opened in a popup window, for users without the extension.
+    ///
+    /// Web wallets are listed under their own name, so an extension injected after the registry
+    /// was built still shows up under its adapter.
+    #[allow(unused_mut)]
+    pub fn with_popup_fallbacks(mut self) -> Self {
+        #[cfg(feature = "solflare")]
+        self.register_popup::<crate::solflare::SolflareProvider>();
+        self
+    }
+
+    #[allow(dead_code)]
+    fn register_popup<P: PopupProvider>(&mut self) {
+        self.register(Arc::new(PopupWalletAdapter::<P>::new()));
+    }
+
+    /// Replaces the adapters of the wallets that are not installed by their universal-link adapter,
+    /// when running in a mobile browser.
+    ///
+    /// In-app browsers of mobile wallets still inject their provider, those keep their adapter.
+    /// The adapters connect to `cluster` as it is when connecting.
+    #[cfg(feature = "deeplink")]
+    #[allow(unused_mut, unused_variables)]
+    pub fn with_deep_links(mut self, app_url: &str, cluster: &DeepLinkCluster) -> Self {
+        if !crate::deeplink::is_mobile_browser() {
+            return self;
+        }
+        #[cfg(feature = "phantom")]
+        self.register_deep_link::<crate::phantom::PhantomProvider>(app_url, cluster);
+        #[cfg(feature = "solflare")]
+        self.register_deep_link::<crate::solflare::SolflareProvider>(app_url, cluster);
+        self
+    }
+
+    #[cfg(feature = "deeplink")]
+    #[allow(dead_code)]
+    fn register_deep_link<P: DeepLinkProvider>(
+        &mut self,
+        app_url: &str,
+        cluster: &DeepLinkCluster,
+    ) {
+        let installed = self
+            .get(&WalletName::new(P::NAME))
+            .map_or(false, |a| a.ready_state() == WalletReadyState::Installed);
+        if !installed {
+            self.register(Arc::new(DeepLinkWalletAdapter::<P>::new(
+                app_url,
+                cluster.clone(),
+            )));
+        }
+    }
+
+    /// Registers an adapter, returning the adapter it replaced, if any.
+    pub fn register(&mut self, adapter: Arc<dyn WalletAdapter>) -> Option<Arc<dyn WalletAdapter>> {
+        let name = WalletName::new(adapter.name());
+        match self.adapters.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, existing)) => Some(std::mem::replace(existing, adapter)),
+            None => {
+                self.adapters.push((name, adapter));
+                None
+            }
+        }
+    }
+
+    /// Builds and registers the adapter of an integrated wallet.
+    ///
+    /// Returns `false` when the wallet has no adapter to build.
+    pub fn register_wallet(&mut self, wallet: &Wallet) -> bool {
+        match wallet.into_wallet_adapter() {
+            Some(adapter) => {
+                self.register(adapter);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Registers an adapter, builder style.
+    pub fn with(mut self, adapter: Arc<dyn WalletAdapter>) -> Self {
+        self.register(adapter);
+        self
+    }
+
+    /// Removes the adapter registered under `name`.
+    pub fn unregister(&mut self, name: &WalletName) -> Option<Arc<dyn WalletAdapter>> {
+        let index = self.adapters.iter().position(|(n, _)| n == name)?;
+        Some(self.adapters.remove(index).1)
+    }
+
+    /// Returns the adapter registered under `name`.
+    pub fn get(&self, name: &WalletName) -> Option<Arc<dyn WalletAdapter>> {
+        self.adapters
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, adapter)| adapter.clone())
+    }
+
+    pub fn contains(&self, name: &WalletName) -> bool {
+        self.adapters.iter().any(|(n, _)| n == name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &WalletName> {
+        self.adapters.iter().map(|(name, _)| name)
+    }
+
+    pub fn adapters(&self) -> impl Iterator<Item = &Arc<dyn WalletAdapter>> {
+        self.adapters.iter().map(|(_, adapter)| adapter)
+    }
+
+    pub fn len(&self) -> usize {
+        self.adapters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.adapters.is_empty()
+    }
+}
+
+impl std::fmt::Debug for WalletRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+/// Two registries are equal when they hold the very same adapters, in the same order.
+impl PartialEq for WalletRegistry {
+    fn eq(&self, other: &Self) -> bool {
+        self.adapters.len() == other.adapters.len()
+            && self
+                .adapters
+                .iter()
+                .zip(other.adapters.iter())
+                .all(|((a, x), (b, y))| {
+                    a == b && Arc::as_ptr(x) as *const () == Arc::as_ptr(y) as *const ()
+                })
+    }
+}
@@ -1,222 +1,34 @@
-#![allow(non_upper_case_globals)]
-use self::wasmgen::solflare;
-use crate::{
-    proxy::WalletEventListener, IntoPubkey, IntoWalletIcon, IntoWalletName, IntoWalletUrl, Wallet,
-};
-use async_trait::async_trait;
-use futures::StreamExt;
-use solana_client_wasm::solana_sdk::pubkey::Pubkey;
-use solana_wallet_adapter_base::{
-    Event, WalletAdapter, WalletAdapterBase, WalletAdapterMetadata, WalletReadyState,
-};
-use std::sync::Mutex;
-
-pub(crate) mod wasmgen {
-    use wasm_bindgen::{prelude::*, JsStatic};
-
-    use crate::{JsPublicKey, WalletEventEmitter};
-
-    #[wasm_bindgen]
-    extern "C" {
-        pub type Solflare;
-
-        pub static solflare: Solflare;
-
-        #[wasm_bindgen(method, getter=isConnected)]
-        pub fn is_connected(this: &Solflare) -> bool;
-
-        #[wasm_bindgen(method, getter=isSolflare)]
-        pub fn is_solflare(this: &Solflare) -> bool;
-
-        #[wasm_bindgen(method, getter=priorityFeesSupported)]
-        pub fn priority_fees_supported(this: &Solflare) -> bool;
-
-        #[wasm_bindgen(method, getter=publicKey)]
-        pub fn pubkey(this: &Solflare) -> JsPublicKey;
-
-        #[wasm_bindgen(method)]
-        pub fn on(this: &Solflare, e: &str, cb: &Closure<dyn FnMut(web_sys::Event)>) -> bool;
-
-        #[wasm_bindgen(method, catch, js_name=connect)]
-        pub async fn connect(this: &Solflare) -> Result<JsValue, JsValue>;
-        #[wasm_bindgen(method)]
-        pub async fn disconnect(this: &Solflare);
-
-        #[wasm_bindgen(method, catch, js_name=signMessage)]
-        pub async fn sign_message(
-            this: &Solflare,
-            message: Vec<u8>,
-            pubkey: Option<JsPublicKey>,
-        ) -> Result<JsValue, JsValue>;
-    }
-
-    impl WalletEventEmitter for JsStatic<Solflare> {
-        fn on_connect(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) {
-            self.on("connect", closure);
-        }
-
-        fn on_disconnect(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) {
-            self.on("disconnect", closure);
-        }
-
-        fn on_error(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) {
-            self.on("error", closure);
-        }
-
-        fn on_ready_state_change(&self, closure: &Closure<dyn FnMut(web_sys::Event)>) {
-            self.on("readyStateChange", closure);
-        }
-    }
-}
+#[cfg(feature = "deeplink")]
+use crate::deeplink::{DeepLinkProvider, DeepLinkWalletAdapter};
+use crate::injected::{InjectedProvider, InjectedWalletAdapter};
+use crate::popup::{PopupProvider, PopupWalletAdapter};
 
 #[derive(Debug)]
-pub struct SolflareWalletAdapter {
-    connecting: Mutex<bool>,
-    connected: Mutex<bool>,
-    pubkey: Pubkey,
-    wrs: WalletReadyState,
-    wel: Mutex<WalletEventListener>,
-}
-
-impl Clone for SolflareWalletAdapter {
-    fn clone(&self) -> Self {
-        Self {
-            connecting: self.connecting().into(),
-            connected: Mutex::new(*self.connected.lock().unwrap()),
-            pubkey: self.pubkey.clone(),
-            wrs: self.wrs.clone(),
-            wel: self.wel.lock().unwrap().clone().into(),
-        }
-    }
-
-    fn clone_from(&mut self, source: &Self) {
-        self.connecting = source.connecting().into();
-        self.connected = Mutex::new(*source.connected.lock().unwrap());
-        self.pubkey = source.pubkey.clone();
-        self.wel = source.wel.lock().unwrap().clone().into();
-        self.wrs = source.wrs.clone();
-    }
-}
-
-impl PartialEq for SolflareWalletAdapter {
-    fn eq(&self, other: &Self) -> bool {
-        self.pubkey == other.pubkey
-            && self.connecting() == other.connecting()
-            && *self.connected.lock().unwrap() == *other.connected.lock().unwrap()
-    }
-
-    fn ne(&self, other: &Self) -> bool {
-        self.pubkey != other.pubkey
-            && self.connecting() != other.connecting()
-            && *self.connected.lock().unwrap() != *other.connected.lock().unwrap()
-    }
-}
-
-impl SolflareWalletAdapter {
-    pub fn new() -> Self {
-        let pubkey = solflare.pubkey();
-        let wel = WalletEventListener::new(&solflare);
+pub struct SolflareProvider;
 
-        let wrs = if let Some(window) = web_sys::window() {
-            if let Some(_) = window.document() {
-                if solflare.is_undefined() {
-                    WalletReadyState::NotDetected
-                } else {
-                    WalletReadyState::Installed
-                }
-            } else {
-                WalletReadyState::Unsupported
-            }
-        } else {
-            WalletReadyState::Unsupported
-        };
-        Self {
-            connecting: false.into(),
-            connected: solflare.is_connected().into(),
-            pubkey: if pubkey.is_null() {
-                Pubkey::default()
-            } else {
-                pubkey.to_pubkey()
-            },
-            wrs,
-            wel: Mutex::new(wel),
-        }
-    }
+impl InjectedProvider for SolflareProvider {
+    const NAME: &'static str = "Solflare";
+    const URL: &'static str = "https://solflare.com/";
+    const ICON: &'static str = "data:image/svg+xml;base64,PHN2ZyBmaWxsPSJub25lIiBoZWlnaHQ9IjUwIiB2aWV3Qm94PSIwIDAgNTAgNTAiIHdpZHRoPSI1MCIgeG1sbnM9Imh0dHA6Ly93d3cudzMub3JnLzIwMDAvc3ZnIiB4bWxuczp4bGluaz0iaHR0cDovL3d3dy53My5vcmcvMTk5OS94bGluayI+PGxpbmVhckdyYWRpZW50IGlkPSJhIj48c3RvcCBvZmZzZXQ9IjAiIHN0b3AtY29sb3I9IiNmZmMxMGIiLz48c3RvcCBvZmZzZXQ9IjEiIHN0b3AtY29sb3I9IiNmYjNmMmUiLz48L2xpbmVhckdyYWRpZW50PjxsaW5lYXJHcmFkaWVudCBpZD0iYiIgZ3JhZGllbnRVbml0cz0idXNlclNwYWNlT25Vc2UiIHgxPSI2LjQ3ODM1IiB4Mj0iMzQuOTEwNyIgeGxpbms6aHJlZj0iI2EiIHkxPSI3LjkyIiB5Mj0iMzMuNjU5MyIvPjxyYWRpYWxHcmFkaWVudCBpZD0iYyIgY3g9IjAiIGN5PSIwIiBncmFkaWVudFRyYW5zZm9ybT0ibWF0cml4KDQuOTkyMTg4MzIgMTIuMDYzODc5NjMgLTEyLjE4MTEzNjU1IDUuMDQwNzEwNzQgMjIuNTIwMiAyMC42MTgzKSIgZ3JhZGllbnRVbml0cz0idXNlclNwYWNlT25Vc2UiIHI9IjEiIHhsaW5rOmhyZWY9IiNhIi8+PHBhdGggZD0ibTI1LjE3MDggNDcuOTEwNGMuNTI1IDAgLjk1MDcuNDIxLjk1MDcuOTQwM3MtLjQyNTcuOTQwMi0uOTUwNy45NDAyLS45NTA3LS40MjA5LS45NTA3LS45NDAyLjQyNTctLjk0MDMuOTUwNy0uOTQwM3ptLTEuMDMyOC00NC45MTU2NWMuNDY0Ni4wMzgzNi44Mzk4LjM5MDQuOTAyNy44NDY4MWwxLjEzMDcgOC4yMTU3NGMuMzc5OCAyLjcxNDMgMy42NTM1IDMuODkwNCA1LjY3NDMgMi4wNDU5bDExLjMyOTEtMTAuMzExNThjLjI3MzMtLjI0ODczLjY5ODktLjIzMTQ5Ljk1MDcuMDM4NTEuMjMwOS4yNDc3Mi4yMzc5LjYyNjk3LjAxNjEuODgyNzdsLTkuODc5MSAxMS4zOTU4Yy0xLjgxODcgMi4wOTQyLS40NzY4IDUuMzY0MyAyLjI5NTYgNS41OTc4bDguNzE2OC44NDAzYy40MzQxLjA0MTguNzUxNy40MjM0LjcwOTMuODUyNC0uMDM0OS4zNTM3LS4zMDc0LjYzOTUtLjY2MjguNjk0OWwtOS4xNTk0IDEuNDMwMmMtMi42NTkzLjM2MjUtMy44NjM2IDMuNTExNy0yLjEzMzkgNS41NTc2bDMuMjIgMy43OTYxYy4yNTk0LjMwNTguMjE4OC43NjE1LS4wOTA4IDEuMDE3OC0uMjYyMi4yMTcyLS42NDE5LjIyNTYtLjkxMzguMDIwM2wtMy45Njk0LTIuOTk3OGMtMi4xNDIxLTEuNjEwOS01LjIyOTctLjI0MTctNS40NTYxIDIuNDI0M2wtLjg3NDcgMTAuMzk3NmMtLjAzNjIuNDI5NS0uNDE3OC43NDg3LS44NTI1LjcxMy0uMzY5LS4wMzAzLS42NjcxLS4zMDk3LS43MTcxLS42NzIxbC0xLjM4NzEtMTAuMDQzN2MtLjM3MTctMi43MTQ0LTMuNjQ1NC0zLjg5MDQtNS42NzQzLTIuMDQ1OWwtMTIuMDUxOTUgMTAuOTc0Yy0uMjQ5NDcuMjI3MS0uNjM4MDkuMjExNC0uODY4LS4wMzUtLjIxMDk0LS4yMjYyLS4yMTczNS0uNTcyNC0uMDE0OTMtLjgwNmwxMC41MTgxOC0xMi4xMzg1YzEuODE4Ny0yLjA5NDIuNDg0OS01LjM2NDQtMi4yODc2LTUuNTk3OGwtOC43MTg3Mi0uODQwNWMtLjQzNDEzLS4wNDE4LS43NTE3Mi0uNDIzNS0uNzA5MzYtLjg1MjQuMDM0OTMtLjM1MzcuMzA3MzktLjYzOTQuNjYyNy0uNjk1bDkuMTUzMzgtMS40Mjk5YzIuNjU5NC0uMzYyNSAzLjg3MTgtMy41MTE3IDIuMTQyMS01LjU1NzZsLTIuMTkyLTIuNTg0MWMtLjMyMTctLjM3OTItLjI3MTMtLjk0NDMuMTEyNi0xLjI2MjEuMzI1My0uMjY5NC43OTYzLS4yNzk3IDEuMTMzNC0uMDI0OWwyLjY5MTggMi4wMzQ3YzIuMTQyMSAxLjYxMDkgNS4yMjk3LjI0MTcgNS40NTYxLTIuNDI0M2wuNzI0MS04LjU1OTk4Yy4wNDU3LS41NDA4LjUyNjUtLjk0MjU3IDEuMDczOS0uODk3Mzd6bS0yMy4xODczMyAyMC40Mzk2NWMuNTI1MDQgMCAuOTUwNjcuNDIxLjk1MDY3Ljk0MDNzLS40MjU2My45NDAzLS45NTA2Ny45NDAzYy0uNTI1MDQxIDAtLjk1MDY3LS40MjEtLjk1MDY3LS45NDAzcy40MjU2MjktLjk0MDMuOTUwNjctLjk0MDN6bTQ3LjY3OTczLS45NTQ3Yy41MjUgMCAuOTUwNy40MjEuOTUwNy45NDAzcy0uNDI1Ny45NDAyLS45NTA3Ljk0MDItLjk1MDctLjQyMDktLjk1MDctLjk0MDIuNDI1Ny0uOTQwMy45NTA3LS45NDAzem0tMjQuNjI5Ni0yMi40Nzk3Yy41MjUgMCAuOTUwNi40MjA5NzMuOTUwNi45NDAyNyAwIC41MTkzLS40MjU2Ljk0MDI3LS45NTA2Ljk0MDI3LS41MjUxIDAtLjk1MDctLjQyMDk3LS45NTA3LS45NDAyNyAwLS41MTkyOTcuNDI1Ni0uOTQwMjcuOTUwNy0uOTQwMjd6IiBmaWxsPSJ1cmwoI2IpIi8+PHBhdGggZD0ibTI0LjU3MSAzMi43NzkyYzQuOTU5NiAwIDguOTgwMi0zLjk3NjUgOC45ODAyLTguODgxOSAwLTQuOTA1My00LjAyMDYtOC44ODE5LTguOTgwMi04Ljg4MTlzLTguOTgwMiAzLjk3NjYtOC45ODAyIDguODgxOWMwIDQuOTA1NCA0LjAyMDYgOC44ODE5IDguOTgwMiA4Ljg4MTl6IiBmaWxsPSJ1cmwoI2MpIi8+PC9zdmc+";
+    const PATH: &'static [&'static str] = &["solflare"];
+    const FLAG: Option<&'static str> = Some("isSolflare");
 }
 
-impl WalletAdapterMetadata for SolflareWalletAdapter {
-    fn name(&self) -> &str {
-        Wallet::Solflare.into_wallet_name()
-    }
+pub type SolflareWalletAdapter = InjectedWalletAdapter<SolflareProvider>;
 
-    fn url(&self) -> &str {
-        Wallet::Solflare.into_wallet_url()
-    }
-
-    fn icon(&self) -> &str {
-        Wallet::Solflare.into_wallet_icon()
-    }
+impl PopupProvider for SolflareProvider {
+    const POPUP_NAME: &'static str = "Solflare Web";
+    const POPUP_URL: &'static str = "https://solflare.com/provider";
 }
 
-#[async_trait(?Send)]
-impl WalletAdapterBase for SolflareWalletAdapter {
-    fn is_name(&self) -> bool {
-        solflare.is_solflare()
-    }
-
-    fn is_connected(&self) -> bool {
-        solflare.is_connected()
-    }
-
-    fn ready_state(&self) -> WalletReadyState{
-        self.wrs.clone()
-    }
-
-    fn pubkey(&self) -> Option<Pubkey> {
-        let pubkey = solflare.pubkey();
-        if pubkey.is_null() {
-            None
-        } else {
-            Some(pubkey.to_pubkey())
-        }
-    }
+/// The Solflare web wallet, for browsers without the extension.
+pub type SolflareWebWalletAdapter = PopupWalletAdapter<SolflareProvider>;
 
-    fn connecting(&self) -> bool {
-        *self.connecting.lock().unwrap()
-    }
-
-    async fn connect(&self, _: Option<&str>) {
-        *self.connecting.lock().unwrap() = true;
-        match solflare.connect().await {
-            Ok(r) => {
-                log::info!("Successfully invoked connect. {:?}", r.as_string());
-            }
-            Err(e) => {
-                log::error!("Error invoking connect. {:?}", e.as_string());
-            }
-        }
-        *self.connecting.lock().unwrap() = false;
-    }
-
-    async fn disconnect(&self) {
-        solflare.disconnect().await
-    }
+#[cfg(feature = "deeplink")]
+impl DeepLinkProvider for SolflareProvider {
+    const BASE_URL: &'static str = "https://solflare.com/ul/v1/";
+    const ENCRYPTION_PUBLIC_KEY_PARAM: &'static str = "solflare_encryption_public_key";
 }
 
-#[async_trait(?Send)]
-impl WalletAdapter for SolflareWalletAdapter {
-    fn priority_fees_supported(&self) -> bool {
-        solflare.priority_fees_supported()
-    }
-
-    async fn on_event(&self, f: &mut dyn FnMut(Event)) {
-        let mut wel = self.wel.lock().unwrap();
-        if let Some(ref mut receiver) = &mut wel.receiver {
-            loop {
-                if let Some(msg) = receiver.next().await {
-                    f(msg)
-                }
-            }
-        }
-    }
-}
+#[cfg(feature = "deeplink")]
+pub type SolflareDeepLinkAdapter = DeepLinkWalletAdapter<SolflareProvider>;
@@ -0,0 +1,611 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use solana_client_wasm::solana_sdk::{
+    hash::{hash, Hash},
+    instruction::{AccountMeta, Instruction},
+    message::{v0::MessageAddressTableLookup, MessageHeader, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Signature,
+    system_program,
+    transaction::Transaction,
+};
+use solana_wallet_adapter_base::{
+    ConnectOptions, Connection, Event, EventBus, EventSubscription, ListenerStatus, ProviderError,
+    ProviderErrorCode, SignInInput, SignInOutput, WalletAdapter, WalletAdapterBase,
+    WalletAdapterMetadata, WalletCapabilities, WalletError, WalletReadyState, WalletTransaction,
+    DEFAULT_TRANSACTION_VERSIONS,
+};
+use std::sync::Arc;
+
+/// The Squads v4 program, `SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf`.
+pub const SQUADS_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+    6, 129, 196, 206, 71, 226, 35, 104, 184, 177, 85, 94, 200, 135, 175, 9, 46, 252, 126, 251, 182,
+    108, 163, 245, 47, 191, 104, 212, 172, 156, 183, 168,
+]);
+
+pub const SQUADS_WALLET_NAME: &str = "Squads";
+
+const SQUADS_WALLET_ICON: &str = "data:image/svg+xml;base64,PHN2ZyB4bWxucz0iaHR0cDovL3d3dy53My5vcmcvMjAwMC9zdmciIHdpZHRoPSIyNCIgaGVpZ2h0PSIyNCIgdmlld0JveD0iMCAwIDI0IDI0Ij48cmVjdCB3aWR0aD0iMjQiIGhlaWdodD0iMjQiIHJ4PSI2IiBmaWxsPSIjMDAwIi8+PHJlY3QgeD0iNiIgeT0iNiIgd2lkdGg9IjEyIiBoZWlnaHQ9IjEyIiByeD0iMiIgZmlsbD0ibm9uZSIgc3Ryb2tlPSIjZmZmIiBzdHJva2Utd2lkdGg9IjIiLz48L3N2Zz4=";
+
+const SEED_PREFIX: &[u8] = b"multisig";
+const SEED_VAULT: &[u8] = b"vault";
+const SEED_TRANSACTION: &[u8] = b"transaction";
+const SEED_PROPOSAL: &[u8] = b"proposal";
+
+/// Offset of `transaction_index` in a `Multisig` account: the discriminator, `create_key`,
+/// `config_authority`, `threshold` and `time_lock` come before it.
+const MULTISIG_TRANSACTION_INDEX_OFFSET: usize = 8 + 32 + 32 + 2 + 4;
+
+/// The vault of a multisig, the account that holds its assets and signs its transactions.
+pub fn get_vault_pda(multisig: &Pubkey, vault_index: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SEED_PREFIX, multisig.as_ref(), SEED_VAULT, &[vault_index]],
+        &SQUADS_PROGRAM_ID,
+    )
+}
+
+pub fn get_transaction_pda(multisig: &Pubkey, transaction_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SEED_PREFIX,
+            multisig.as_ref(),
+            SEED_TRANSACTION,
+            &transaction_index.to_le_bytes(),
+        ],
+        &SQUADS_PROGRAM_ID,
+    )
+}
+
+pub fn get_proposal_pda(multisig: &Pubkey, transaction_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SEED_PREFIX,
+            multisig.as_ref(),
+            SEED_TRANSACTION,
+            &transaction_index.to_le_bytes(),
+            SEED_PROPOSAL,
+        ],
+        &SQUADS_PROGRAM_ID,
+    )
+}
+
+/// The anchor discriminator of a program instruction.
+fn discriminator(name: &str) -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash(format!("global:{}", name).as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+/// Reads the index of the last transaction created in a multisig from its account data.
+pub fn multisig_transaction_index(data: &[u8]) -> Result<u64, WalletError> {
+    let bytes = data
+        .get(MULTISIG_TRANSACTION_INDEX_OFFSET..MULTISIG_TRANSACTION_INDEX_OFFSET + 8)
+        .ok_or(WalletError::Account)?;
+    Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+/// Encodes a message in the compact `TransactionMessage` format stored by vault transactions.
+///
+/// Vectors are prefixed with a `u8` length, except for instruction data which takes a `u16`.
+/// The message must only require the signature of the vault, which pays for it.
+pub fn encode_transaction_message(
+    message: &VersionedMessage,
+    vault: &Pubkey,
+) -> Result<Vec<u8>, WalletError> {
+    let (header, account_keys, instructions, lookups): (
+        &MessageHeader,
+        _,
+        _,
+        &[MessageAddressTableLookup],
+    ) = match message {
+        VersionedMessage::Legacy(m) => (&m.header, &m.account_keys, &m.instructions, &[]),
+        VersionedMessage::V0(m) => (
+            &m.header,
+            &m.account_keys,
+            &m.instructions,
+            &m.address_table_lookups,
+        ),
+    };
+    if header.num_required_signatures != 1 || account_keys.first() != Some(vault) {
+        log::error!("Vault transactions can only be signed by the vault.");
+        return Err(WalletError::SignTransaction);
+    }
+
+    let num_signers = header.num_required_signatures;
+    let num_writable_signers = num_signers - header.num_readonly_signed_accounts;
+    let num_writable_non_signers = (account_keys.len() as u8)
+        .checked_sub(num_signers + header.num_readonly_unsigned_accounts)
+        .ok_or(WalletError::SignTransaction)?;
+
+    let mut out = vec![num_signers, num_writable_signers, num_writable_non_signers];
+    push_len_u8(&mut out, account_keys.len())?;
+    for key in account_keys {
+        out.extend_from_slice(key.as_ref());
+    }
+    push_len_u8(&mut out, instructions.len())?;
+    for instruction in instructions {
+        out.push(instruction.program_id_index);
+        push_len_u8(&mut out, instruction.accounts.len())?;
+        out.extend_from_slice(&instruction.accounts);
+        let len =
+            u16::try_from(instruction.data.len()).map_err(|_| WalletError::SignTransaction)?;
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&instruction.data);
+    }
+    push_len_u8(&mut out, lookups.len())?;
+    for lookup in lookups {
+        out.extend_from_slice(lookup.account_key.as_ref());
+        push_len_u8(&mut out, lookup.writable_indexes.len())?;
+        out.extend_from_slice(&lookup.writable_indexes);
+        push_len_u8(&mut out, lookup.readonly_indexes.len())?;
+        out.extend_from_slice(&lookup.readonly_indexes);
+    }
+    Ok(out)
+}
+
+fn push_len_u8(out: &mut Vec<u8>, len: usize) -> Result<(), WalletError> {
+    out.push(u8::try_from(len).map_err(|_| WalletError::SignTransaction)?);
+    Ok(())
+}
+
+/// Builds the `vault_transaction_create` instruction, storing `transaction_message` as the
+/// transaction at `transaction_index`.
+pub fn vault_transaction_create(
+    multisig: &Pubkey,
+    creator: &Pubkey,
+    rent_payer: &Pubkey,
+    transaction_index: u64,
+    vault_index: u8,
+    transaction_message: &[u8],
+    memo: Option<&str>,
+) -> Instruction {
+    let (transaction, _) = get_transaction_pda(multisig, transaction_index);
+
+    // Borsh encoded `VaultTransactionCreateArgs`
+    let mut data = discriminator("vault_transaction_create").to_vec();
+    data.push(vault_index);
+    data.push(0); // ephemeral signers
+    data.extend_from_slice(&(transaction_message.len() as u32).to_le_bytes());
+    data.extend_from_slice(transaction_message);
+    match memo {
+        Some(memo) => {
+            data.push(1);
+            data.extend_from_slice(&(memo.len() as u32).to_le_bytes());
+            data.extend_from_slice(memo.as_bytes());
+        }
+        None => data.push(0),
+    }
+
+    Instruction {
+        program_id: SQUADS_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*multisig, false),
+            AccountMeta::new(transaction, false),
+            AccountMeta::new_readonly(*creator, true),
+            AccountMeta::new(*rent_payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data,
+    }
+}
+
+/// Builds the `proposal_create` instruction, opening the vote on the transaction at `transaction_index`.
+pub fn proposal_create(
+    multisig: &Pubkey,
+    creator: &Pubkey,
+    rent_payer: &Pubkey,
+    transaction_index: u64,
+    draft: bool,
+) -> Instruction {
+    let (proposal, _) = get_proposal_pda(multisig, transaction_index);
+
+    // Borsh encoded `ProposalCreateArgs`
+    let mut data = discriminator("proposal_create").to_vec();
+    data.extend_from_slice(&transaction_index.to_le_bytes());
+    data.push(draft as u8);
+
+    Instruction {
+        program_id: SQUADS_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(*multisig, false),
+            AccountMeta::new(proposal, false),
+            AccountMeta::new_readonly(*creator, true),
+            AccountMeta::new(*rent_payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data,
+    }
+}
+
+/// Wraps a transaction of the vault into a transaction of `member` proposing it to the multisig.
+pub fn propose_transaction(
+    multisig: &Pubkey,
+    vault_index: u8,
+    member: &Pubkey,
+    transaction_index: u64,
+    transaction: &WalletTransaction,
+) -> Result<WalletTransaction, WalletError> {
+    let (vault, _) = get_vault_pda(multisig, vault_index);
+    let (message, blockhash): (VersionedMessage, Hash) = match transaction {
+        WalletTransaction::Legacy(tx) => (
+            VersionedMessage::Legacy(tx.message.clone()),
+            tx.message.recent_blockhash,
+        ),
+        WalletTransaction::Versioned(tx) => (tx.message.clone(), *tx.message.recent_blockhash()),
+    };
+    let transaction_message = encode_transaction_message(&message, &vault)?;
+
+    let instructions = [
+        vault_transaction_create(
+            multisig,
+            member,
+            member,
+            transaction_index,
+            vault_index,
+            &transaction_message,
+            None,
+        ),
+        proposal_create(multisig, member, member, transaction_index, false),
+    ];
+    let mut proposal = Transaction::new_with_payer(&instructions, Some(member));
+    proposal.message.recent_blockhash = blockhash;
+    Ok(proposal.into())
+}
+
+/// A wallet adapter acting as the vault of a Squads v4 multisig.
+///
+/// Its public key is the vault, transactions are not signed directly but proposed to the
+/// multisig: each one is wrapped into a `vault_transaction_create` and `proposal_create` pair,
+/// signed by the connected wallet of a member. The vault transaction executes once the
+/// proposal is approved in Squads.
+pub struct SquadsWalletAdapter {
+    multisig: Pubkey,
+    vault_index: u8,
+    vault: Pubkey,
+    member: Arc<dyn WalletAdapter>,
+    connection: Connection,
+    bus: EventBus,
+}
+
+impl std::fmt::Debug for SquadsWalletAdapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SquadsWalletAdapter")
+            .field("multisig", &format!("{}", self.multisig))
+            .field("vault", &format!("{}", self.vault))
+            .field("member", &self.member.name())
+            .finish()
+    }
+}
+
+impl SquadsWalletAdapter {
+    /// Creates the adapter for the vault at `vault_index` of `multisig`, proposing transactions
+    /// through the wallet of a `member`.
+    pub fn new(
+        multisig: Pubkey,
+        vault_index: u8,
+        member: Arc<dyn WalletAdapter>,
+        connection: Connection,
+    ) -> Self {
+        let bus = EventBus::new();
+
+        // Follow the member wallet, the connected account is always the vault
+        let mut events = member.subscribe();
+        let forward = bus.clone();
+        let (vault, _) = get_vault_pda(&multisig, vault_index);
+        wasm_bindgen_futures::spawn_local(async move {
+            while let Some(event) = events.next().await {
+                forward.emit(match event {
+                    Event::Connect(_) | Event::AccountChanged(Some(_)) => Event::Connect(vault),
+                    Event::AccountChanged(None) => Event::Disconnect,
+                    event => event,
+                });
+            }
+        });
+
+        Self {
+            multisig,
+            vault_index,
+            vault,
+            member,
+            connection,
+            bus,
+        }
+    }
+
+    pub fn multisig(&self) -> &Pubkey {
+        &self.multisig
+    }
+
+    pub fn vault(&self) -> &Pubkey {
+        &self.vault
+    }
+
+    /// The index the next transaction of the multisig is created at.
+    async fn next_transaction_index(&self) -> Result<u64, WalletError> {
+        let data = self
+            .connection
+            .rpc()
+            .get_account_data(&self.multisig)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to fetch multisig {}: {:?}", self.multisig, e);
+                WalletError::Account
+            })?;
+        Ok(multisig_transaction_index(&data)? + 1)
+    }
+
+    fn member_pubkey(&self) -> Result<Pubkey, WalletError> {
+        self.member.pubkey().ok_or(WalletError::NotConnected)
+    }
+
+    fn unsupported(method: &str) -> WalletError {
+        WalletError::Provider(ProviderError::new(
+            ProviderErrorCode::UnsupportedMethod,
+            format!("A multisig vault cannot {}", method),
+        ))
+    }
+}
+
+impl WalletAdapterMetadata for SquadsWalletAdapter {
+    fn name(&self) -> &str {
+        SQUADS_WALLET_NAME
+    }
+
+    fn url(&self) -> &str {
+        "https://squads.so/"
+    }
+
+    fn icon(&self) -> &str {
+        SQUADS_WALLET_ICON
+    }
+}
+
+#[async_trait(?Send)]
+impl WalletAdapterBase for SquadsWalletAdapter {
+    fn is_name(&self) -> bool {
+        true
+    }
+
+    fn is_connected(&self) -> bool {
+        self.member.is_connected()
+    }
+
+    fn ready_state(&self) -> WalletReadyState {
+        self.member.ready_state()
+    }
+
+    fn pubkey(&self) -> Option<Pubkey> {
+        self.member.pubkey().map(|_| self.vault)
+    }
+
+    fn connecting(&self) -> bool {
+        self.member.connecting()
+    }
+
+    async fn connect(&self, options: ConnectOptions) -> Result<Pubkey, WalletError> {
+        self.member.connect(options).await.map(|_| self.vault)
+    }
+
+    async fn disconnect(&self) {
+        self.member.disconnect().await
+    }
+
+    /// Returns the proposal of the transaction, signed by the member, rather than the transaction itself.
+    async fn sign_transaction(
+        &self,
+        transaction: WalletTransaction,
+    ) -> Result<WalletTransaction, WalletError> {
+        let member = self.member_pubkey()?;
+        let index = self.next_transaction_index().await?;
+        let proposal = propose_transaction(
+            &self.multisig,
+            self.vault_index,
+            &member,
+            index,
+            &transaction,
+        )?;
+        self.member.sign_transaction(proposal).await
+    }
+
+    async fn sign_all_transactions(
+        &self,
+        transactions: Vec<WalletTransaction>,
+    ) -> Result<Vec<WalletTransaction>, WalletError> {
+        let member = self.member_pubkey()?;
+        let first_index = self.next_transaction_index().await?;
+        let proposals = transactions
+            .iter()
+            .enumerate()
+            .map(|(i, transaction)| {
+                propose_transaction(
+                    &self.multisig,
+                    self.vault_index,
+                    &member,
+                    first_index + i as u64,
+                    transaction,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.member.sign_all_transactions(proposals).await
+    }
+
+    /// Sends the proposal of the transaction, returning its signature.
+    async fn sign_and_send_transaction(
+        &self,
+        transaction: WalletTransaction,
+    ) -> Result<Signature, WalletError> {
+        let member = self.member_pubkey()?;
+        let index = self.next_transaction_index().await?;
+        let proposal = propose_transaction(
+            &self.multisig,
+            self.vault_index,
+            &member,
+            index,
+            &transaction,
+        )?;
+        self.member.sign_and_send_transaction(proposal).await
+    }
+
+    async fn sign_message(&self, _: &[u8]) -> Result<Signature, WalletError> {
+        Err(Self::unsupported("sign messages"))
+    }
+
+    async fn sign_in(&self, _: SignInInput) -> Result<SignInOutput, WalletError> {
+        Err(Self::unsupported("sign in"))
+    }
+}
+
+#[async_trait(?Send)]
+impl WalletAdapter for SquadsWalletAdapter {
+    fn priority_fees_supported(&self) -> bool {
+        self.member.priority_fees_supported()
+    }
+
+    /// Vault transactions of either version are proposed in a legacy transaction, which the
+    /// member signs.
+    fn capabilities(&self) -> WalletCapabilities {
+        let member = self.member.capabilities();
+        WalletCapabilities {
+            supported_transaction_versions: DEFAULT_TRANSACTION_VERSIONS.to_vec(),
+            sign_message: false,
+            sign_in: false,
+            ..member
+        }
+    }
+
+    fn subscribe(&self) -> EventSubscription {
+        self.bus.subscribe()
+    }
+
+    fn listener_status(&self) -> ListenerStatus {
+        self.member.listener_status()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_client_wasm::solana_sdk::{message::Message, system_instruction};
+    use std::str::FromStr;
+
+    /// The multisig of the create key `[1, 2, .., 32]`.
+    const MULTISIG: &str = "7EBQS3dn9pEurmyx9ZSCy5ovHupVgzM3VTHBf27Xbjaa";
+
+    fn multisig() -> Pubkey {
+        Pubkey::from_str(MULTISIG).unwrap()
+    }
+
+    #[test]
+    fn matches_the_program_id() {
+        assert_eq!(
+            SQUADS_PROGRAM_ID,
+            Pubkey::from_str("SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf").unwrap()
+        );
+    }
+
+    #[test]
+    fn matches_the_instruction_discriminators() {
+        assert_eq!(
+            discriminator("vault_transaction_create"),
+            [48, 250, 78, 168, 208, 226, 218, 211]
+        );
+        assert_eq!(
+            discriminator("proposal_create"),
+            [220, 60, 73, 224, 30, 108, 79, 159]
+        );
+    }
+
+    #[test]
+    fn derives_the_program_addresses() {
+        let create_key = Pubkey::new_from_array(std::array::from_fn(|i| i as u8 + 1));
+        let (multisig_pda, _) = Pubkey::find_program_address(
+            &[SEED_PREFIX, b"multisig", create_key.as_ref()],
+            &SQUADS_PROGRAM_ID,
+        );
+        assert_eq!(multisig_pda, multisig());
+
+        let (vault, _) = get_vault_pda(&multisig(), 0);
+        assert_eq!(
+            vault.to_string(),
+            "4CvTsDr7gxomG9arJgaECwStRoZc4BDzmWieNN5Cfsyw"
+        );
+        let (transaction, _) = get_transaction_pda(&multisig(), 1);
+        assert_eq!(
+            transaction.to_string(),
+            "3JCcWTheYSgq9jrEofBt4RFvZscT1ZWg2E2ofKUXjQWG"
+        );
+        let (proposal, bump) = get_proposal_pda(&multisig(), 1);
+        assert_eq!(
+            proposal.to_string(),
+            "DHfaVNRASgr4oaSZi9gJm7B9MhpqEkcH2CnhYwspDiR9"
+        );
+        assert_eq!(bump, 254);
+    }
+
+    #[test]
+    fn reads_the_transaction_index_of_a_multisig() {
+        let mut data = vec![0u8; 8 + 32 + 32];
+        data.extend_from_slice(&2u16.to_le_bytes()); // threshold
+        data.extend_from_slice(&0u32.to_le_bytes()); // time_lock
+        data.extend_from_slice(&42u64.to_le_bytes()); // transaction_index
+        data.extend_from_slice(&40u64.to_le_bytes()); // stale_transaction_index
+        assert_eq!(multisig_transaction_index(&data).unwrap(), 42);
+        assert_eq!(
+            multisig_transaction_index(&data[..80]),
+            Err(WalletError::Account)
+        );
+    }
+
+    #[test]
+    fn encodes_compact_transaction_messages() {
+        let (vault, _) = get_vault_pda(&multisig(), 0);
+        let recipient = Pubkey::new_from_array([9; 32]);
+        let message = Message::new(
+            &[system_instruction::transfer(&vault, &recipient, 1_000)],
+            Some(&vault),
+        );
+
+        let mut expected = vec![1, 1, 1, 3];
+        expected.extend_from_slice(vault.as_ref());
+        expected.extend_from_slice(recipient.as_ref());
+        expected.extend_from_slice(system_program::id().as_ref());
+        expected.extend_from_slice(&[1, 2, 2, 0, 1, 12, 0]);
+        expected.extend_from_slice(&[2, 0, 0, 0]);
+        expected.extend_from_slice(&1_000u64.to_le_bytes());
+        expected.push(0);
+        assert_eq!(
+            encode_transaction_message(&VersionedMessage::Legacy(message.clone()), &vault).unwrap(),
+            expected
+        );
+
+        // Only the vault may sign the stored transaction
+        assert_eq!(
+            encode_transaction_message(&VersionedMessage::Legacy(message), &recipient),
+            Err(WalletError::SignTransaction)
+        );
+    }
+
+    #[test]
+    fn encodes_the_instruction_args() {
+        let member = Pubkey::new_from_array([7; 32]);
+        let create =
+            vault_transaction_create(&multisig(), &member, &member, 1, 0, &[1, 2, 3], Some("hi"));
+        let mut expected = vec![48, 250, 78, 168, 208, 226, 218, 211, 0, 0];
+        expected.extend_from_slice(&[3, 0, 0, 0, 1, 2, 3]);
+        expected.extend_from_slice(&[1, 2, 0, 0, 0, b'h', b'i']);
+        assert_eq!(create.data, expected);
+        assert_eq!(
+            create.accounts[1].pubkey,
+            get_transaction_pda(&multisig(), 1).0
+        );
+
+        let proposal = proposal_create(&multisig(), &member, &member, 1, false);
+        let mut expected = vec![220, 60, 73, 224, 30, 108, 79, 159];
+        expected.extend_from_slice(&[1, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(proposal.data, expected);
+        assert_eq!(
+            proposal.accounts[1].pubkey,
+            get_proposal_pda(&multisig(), 1).0
+        );
+    }
+}
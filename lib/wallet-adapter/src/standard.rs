@@ -0,0 +1,815 @@
+use self::wasmgen::{StandardWallet, StandardWalletAccount};
+use crate::{from_js_sign_in_output, proxy::WalletEventListener, to_js_sign_in_input, Wallet};
+use async_trait::async_trait;
+use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use gloo_events::EventListener;
+use solana_client_wasm::solana_sdk::{
+    pubkey::Pubkey, signature::Signature, transaction::TransactionVersion,
+};
+use solana_wallet_adapter_base::{
+    sign_in_with_message, with_timeout, ConnectOptions, Event, EventBus, EventSubscription,
+    ListenerStatus, SignInInput, SignInOutput, WalletAdapter, WalletAdapterBase,
+    WalletAdapterMetadata, WalletCapabilities, WalletError, WalletReadyState, WalletTransaction,
+};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+
+/// Dispatched by wallets when they are ready to register.
+const REGISTER_WALLET_EVENT: &str = "wallet-standard:register-wallet";
+/// Dispatched by the app so that wallets which loaded first can register.
+const APP_READY_EVENT: &str = "wallet-standard:app-ready";
+
+const STANDARD_CONNECT: &str = "standard:connect";
+const STANDARD_DISCONNECT: &str = "standard:disconnect";
+const STANDARD_EVENTS: &str = "standard:events";
+const SOLANA_SIGN_TRANSACTION: &str = "solana:signTransaction";
+const SOLANA_SIGN_AND_SEND_TRANSACTION: &str = "solana:signAndSendTransaction";
+const SOLANA_SIGN_MESSAGE: &str = "solana:signMessage";
+const SOLANA_SIGN_IN: &str = "solana:signIn";
+
+const DEFAULT_CHAIN: &str = "solana:mainnet";
+
+/// The wallet standard chain of the cluster behind the given RPC endpoint.
+fn chain_of_endpoint(endpoint: &str) -> &'static str {
+    if endpoint.contains("devnet") {
+        "solana:devnet"
+    } else if endpoint.contains("testnet") {
+        "solana:testnet"
+    } else if ["localhost", "127.0.0.1", "0.0.0.0"]
+        .iter()
+        .any(|host| endpoint.contains(host))
+    {
+        "solana:localnet"
+    } else {
+        DEFAULT_CHAIN
+    }
+}
+
+pub(crate) mod wasmgen {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        /// A wallet registered through the wallet standard.
+        #[derive(Debug, Clone)]
+        pub type StandardWallet;
+
+        #[wasm_bindgen(method, getter)]
+        pub fn name(this: &StandardWallet) -> String;
+
+        #[wasm_bindgen(method, getter)]
+        pub fn icon(this: &StandardWallet) -> String;
+
+        #[wasm_bindgen(method, getter)]
+        pub fn chains(this: &StandardWallet) -> js_sys::Array;
+
+        #[wasm_bindgen(method, getter)]
+        pub fn features(this: &StandardWallet) -> js_sys::Object;
+
+        #[wasm_bindgen(method, getter)]
+        pub fn accounts(this: &StandardWallet) -> js_sys::Array;
+
+        /// An account a standard wallet has authorized the app to use.
+        #[derive(Debug, Clone)]
+        pub type StandardWalletAccount;
+
+        #[wasm_bindgen(method, getter)]
+        pub fn address(this: &StandardWalletAccount) -> String;
+
+        #[wasm_bindgen(method, getter)]
+        pub fn chains(this: &StandardWalletAccount) -> js_sys::Array;
+    }
+}
+
+/// Describes a wallet discovered through the wallet standard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StandardWalletInfo {
+    pub name: String,
+    pub icon: String,
+}
+
+impl From<&StandardWallet> for StandardWalletInfo {
+    fn from(wallet: &StandardWallet) -> Self {
+        Self {
+            name: wallet.name(),
+            icon: wallet.icon(),
+        }
+    }
+}
+
+/// The wallets registered so far and the subscribers waiting for new ones.
+struct Registry {
+    wallets: Vec<StandardWallet>,
+    subscribers: Vec<UnboundedSender<StandardWalletInfo>>,
+    _register: Closure<dyn FnMut(JsValue)>,
+    _listener: EventListener,
+}
+
+thread_local! {
+    static REGISTRY: RefCell<Option<Registry>> = const { RefCell::new(None) };
+}
+
+/// Registers a wallet, ignoring it if it does not support the solana features we need.
+fn register(wallet: JsValue) {
+    let wallet = wallet.unchecked_into::<StandardWallet>();
+    if !is_solana_wallet(&wallet) {
+        return;
+    }
+    let info = StandardWalletInfo::from(&wallet);
+    REGISTRY.with(|registry| {
+        if let Some(registry) = registry.borrow_mut().as_mut() {
+            if registry.wallets.iter().any(|w| w.name() == info.name) {
+                return;
+            }
+            log::info!("Registered standard wallet: {}", info.name);
+            registry.wallets.push(wallet);
+            registry
+                .subscribers
+                .retain(|s| s.unbounded_send(info.clone()).is_ok());
+        }
+    });
+}
+
+fn is_solana_wallet(wallet: &StandardWallet) -> bool {
+    let has_solana_chain = wallet
+        .chains()
+        .iter()
+        .any(|c| c.as_string().map_or(false, |c| c.starts_with("solana:")));
+    has_solana_chain
+        && has_feature(wallet, STANDARD_CONNECT)
+        && (has_feature(wallet, SOLANA_SIGN_TRANSACTION)
+            || has_feature(wallet, SOLANA_SIGN_AND_SEND_TRANSACTION))
+}
+
+/// Starts listening for wallet registrations, once.
+///
+/// ## Standard
+/// This implements the app side of the wallet standard registration protocol.
+///
+/// Read: https://github.com/wallet-standard/wallet-standard/blob/master/packages/core/app/src/wallets.ts
+fn init() {
+    if REGISTRY.with(|r| r.borrow().is_some()) {
+        return;
+    }
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+
+    let register = Closure::<dyn FnMut(JsValue)>::new(register);
+    let api = js_sys::Object::new();
+    if js_sys::Reflect::set(&api, &"register".into(), register.as_ref()).is_err() {
+        return;
+    }
+    let api: JsValue = api.into();
+
+    let api_c = api.clone();
+    let listener = EventListener::new(&window, REGISTER_WALLET_EVENT, move |e| {
+        let callback = e
+            .dyn_ref::<web_sys::CustomEvent>()
+            .and_then(|e| e.detail().dyn_into::<js_sys::Function>().ok());
+        if let Some(callback) = callback {
+            if let Err(e) = callback.call1(&JsValue::UNDEFINED, &api_c) {
+                log::error!("Error registering standard wallet. {:?}", e.as_string());
+            }
+        }
+    });
+
+    REGISTRY.with(|r| {
+        *r.borrow_mut() = Some(Registry {
+            wallets: vec![],
+            subscribers: vec![],
+            _register: register,
+            _listener: listener,
+        })
+    });
+
+    // Wallets that were injected before the app are waiting for this to register
+    let mut init = web_sys::CustomEventInit::new();
+    init.detail(&api);
+    match web_sys::CustomEvent::new_with_event_init_dict(APP_READY_EVENT, &init) {
+        Ok(event) => {
+            let _ = window.dispatch_event(&event);
+        }
+        Err(e) => {
+            log::error!("Error dispatching app ready event. {:?}", e.as_string());
+        }
+    }
+}
+
+/// Returns the wallets registered so far.
+pub fn standard_wallets() -> Vec<StandardWalletInfo> {
+    init();
+    REGISTRY.with(|r| {
+        r.borrow().as_ref().map_or(vec![], |registry| {
+            registry
+                .wallets
+                .iter()
+                .map(StandardWalletInfo::from)
+                .collect()
+        })
+    })
+}
+
+/// Subscribes to wallet registrations.
+///
+/// Wallets that registered before the call are replayed to the new subscriber.
+pub fn subscribe_standard_wallets() -> UnboundedReceiver<StandardWalletInfo> {
+    init();
+    let (sender, receiver) = unbounded();
+    REGISTRY.with(|r| {
+        if let Some(registry) = r.borrow_mut().as_mut() {
+            for wallet in &registry.wallets {
+                let _ = sender.unbounded_send(StandardWalletInfo::from(wallet));
+            }
+            registry.subscribers.push(sender);
+        }
+    });
+    receiver
+}
+
+pub(crate) fn find_standard_wallet(name: &str) -> Option<StandardWallet> {
+    REGISTRY.with(|r| {
+        r.borrow()
+            .as_ref()
+            .and_then(|registry| registry.wallets.iter().find(|w| w.name() == name).cloned())
+    })
+}
+
+fn feature(wallet: &StandardWallet, name: &str) -> Option<JsValue> {
+    js_sys::Reflect::get(&wallet.features(), &name.into())
+        .ok()
+        .filter(|f| f.is_object())
+}
+
+fn has_feature(wallet: &StandardWallet, name: &str) -> bool {
+    feature(wallet, name).is_some()
+}
+
+/// The transaction versions listed by the signing features, legacy only when they list none.
+fn supported_transaction_versions(wallet: &StandardWallet) -> Vec<TransactionVersion> {
+    let Some(feature) = feature(wallet, SOLANA_SIGN_TRANSACTION)
+        .or_else(|| feature(wallet, SOLANA_SIGN_AND_SEND_TRANSACTION))
+    else {
+        return vec![];
+    };
+    let Some(versions) = js_sys::Reflect::get(&feature, &"supportedTransactionVersions".into())
+        .ok()
+        .and_then(|v| v.dyn_into::<js_sys::Array>().ok())
+    else {
+        return vec![TransactionVersion::LEGACY];
+    };
+    versions
+        .iter()
+        .filter_map(|v| match v.as_string() {
+            Some(v) if v == "legacy" => Some(TransactionVersion::LEGACY),
+            _ => v.as_f64().map(|n| TransactionVersion::Number(n as u8)),
+        })
+        .collect()
+}
+
+/// Invokes `wallet.features[feature][method](...args)` and awaits the result.
+async fn call_feature(
+    wallet: &StandardWallet,
+    feature_name: &str,
+    method: &str,
+    args: &js_sys::Array,
+) -> Result<JsValue, JsValue> {
+    let feature = feature(wallet, feature_name)
+        .ok_or_else(|| JsValue::from_str(&format!("{} is not supported", feature_name)))?;
+    let function =
+        js_sys::Reflect::get(&feature, &method.into())?.dyn_into::<js_sys::Function>()?;
+    let result = function.apply(&feature, args)?;
+    wasm_bindgen_futures::JsFuture::from(js_sys::Promise::resolve(&result)).await
+}
+
+/// Builds a feature input object from the given key-value pairs.
+fn to_js_input(pairs: &[(&str, JsValue)]) -> JsValue {
+    let object = js_sys::Object::new();
+    for (key, value) in pairs {
+        let _ = js_sys::Reflect::set(&object, &(*key).into(), value);
+    }
+    object.into()
+}
+
+/// Reads the `key` byte array of every output of a feature method.
+fn from_js_outputs(value: &JsValue, key: &str) -> Option<Vec<Vec<u8>>> {
+    value
+        .dyn_ref::<js_sys::Array>()?
+        .iter()
+        .map(|output| {
+            js_sys::Reflect::get(&output, &key.into())
+                .ok()?
+                .dyn_into::<js_sys::Uint8Array>()
+                .ok()
+                .map(|b| b.to_vec())
+        })
+        .collect()
+}
+
+fn to_pubkey(account: &StandardWalletAccount) -> Option<Pubkey> {
+    Pubkey::from_str(&account.address()).ok()
+}
+
+fn first_account(accounts: &js_sys::Array) -> Option<StandardWalletAccount> {
+    let account = accounts.get(0);
+    if account.is_undefined() {
+        None
+    } else {
+        Some(account.unchecked_into())
+    }
+}
+
+/// A wallet adapter for any wallet that registers through the wallet standard.
+pub struct StandardWalletAdapter {
+    wallet: StandardWallet,
+    info: StandardWalletInfo,
+    connecting: Mutex<bool>,
+    /// The chain of the cluster the adapter was connected for, transactions are signed for it.
+    chain: Mutex<&'static str>,
+    /// Shared with the `change` listener, which updates it when the wallet switches accounts.
+    account: Arc<Mutex<Option<StandardWalletAccount>>>,
+    wel: Mutex<WalletEventListener>,
+    /// Shared with `wel`, so the adapter can emit the events the standard has no callback for.
+    bus: EventBus,
+    /// The `change` listener of the active session.
+    change_listener: Mutex<Option<ChangeListener>>,
+    listener_status: Mutex<ListenerStatus>,
+}
+
+/// A listener registered on `standard:events`.
+struct ChangeListener {
+    listener: Closure<dyn FnMut(JsValue)>,
+    /// Cleared once the listener outlives its session, it then ignores the changes.
+    active: Rc<Cell<bool>>,
+    /// Returned by `on`, removes the listener.
+    off: Option<js_sys::Function>,
+}
+
+impl ChangeListener {
+    /// Removes the listener, muting it and leaving it to the wallet when it cannot be removed.
+    fn remove(self) -> ListenerStatus {
+        if let Some(off) = &self.off {
+            match off.call0(&JsValue::UNDEFINED) {
+                Ok(_) => return ListenerStatus::Removed,
+                Err(e) => log::error!(
+                    "Error unsubscribing from wallet changes. {:?}",
+                    e.as_string()
+                ),
+            }
+        }
+        self.active.set(false);
+        self.listener.forget();
+        ListenerStatus::Retained
+    }
+}
+
+impl std::fmt::Debug for StandardWalletAdapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StandardWalletAdapter")
+            .field("info", &self.info)
+            .field("connecting", &self.connecting)
+            .field("chain", &self.chain)
+            .field("account", &format!("{:?}", self.pubkey()))
+            .field("wel", &self.wel)
+            .field("listener_status", &self.listener_status())
+            .finish()
+    }
+}
+
+impl StandardWalletAdapter {
+    /// Creates an adapter for the registered wallet with the given name.
+    pub fn new(name: &str) -> Option<Self> {
+        find_standard_wallet(name).map(Self::from_wallet)
+    }
+
+    fn from_wallet(wallet: StandardWallet) -> Self {
+        let wel = WalletEventListener::unbound();
+        let account = Arc::new(Mutex::new(first_account(&wallet.accounts())));
+
+        let adapter = Self {
+            info: StandardWalletInfo::from(&wallet),
+            wallet,
+            connecting: false.into(),
+            chain: Mutex::new(DEFAULT_CHAIN),
+            account,
+            bus: wel.bus.clone(),
+            wel: Mutex::new(wel),
+            change_listener: Mutex::new(None),
+            listener_status: Mutex::new(ListenerStatus::Unbound),
+        };
+        // The wallet still authorizes the app from a previous visit, the session is already active
+        if adapter.is_connected() {
+            adapter.listen_for_changes();
+        }
+        adapter
+    }
+
+    /// Subscribes to `standard:events` for the session, emitting connect, account changed and
+    /// disconnect events when the wallet's authorized accounts change.
+    fn listen_for_changes(&self) {
+        self.stop_listening();
+        let Some(events) = feature(&self.wallet, STANDARD_EVENTS) else {
+            return;
+        };
+        let Some(on) = js_sys::Reflect::get(&events, &"on".into())
+            .ok()
+            .and_then(|on| on.dyn_into::<js_sys::Function>().ok())
+        else {
+            return;
+        };
+
+        let active = Rc::new(Cell::new(true));
+        let (account, bus) = (self.account.clone(), self.bus.clone());
+        let listener_active = active.clone();
+        let listener = Closure::<dyn FnMut(JsValue)>::new(move |properties: JsValue| {
+            if !listener_active.get() {
+                return;
+            }
+            let Ok(accounts) = js_sys::Reflect::get(&properties, &"accounts".into()) else {
+                return;
+            };
+            let Some(accounts) = accounts.dyn_ref::<js_sys::Array>() else {
+                return;
+            };
+            let new_account = first_account(accounts);
+            let new_pubkey = new_account.as_ref().and_then(to_pubkey);
+            let mut account = account.lock().unwrap();
+            let old_pubkey = account.as_ref().and_then(to_pubkey);
+            if old_pubkey == new_pubkey {
+                return;
+            }
+            *account = new_account;
+            let event = match (old_pubkey, new_pubkey) {
+                (None, Some(pubkey)) => Event::Connect(pubkey),
+                (Some(_), Some(pubkey)) => Event::AccountChanged(Some(pubkey)),
+                (_, None) => Event::Disconnect,
+            };
+            bus.emit(event);
+        });
+
+        match on.call2(&events, &"change".into(), listener.as_ref()) {
+            Ok(off) => {
+                *self.change_listener.lock().unwrap() = Some(ChangeListener {
+                    listener,
+                    active,
+                    off: off.dyn_into().ok(),
+                });
+                *self.listener_status.lock().unwrap() = ListenerStatus::Bound;
+            }
+            Err(e) => log::error!("Error subscribing to wallet changes. {:?}", e.as_string()),
+        }
+    }
+
+    /// Removes the `change` listener once the session is over.
+    fn stop_listening(&self) -> ListenerStatus {
+        let Some(change_listener) = self.change_listener.lock().unwrap().take() else {
+            return self.listener_status();
+        };
+        let status = change_listener.remove();
+        *self.listener_status.lock().unwrap() = status;
+        status
+    }
+
+    fn account(&self) -> Result<StandardWalletAccount, WalletError> {
+        self.account
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or(WalletError::NotConnected)
+    }
+
+    /// The chain transactions are signed for, the one of the connected cluster.
+    fn chain(&self, account: &StandardWalletAccount) -> Result<JsValue, WalletError> {
+        let chain = *self.chain.lock().unwrap();
+        if account
+            .chains()
+            .iter()
+            .any(|c| c.as_string().map_or(false, |c| c == chain))
+        {
+            Ok(JsValue::from_str(chain))
+        } else {
+            log::error!("The {} account does not support {}.", self.info.name, chain);
+            Err(WalletError::Config)
+        }
+    }
+
+    fn emit(&self, event: Event) {
+        self.bus.emit(event);
+    }
+
+    /// Signs the given transactions with `solana:signTransaction`, in a single request.
+    async fn sign_transactions(
+        &self,
+        transactions: &[WalletTransaction],
+    ) -> Result<Vec<WalletTransaction>, WalletError> {
+        let account = self.account()?;
+        let inputs = js_sys::Array::new();
+        for transaction in transactions {
+            let bytes = js_sys::Uint8Array::from(transaction.serialize()?.as_slice());
+            inputs.push(&to_js_input(&[
+                ("account", account.clone().into()),
+                ("transaction", bytes.into()),
+                ("chain", self.chain(&account)?),
+            ]));
+        }
+
+        let outputs = call_feature(
+            &self.wallet,
+            SOLANA_SIGN_TRANSACTION,
+            "signTransaction",
+            &inputs,
+        )
+        .await
+        .map_err(|e| {
+            let error = WalletError::from_js(&e, WalletError::SignTransaction);
+            log::error!("Error invoking signTransaction. {}", error);
+            error
+        })?;
+        let signed = from_js_outputs(&outputs, "signedTransaction")
+            .filter(|s| s.len() == transactions.len())
+            .ok_or(WalletError::SignTransaction)?;
+
+        transactions
+            .iter()
+            .zip(signed)
+            .map(|(transaction, bytes)| transaction.deserialize_as(&bytes))
+            .collect()
+    }
+}
+
+impl WalletAdapterMetadata for StandardWalletAdapter {
+    fn name(&self) -> &str {
+        &self.info.name
+    }
+
+    fn url(&self) -> &str {
+        ""
+    }
+
+    fn icon(&self) -> &str {
+        &self.info.icon
+    }
+}
+
+#[async_trait(?Send)]
+impl WalletAdapterBase for StandardWalletAdapter {
+    fn is_name(&self) -> bool {
+        true
+    }
+
+    fn is_connected(&self) -> bool {
+        self.account.lock().unwrap().is_some()
+    }
+
+    fn ready_state(&self) -> WalletReadyState {
+        WalletReadyState::Installed
+    }
+
+    fn pubkey(&self) -> Option<Pubkey> {
+        self.account.lock().unwrap().as_ref().and_then(to_pubkey)
+    }
+
+    fn connecting(&self) -> bool {
+        *self.connecting.lock().unwrap()
+    }
+
+    async fn connect(&self, options: ConnectOptions) -> Result<Pubkey, WalletError> {
+        *self.connecting.lock().unwrap() = true;
+        if let Some(endpoint) = &options.rpc_endpoint {
+            *self.chain.lock().unwrap() = chain_of_endpoint(endpoint);
+        }
+        // The standard names a connect that must not prompt the user a `silent` connect
+        let input = to_js_input(&[("silent", options.only_if_trusted.into())]);
+        let result = with_timeout(
+            call_feature(
+                &self.wallet,
+                STANDARD_CONNECT,
+                "connect",
+                &js_sys::Array::of1(&input),
+            ),
+            options.timeout,
+        )
+        .await
+        .and_then(|r| r.map_err(|e| WalletError::from_js(&e, WalletError::Connection)))
+        .and_then(|r| {
+            let account = js_sys::Reflect::get(&r, &"accounts".into())
+                .ok()
+                .and_then(|a| a.dyn_into::<js_sys::Array>().ok())
+                .and_then(|a| first_account(&a));
+            let pubkey = account
+                .as_ref()
+                .and_then(to_pubkey)
+                .ok_or(WalletError::Account)?;
+            *self.account.lock().unwrap() = account;
+            Ok(pubkey)
+        });
+        *self.connecting.lock().unwrap() = false;
+
+        match &result {
+            Ok(pubkey) => {
+                log::info!("Successfully invoked connect. {:?}", pubkey);
+                // Bound once connected, the wallet reports the connection itself as a change
+                self.listen_for_changes();
+                self.emit(Event::Connect(*pubkey));
+            }
+            Err(e) => log::error!("Error invoking connect. {}", e),
+        }
+        result
+    }
+
+    async fn disconnect(&self) {
+        // Removed first, the wallet reports the disconnection itself as a change
+        let status = self.stop_listening();
+        log::info!("Unbound {} change listener. {:?}", self.info.name, status);
+        if has_feature(&self.wallet, STANDARD_DISCONNECT) {
+            if let Err(e) = call_feature(
+                &self.wallet,
+                STANDARD_DISCONNECT,
+                "disconnect",
+                &js_sys::Array::new(),
+            )
+            .await
+            {
+                log::error!("Error invoking disconnect. {:?}", e.as_string());
+            }
+        }
+        *self.account.lock().unwrap() = None;
+        self.emit(Event::Disconnect);
+    }
+
+    async fn sign_transaction(
+        &self,
+        transaction: WalletTransaction,
+    ) -> Result<WalletTransaction, WalletError> {
+        self.sign_transactions(&[transaction])
+            .await?
+            .pop()
+            .ok_or(WalletError::SignTransaction)
+    }
+
+    async fn sign_all_transactions(
+        &self,
+        transactions: Vec<WalletTransaction>,
+    ) -> Result<Vec<WalletTransaction>, WalletError> {
+        self.sign_transactions(&transactions).await
+    }
+
+    async fn sign_and_send_transaction(
+        &self,
+        transaction: WalletTransaction,
+    ) -> Result<Signature, WalletError> {
+        let account = self.account()?;
+        let bytes = js_sys::Uint8Array::from(transaction.serialize()?.as_slice());
+        let input = to_js_input(&[
+            ("account", account.clone().into()),
+            ("transaction", bytes.into()),
+            ("chain", self.chain(&account)?),
+        ]);
+
+        let outputs = call_feature(
+            &self.wallet,
+            SOLANA_SIGN_AND_SEND_TRANSACTION,
+            "signAndSendTransaction",
+            &js_sys::Array::of1(&input),
+        )
+        .await
+        .map_err(|e| {
+            let error = WalletError::from_js(&e, WalletError::SendTransaction);
+            log::error!("Error invoking signAndSendTransaction. {}", error);
+            error
+        })?;
+
+        from_js_outputs(&outputs, "signature")
+            .and_then(|mut s| s.pop())
+            .and_then(|s| Signature::try_from(s).ok())
+            .ok_or(WalletError::SendTransaction)
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature, WalletError> {
+        let account = self.account()?;
+        let input = to_js_input(&[
+            ("account", account.into()),
+            ("message", js_sys::Uint8Array::from(message).into()),
+        ]);
+
+        let outputs = call_feature(
+            &self.wallet,
+            SOLANA_SIGN_MESSAGE,
+            "signMessage",
+            &js_sys::Array::of1(&input),
+        )
+        .await
+        .map_err(|e| {
+            let error = WalletError::from_js(&e, WalletError::SignMessage);
+            log::error!("Error invoking signMessage. {}", error);
+            error
+        })?;
+
+        from_js_outputs(&outputs, "signature")
+            .and_then(|mut s| s.pop())
+            .and_then(|s| Signature::try_from(s).ok())
+            .ok_or(WalletError::SignMessage)
+    }
+
+    async fn sign_in(&self, input: SignInInput) -> Result<SignInOutput, WalletError> {
+        if !has_feature(&self.wallet, SOLANA_SIGN_IN) {
+            return sign_in_with_message(self, &input).await;
+        }
+
+        let outputs = call_feature(
+            &self.wallet,
+            SOLANA_SIGN_IN,
+            "signIn",
+            &js_sys::Array::of1(&to_js_sign_in_input(&input)?),
+        )
+        .await
+        .map_err(|e| {
+            let error = WalletError::from_js(&e, WalletError::SignIn);
+            log::error!("Error invoking signIn. {}", error);
+            error
+        })?;
+        let output = outputs
+            .dyn_ref::<js_sys::Array>()
+            .map(|o| o.get(0))
+            .ok_or(WalletError::SignIn)?;
+        let output = from_js_sign_in_output(output)?;
+
+        *self.account.lock().unwrap() = first_account(&self.wallet.accounts());
+        self.listen_for_changes();
+        self.emit(Event::Connect(output.account));
+        Ok(output)
+    }
+}
+
+#[async_trait(?Send)]
+impl WalletAdapter for StandardWalletAdapter {
+    fn priority_fees_supported(&self) -> bool {
+        false
+    }
+
+    fn capabilities(&self) -> WalletCapabilities {
+        let sign_message = has_feature(&self.wallet, SOLANA_SIGN_MESSAGE);
+        WalletCapabilities {
+            supported_transaction_versions: supported_transaction_versions(&self.wallet),
+            sign_message,
+            sign_in: sign_message || has_feature(&self.wallet, SOLANA_SIGN_IN),
+            sign_and_send_transaction: has_feature(&self.wallet, SOLANA_SIGN_AND_SEND_TRANSACTION),
+            // `signTransaction` takes any number of inputs
+            sign_all_transactions: has_feature(&self.wallet, SOLANA_SIGN_TRANSACTION),
+        }
+    }
+
+    fn subscribe(&self) -> EventSubscription {
+        self.wel.lock().unwrap().subscribe()
+    }
+
+    fn listener_status(&self) -> ListenerStatus {
+        *self.listener_status.lock().unwrap()
+    }
+}
+
+impl Drop for StandardWalletAdapter {
+    fn drop(&mut self) {
+        self.stop_listening();
+    }
+}
+
+impl From<StandardWalletInfo> for Wallet {
+    fn from(value: StandardWalletInfo) -> Self {
+        Wallet::Standard(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_endpoints_to_chains() {
+        assert_eq!(
+            chain_of_endpoint("https://api.devnet.solana.com"),
+            "solana:devnet"
+        );
+        assert_eq!(
+            chain_of_endpoint("https://api.testnet.solana.com"),
+            "solana:testnet"
+        );
+        assert_eq!(
+            chain_of_endpoint("http://localhost:8899"),
+            "solana:localnet"
+        );
+        assert_eq!(
+            chain_of_endpoint("http://127.0.0.1:8899"),
+            "solana:localnet"
+        );
+        assert_eq!(
+            chain_of_endpoint("https://api.mainnet-beta.solana.com"),
+            "solana:mainnet"
+        );
+    }
+}
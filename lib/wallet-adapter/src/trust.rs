@@ -0,0 +1,14 @@
+use crate::injected::{InjectedProvider, InjectedWalletAdapter};
+
+#[derive(Debug)]
+pub struct TrustProvider;
+
+impl InjectedProvider for TrustProvider {
+    const NAME: &'static str = "Trust";
+    const URL: &'static str = "https://trustwallet.com/";
+    const ICON: &'static str = "data:image/svg+xml;base64,PHN2ZyB3aWR0aD0iNjQiIGhlaWdodD0iNjQiIHZpZXdCb3g9IjAgMCA2NCA2NCIgZmlsbD0ibm9uZSIgeG1sbnM9Imh0dHA6Ly93d3cudzMub3JnLzIwMDAvc3ZnIj4KPHBhdGggZD0iTTMyIDY0QzQ5LjY3MzEgNjQgNjQgNDkuNjczMSA2NCAzMkM2NCAxNC4zMjY5IDQ5LjY3MzEgMCAzMiAwQzE0LjMyNjkgMCAwIDE0LjMyNjkgMCAzMkMwIDQ5LjY3MzEgMTQuMzI2OSA2NCAzMiA2NFoiIGZpbGw9IiMwNTAwRkYiLz4KPHBhdGggZD0iTTMyLjQ0NjggMTQuNzQ4OUMzOC41Nzk0IDE5Ljg3MDUgNDUuNjExMSAxOS41NTQ3IDQ3LjYxOTkgMTkuNTU0N0M0Ny4xODA2IDQ4LjY4MDcgNDMuODMxNCA0Mi45MDQ3IDMyLjQ0NjggNTEuMDcxNUMyMS4wNjIxIDQyLjkwNDcgMTcuNzMzOCA0OC42ODA3IDE3LjI5NDEgMTkuNTU0N0MxOS4yODE5IDE5LjU1NDcgMjYuMzE0NiAxOS44NzA1IDMyLjQ0NjggMTQuNzQ4OVoiIHN0cm9rZT0id2hpdGUiIHN0cm9rZS13aWR0aD0iNC4wMzQwMyIgc3Ryb2tlLW1pdGVybGltaXQ9IjEwIiBzdHJva2UtbGluZWpvaW49InJvdW5kIi8+Cjwvc3ZnPgo=";
+    const PATH: &'static [&'static str] = &["trustwallet", "solana"];
+    const FLAG: Option<&'static str> = Some("isTrust");
+}
+
+pub type TrustWalletAdapter = InjectedWalletAdapter<TrustProvider>;
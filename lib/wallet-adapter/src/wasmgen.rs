@@ -9,4 +9,40 @@ extern "C" {
 
     #[wasm_bindgen(method, js_name = toBuffer)]
     pub fn to_bytes(this: &JsPublicKey) -> Vec<u8>;
+
+    /// A web3.js `Transaction` or `VersionedTransaction` handed back by a wallet provider.
+    pub type JsTransaction;
+
+    #[wasm_bindgen(method, js_name = serialize)]
+    pub fn serialize(this: &JsTransaction, config: &JsValue) -> Vec<u8>;
+}
+
+/// A minimal stand-in for a web3.js `Transaction` or `VersionedTransaction`.
+///
+/// Injected providers tell versioned transactions apart by the presence of a `version`
+/// property and call `serialize()` on the transactions they are given, so a plain object
+/// exposing the wire format is enough. Legacy transactions must not carry `version` at all.
+#[wasm_bindgen]
+extern "C" {
+    #[derive(Debug, Clone)]
+    pub type WasmTransaction;
+}
+
+impl WasmTransaction {
+    /// Wraps the serialized transaction, `version` is `None` for legacy transactions.
+    pub fn new(bytes: Vec<u8>, version: Option<u8>) -> Self {
+        let transaction = js_sys::Object::new();
+        let serialize = Closure::<dyn Fn() -> js_sys::Uint8Array>::new(move || {
+            js_sys::Uint8Array::from(bytes.as_slice())
+        });
+        let _ = js_sys::Reflect::set(
+            &transaction,
+            &"serialize".into(),
+            &serialize.into_js_value(),
+        );
+        if let Some(version) = version {
+            let _ = js_sys::Reflect::set(&transaction, &"version".into(), &version.into());
+        }
+        transaction.unchecked_into()
+    }
 }
@@ -0,0 +1,18 @@
+use std::{path::Path, process::Command};
+
+/// Runs the tests of the popup stand-in page, which need node.
+#[test]
+fn popup_stand_in_page() {
+    let tests = Path::new(env!("CARGO_MANIFEST_DIR")).join("popup/stand-in.test.mjs");
+    let status = match Command::new("node").arg("--test").arg(&tests).status() {
+        Ok(status) => status,
+        Err(e) => {
+            eprintln!(
+                "Skipping the popup stand-in tests, node is not available. {}",
+                e
+            );
+            return;
+        }
+    };
+    assert!(status.success(), "popup stand-in tests failed");
+}
@@ -1,10 +1,11 @@
 use anchor_lang::prelude::Pubkey;
-use dioxus::prelude::*;
+use dioxus::{html::input_data::keyboard_types::Key, prelude::*};
 use dioxus_free_icons::prelude::Icon;
 use dioxus_router::prelude::*;
 use gloo_events::EventListener;
 use gloo_storage::{LocalStorage, Storage};
 use solana_wallet_adapter::{prelude::*, IntoWalletIcon};
+use solana_wallet_adapter_base::{ConnectOptions, WATCH_WALLET_ICON, WATCH_WALLET_NAME};
 use solana_wallet_adapter_dioxus::{use_local_storage, use_wallet, WalletContextState};
 use std::str::FromStr;
 
@@ -20,17 +21,21 @@ pub fn ConnectButton() -> Element {
     let mut show_wallet_popover = use_signal(|| false);
     let mut show_wallet_select_dropdown = use_signal(|| false);
 
+    let wallet_ctx = use_wallet();
     let WalletContextState {
         wallets,
         connected,
+        connecting,
         wallet,
         pubkey,
+        mut watch_address,
         ..
-    } = use_wallet();
+    } = wallet_ctx;
     let mut wallet_name: Signal<Option<String>> = use_context();
     let mut user_ctx = use_context::<Signal<User>>();
     let cluster_ctx = use_context::<Signal<Cluster>>();
     let event_listeners = use_signal(|| vec![]);
+    let mut watch_input = use_signal(|| String::new());
 
     let endpoint =
         use_local_storage::<String>("rpc_url".to_string(), Some(DEFAULT_RPC_URL.to_string()));
@@ -46,6 +51,10 @@ pub fn ConnectButton() -> Element {
     // Wallet connect flow and set popover state
     let handle_click = move |_| {
         show_wallet_select_dropdown.set(false);
+        if connecting() {
+            log::info!("Wallet connection already in progress..");
+            return;
+        }
 
         spawn(async move {
             log::info!("Wallet Name: {:?} ", wallet_name());
@@ -94,7 +103,23 @@ pub fn ConnectButton() -> Element {
                     log::info!(
                         "Wallet not connected. Attempting to connect with wallet provider.."
                     );
-                    wallet.connect(Some(&endpoint())).await;
+                    match wallet_ctx
+                        .connect(ConnectOptions::new(Some(&endpoint())))
+                        .await
+                    {
+                        Ok(pubkey) => {
+                            user_ctx.set(User::Wallet {
+                                pubkey: Some(pubkey),
+                                lamports: 0,
+                            });
+                        }
+                        Err(e) if e.is_user_rejected() => {
+                            log::info!("Connection request rejected by the user.");
+                        }
+                        Err(e) => {
+                            log::error!("Failed to connect wallet: {}", e);
+                        }
+                    }
                 }
             } else {
                 log::info!("Wallet not selected!");
@@ -102,6 +127,52 @@ pub fn ConnectButton() -> Element {
         });
     };
 
+    // Watch an address with the read-only wallet
+    let mut handle_watch_click = move || {
+        let input = watch_input.peek().trim().to_string();
+        match Pubkey::from_str(&input) {
+            Ok(address) => {
+                log::info!("Watching address: {}", address);
+                watch_address.set(Some(address.to_string()));
+                wallet_name.set(Some(WATCH_WALLET_NAME.to_string()));
+                user_ctx.set(User::Wallet {
+                    pubkey: Some(address),
+                    lamports: 0,
+                });
+                watch_input.set(String::new());
+                show_wallet_select_dropdown.set(false);
+            }
+            Err(_) => {
+                // TODO Display "invalid address" error to user
+                log::info!("Invalid watch address");
+            }
+        }
+    };
+
+    // Follow account changes made inside the wallet
+    use_effect(move || {
+        if !connected() {
+            return;
+        }
+        let Some(pubkey) = pubkey() else {
+            return;
+        };
+        if let User::Wallet {
+            pubkey: Some(user_pubkey),
+            ..
+        } = &*user_ctx.peek()
+        {
+            if *user_pubkey == pubkey {
+                return;
+            }
+        }
+        log::info!("Wallet account changed: {}", pubkey);
+        user_ctx.set(User::Wallet {
+            pubkey: Some(pubkey),
+            lamports: 0,
+        });
+    });
+
     // Wallet disconnect
     let handle_disconnect_click = {
         move |_| {
@@ -109,7 +180,7 @@ pub fn ConnectButton() -> Element {
                 log::info!("Attempting to disconnect with wallet provider..");
                 log::info!("WalletName: {:?} ", wallet_name());
 
-                if let Some(wallet) = wallet() {
+                if wallet().is_some() {
                     // If the wallet is already connected
                     if connected() {
                         // Disable display of popover and dropdown
@@ -118,7 +189,7 @@ pub fn ConnectButton() -> Element {
                         // Reset the user context
                         user_ctx.set(User::Unknown);
                         //wallet_connected.set(false);
-                        wallet.disconnect().await;
+                        wallet_ctx.disconnect().await;
                     } else {
                         log::info!("Wallet not connected..");
                     }
@@ -129,7 +200,6 @@ pub fn ConnectButton() -> Element {
         }
     };
 
-    let wallets_c = wallets.clone();
     use_effect(move || {
         let mut event_listeners = event_listeners.clone();
 
@@ -140,7 +210,7 @@ pub fn ConnectButton() -> Element {
             }
         } else {
             // Add dynamic button display event listeners
-            let wallets_iter = wallets_c.clone();
+            let wallets_iter = wallets();
             let document = gloo_utils::document();
 
             for wallet in wallets_iter {
@@ -165,6 +235,8 @@ pub fn ConnectButton() -> Element {
         } else {
             String::from("Connect")
         }
+    } else if connecting() {
+        String::from("Connecting..")
     } else if let Some(_) = wallet_name() {
         String::from("Connect")
     } else {
@@ -304,7 +376,7 @@ pub fn ConnectButton() -> Element {
                 class: "z-50 dark:bg-gray-900 absolute top-[40px] lg:top-[46px] right-[0px] flex px-2 lg:px-3 items-center shadow-xl rounded-lg transition-all",
                 ul {
                     class: "z-50 px-2 lg:px-3 py-2 lg:py-3 origin-top-right items-center overflow-hidden rounded-lg shadow-lg ring-1 ring-black ring-opacity-5 focus:outline-none",
-                    for wallet in wallets.iter() {
+                    for wallet in wallets.read().iter() {
                         button {
                             class: "cursor-pointer flex items-center h-8 m-1 px-1 py-1 sm:px-2 sm:py-2 sm:px-3 sm:py-3 z-100 w-full border rounded-full transition text-slate-100 hover:bg-slate-800 active:bg-slate-100 active:text-slate-900",
                             id: "{wallet.into_wallet_name()}",
@@ -323,6 +395,31 @@ pub fn ConnectButton() -> Element {
                             }
                         }
                     }
+                    div {
+                        class: "flex flex-row items-center h-8 m-1 space-x-1",
+                        input {
+                            class: "h-8 w-full px-3 font-mono text-sm rounded-full bg-transparent border text-slate-100 focus:ring-0 focus:outline-0",
+                            r#type: "text",
+                            placeholder: "Watch address",
+                            value: "{watch_input}",
+                            oninput: move |e| watch_input.set(e.data.value()),
+                            onkeydown: move |e| {
+                                if e.key() == Key::Enter {
+                                    handle_watch_click();
+                                }
+                            },
+                        }
+                        button {
+                            class: "cursor-pointer flex items-center h-8 px-3 border rounded-full transition text-slate-100 hover:bg-slate-800 active:bg-slate-100 active:text-slate-900",
+                            onclick: move |_| handle_watch_click(),
+                            img {
+                                class: "rounded",
+                                src: WATCH_WALLET_ICON,
+                                height: 20,
+                                width: 20,
+                            }
+                        }
+                    }
                 }
             }
         }
@@ -1,12 +1,15 @@
 use dioxus::prelude::*;
 use dioxus_free_icons::prelude::*;
-use solana_wallet_adapter_dioxus::use_connection;
+use solana_wallet_adapter_dioxus::{use_connection_state, use_endpoints, use_slot};
 
 /// The application footer.
 #[component]
 pub fn Footer() -> Element {
-    let mut solana_slot = use_signal(|| 0);
+    let solana_slot = use_slot();
     let mut solana_tps = use_signal(|| 0.0);
+    // Read on every iteration, the connection changes when failing over to another endpoint
+    let connection_state = use_connection_state();
+    let endpoints = use_endpoints();
 
     use_future(move || async move {
         loop {
@@ -18,48 +21,56 @@ pub fn Footer() -> Element {
     });
 
     use_future(move || async move {
-        let connection = use_connection();
         loop {
-            let recent_perf = match connection.client.get_recent_performance_samples(None).await {
-                Ok(r) => r,
+            let connection = connection_state.peek().connection.clone();
+            match connection.rpc().get_recent_performance_samples(None).await {
+                Ok(recent_perf) => {
+                    if let Some(perf_sample) = recent_perf.iter().max_by_key(|r| r.slot) {
+                        log::info!("{:?}", perf_sample);
+                        solana_tps.set(
+                            perf_sample.num_transactions as f64
+                                / perf_sample.sample_period_secs as f64,
+                        );
+                    }
+                }
                 Err(e) => {
                     log::error!("Failed to fetch recent performance samples. {:?}", e);
-                    continue;
                 }
             };
 
-            if let Some(perf_sample) = recent_perf.iter().max_by_key(|r| r.slot) {
-                log::info!("{:?}", perf_sample);
-                solana_tps.set(
-                    perf_sample.num_transactions as f64 / perf_sample.sample_period_secs as f64,
-                );
-            }
-
             gloo_timers::future::TimeoutFuture::new(5_000).await;
         }
     });
 
-    use_future(move || async move {
-        let connection = use_connection();
-        loop {
-            match connection.client.get_slot().await {
-                Ok(r) => {
-                    solana_slot.set(r);
-                }
-                Err(e) => {
-                    log::error!("Failed to fetch latest slot");
-                    continue;
-                }
-            };
-
-            log::info!("{:?}", solana_slot.peek());
-
-            gloo_timers::future::TimeoutFuture::new(1_000).await;
-        }
-    });
-
     let solana_tps_text = format!("{:.2}", solana_tps());
-    let solana_slot_text = format!("{}", solana_slot());
+    let solana_slot_text = format!("{}", solana_slot().unwrap_or_default());
+    let current_endpoint = (endpoints.current)();
+    let endpoint_status = endpoints
+        .statuses
+        .read()
+        .iter()
+        .find(|s| Some(&s.endpoint) == current_endpoint.as_ref())
+        .cloned();
+    let endpoint_text = current_endpoint
+        .as_deref()
+        .and_then(|e| url::Url::parse(e).ok())
+        .and_then(|u| u.host_str().map(|h| h.to_string()))
+        .unwrap_or_default();
+    let endpoint_title = match &endpoint_status {
+        Some(status) => format!(
+            "Slot lag: {}, latency: {}",
+            status.slot_lag,
+            status
+                .latency
+                .map_or("-".to_string(), |l| format!("{}ms", l.as_millis()))
+        ),
+        None => String::new(),
+    };
+    let endpoint_class = if endpoint_status.map_or(true, |s| s.is_available()) {
+        "h-1.5 w-1.5 rounded-full bg-green-400"
+    } else {
+        "h-1.5 w-1.5 rounded-full bg-red-400"
+    };
 
     rsx! {
         footer {
@@ -108,6 +119,20 @@ pub fn Footer() -> Element {
             }
             div {
                 class: "flex",
+                div {
+                    class: "text-white/50 hover:text-white flex-1 flex flex-row",
+                    title: "{endpoint_title}",
+                    span {
+                        class: "flex items-center space-x-2 px-1",
+                        span {
+                            class: "{endpoint_class}"
+                        }
+                    }
+                    span {
+                        class: "flex items-center space-x-2 px-1 text-white/50",
+                        {endpoint_text}
+                    }
+                }
                 div {
                     class: "text-white/50 hover:text-white flex-1 flex flex-row",
                     span {
@@ -85,7 +85,7 @@ pub fn SearchResults() -> Element {
 
         if let Ok(address) = Pubkey::from_str(&query) {
             // Fetch the account
-            match connection.client.get_account(&address).await {
+            match connection.rpc().get_account(&address).await {
                 Ok(account) => {
                     vec![SearchResult {
                         title: format!("Go to account {}", address),
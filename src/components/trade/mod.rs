@@ -47,7 +47,7 @@ pub fn TradeComponent(display_chart: Signal<bool>) -> Element {
 
                     let connection = use_connection();
 
-                    match connection.client.get_balance(&owner).await {
+                    match connection.rpc().get_balance(&owner).await {
                         Ok(res) => res,
                         Err(e) => {
                             log::error!("Error fetching balance: {:?}", e);
@@ -79,7 +79,7 @@ pub fn TradeComponent(display_chart: Signal<bool>) -> Element {
                     let connection = use_connection();
 
                     let token_accounts = match connection
-                        .client
+                        .rpc()
                         .get_token_accounts_by_owner(
                             &owner,
                             TokenAccountsFilter::ProgramId(spl_token::ID),
@@ -29,7 +29,7 @@ pub fn TransactionHistoryTable(props: TransactionHistoryTableProps) -> Element {
         let connection = use_connection();
         if address != Pubkey::default() {
             connection
-                .client
+                .rpc()
                 .get_signatures_for_address_with_config(
                     &address,
                     GetConfirmedSignaturesForAddress2Config {
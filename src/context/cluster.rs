@@ -64,6 +64,31 @@ impl Cluster {
         }
     }
 
+    /// The RPC endpoints of the cluster, in order of preference, to fail over between.
+    pub fn endpoints(&self) -> Vec<String> {
+        match self {
+            Self::Mainnet => vec![
+                self.url(),
+                crate::DEFAULT_RPC_URL.to_string(),
+                "https://api.mainnet-beta.solana.com".to_string(),
+            ],
+            Self::Devnet => vec![self.url(), "https://api.devnet.solana.com".to_string()],
+            Self::Custom(url) => vec![url.to_string()],
+        }
+    }
+
+    /// The cluster as named by wallets, e.g. in deep links, `None` for a custom cluster that is
+    /// not a public one.
+    pub fn wallet_cluster(&self) -> Option<&'static str> {
+        match self {
+            Self::Mainnet => Some("mainnet-beta"),
+            Self::Devnet => Some("devnet"),
+            Self::Custom(url) if url.contains("devnet") => Some("devnet"),
+            Self::Custom(url) if url.contains("testnet") => Some("testnet"),
+            Self::Custom(_) => None,
+        }
+    }
+
     pub fn to_solana_explorer_cluster(&self) -> String {
         match self {
             Self::Mainnet => String::new(),
@@ -13,7 +13,8 @@ mod utils;
 use context::*;
 use dioxus::prelude::*;
 use dioxus_router::prelude::*;
-use solana_wallet_adapter::{Wallet, WALLETS};
+use solana_wallet_adapter::{deeplink::DeepLinkCluster, WalletRegistry};
+use solana_wallet_adapter_base::{CacheConfig, ConnectionConfig, ResponseCache};
 use solana_wallet_adapter_dioxus::{use_local_storage, ConnectionProvider, WalletProvider};
 use wasm_logger;
 
@@ -26,21 +27,57 @@ fn main() {
 
 pub const DEFAULT_RPC_URL: &'static str = "https://mainnet-beta.solana.com";
 
+/// The RPC endpoints, the configured one first and the others of its cluster to fail over to.
+///
+/// An endpoint that is not one of the cluster's has no failover, as the others may serve another
+/// cluster.
+fn rpc_endpoints(rpc_url: &str, cluster: &Cluster) -> Vec<String> {
+    let mut endpoints = vec![rpc_url.to_string()];
+    let cluster_endpoints = cluster.endpoints();
+    if cluster_endpoints.iter().any(|url| url == rpc_url) {
+        endpoints.extend(cluster_endpoints.into_iter().filter(|url| url != rpc_url));
+    }
+    endpoints
+}
+
 #[component]
 fn App() -> Element {
     log::info!("App");
-    let wallets = WALLETS.to_vec();
+    let cluster = use_cluster();
+    // Wallet apps connect to the active cluster, as long as it is a public one
+    let deep_link_cluster = use_hook(|| DeepLinkCluster::new(cluster.peek().wallet_cluster()));
+    let registry = use_hook(|| {
+        // List web wallets next to the extensions, and fall back to wallet apps on mobile
+        let app_url = gloo_utils::window().location().origin().unwrap_or_default();
+        WalletRegistry::with_builtin()
+            .with_popup_fallbacks()
+            .with_deep_links(&app_url, &deep_link_cluster)
+    });
+    use_effect(move || deep_link_cluster.set(cluster().wallet_cluster()));
     let endpoint =
         use_local_storage::<String>("rpc_url".to_string(), Some(DEFAULT_RPC_URL.to_string()));
+    let mut endpoints = use_signal(|| rpc_endpoints(&endpoint.peek(), &cluster.peek()));
+    use_effect(move || {
+        let rpc_endpoints = rpc_endpoints(&endpoint(), &cluster());
+        if *endpoints.peek() != rpc_endpoints {
+            endpoints.set(rpc_endpoints);
+        }
+    });
+    let connection_config = use_signal(ConnectionConfig::default);
+    let rpc_cache = use_hook(|| {
+        ResponseCache::new(CacheConfig::default()).with_storage(storage::RpcCacheStorage)
+    });
 
     rsx! {
         div {
             class: "flex flex-col min-h-screen justify-between bg-[#13283d]",
             ConnectionProvider {
-                endpoint,
+                endpoints,
+                config: connection_config,
+                cache: rpc_cache,
                 WalletProvider {
-                    wallets,
-                    auto_connect: false,
+                    registry,
+                    auto_connect: true,
                     local_storage_key: None,
                     Router::<Route> { },
                 }
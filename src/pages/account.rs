@@ -4,7 +4,7 @@ use anchor_lang::prelude::Pubkey;
 use dioxus::prelude::*;
 use dioxus_router::prelude::*;
 use solana_client_wasm::{solana_sdk::account::Account, WasmClient};
-use solana_wallet_adapter_dioxus::use_connection;
+use solana_wallet_adapter_dioxus::{use_account_subscription, use_connection};
 
 use crate::{
     components::{account_info_table::AccountInfoTable, TransactionHistoryTable},
@@ -16,11 +16,16 @@ use crate::{
 pub fn AccountPage(address: Pubkey, query_params: QuerySegments) -> Element {
     let cluster = use_context::<Signal<Cluster>>();
 
+    // Follows the address prop, which changes without remounting when navigating between accounts
+    let subscribed_address = use_memo(use_reactive((&address,), |(address,)| Some(address)));
+
     let account = use_resource(move || async move {
         let cluster = cluster();
-        let address = address.clone();
+        let Some(address) = subscribed_address() else {
+            return None;
+        };
         let connection = use_connection();
-        match connection.client.get_account(&address).await {
+        match connection.rpc().get_account(&address).await {
             Ok(maybe_account) => Some(maybe_account),
             Err(e) => {
                 // TODO Handle error
@@ -30,7 +35,14 @@ pub fn AccountPage(address: Pubkey, query_params: QuerySegments) -> Element {
         }
     });
 
-    log::info!("Account: {:?}", account.value());
+    // Kept up to date once the account changes
+    let account_update = use_account_subscription(subscribed_address);
+    let account = match account_update() {
+        Some(update) => Some(Some(update)),
+        None => account(),
+    };
+
+    log::info!("Account: {:?}", account);
 
     rsx! {
         div {
@@ -45,7 +57,7 @@ pub fn AccountPage(address: Pubkey, query_params: QuerySegments) -> Element {
                             class: "text-2xl font-mono mb-6",
                             "Account"
                         }
-                        if let Some(account) = account() {
+                        if let Some(account) = account {
                             if let Some(account) = account {
                                 AccountInfoTable {
                                     account: account.clone(),
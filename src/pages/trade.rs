@@ -28,7 +28,7 @@ pub fn Trade(from: Pubkey, to: Pubkey, amount: f64, query_params: QuerySegments)
             if let Some(address) = pubkey {
                 log::info!("Fetching account: {}", address);
                 let connection = use_connection();
-                match connection.client.get_account(&address).await {
+                match connection.rpc().get_account(&address).await {
                     Ok(maybe_account) => Some(maybe_account),
                     Err(e) => {
                         // TODO Handle error
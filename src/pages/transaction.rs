@@ -29,7 +29,7 @@ pub fn TransactionPage(signature: Signature, query_params: QuerySegments) -> Ele
         if signature != Signature::default() {
             let connection = use_connection();
             let t = connection
-                .client
+                .rpc()
                 .get_transaction_with_config(
                     &signature,
                     RpcTransactionConfig {
@@ -1,6 +1,8 @@
+pub mod rpc_cache;
 pub mod user;
 
 use serde::de::DeserializeOwned;
+pub use rpc_cache::*;
 pub use user::*;
 
 use gloo_storage::Storage;
@@ -0,0 +1,24 @@
+use solana_wallet_adapter_base::{CacheStorage, CachedResponse};
+
+const KEY: &'static str = "rpc_cache";
+
+/// Persists the immutable RPC responses, e.g. confirmed transactions, in the local storage.
+pub struct RpcCacheStorage;
+
+impl CacheStorage for RpcCacheStorage {
+    fn load(&self) -> Vec<CachedResponse> {
+        match crate::storage::get::<Vec<CachedResponse>>(KEY) {
+            Ok(responses) => responses,
+            Err(e) => {
+                log::warn!("No RPC cache loaded from local storage: {:?}", e);
+                vec![]
+            }
+        }
+    }
+
+    fn save(&self, responses: &[CachedResponse]) {
+        if let Err(e) = crate::storage::set(KEY, responses) {
+            log::error!("Error updating RPC cache local storage: {:?}", e);
+        }
+    }
+}